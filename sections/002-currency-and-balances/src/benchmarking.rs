@@ -5,7 +5,9 @@ use super::*;
 #[allow(unused)]
 use crate::Pallet as Template;
 use frame_benchmarking::v2::*;
+use frame_support::{sp_std::vec, BoundedVec};
 use frame_system::RawOrigin;
+use sp_runtime::traits::Saturating;
 
 // TODO!
 #[benchmarks]
@@ -22,5 +24,24 @@ mod benchmarks {
         // assert_eq!(Something::<T>::get(), Some(value));
     }
 
+    #[benchmark]
+    fn transfer_with_memo(m: Linear<0, { T::MaxMemoLen::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let receiver: T::AccountId = account("receiver", 0, 0);
+        let amount = T::Balances::minimum_balance().saturating_mul(100u32.into());
+        T::Balances::make_free_balance_be(&caller, amount.saturating_mul(2u32.into()));
+        let memo = BoundedVec::try_from(vec![b'M'; m as usize]).unwrap();
+
+        #[extrinsic_call]
+        transfer_with_memo(
+            RawOrigin::Signed(caller),
+            T::Lookup::unlookup(receiver.clone()),
+            amount,
+            memo,
+        );
+
+        assert_eq!(T::Balances::free_balance(&receiver), amount);
+    }
+
     impl_benchmark_test_suite!(Template, crate::mock::new_test_ext(), crate::mock::Test);
 }
@@ -13,6 +13,7 @@ mod tests;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod migrations;
 pub mod weights;
 use frame_support::traits::{Currency, ExistenceRequirement};
 use sp_runtime::traits::{StaticLookup, Zero};
@@ -28,10 +29,13 @@ pub type BalanceOf<T> =
 pub mod pallet {
 
     use super::*;
-    use frame_support::pallet_prelude::*;
+    use frame_support::{pallet_prelude::*, sp_std::vec::Vec};
     use frame_system::pallet_prelude::*;
 
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     /// Configure the pallet by specifying the parameters and types on which it depends.
@@ -45,6 +49,10 @@ pub mod pallet {
         // To add this pallet to the Node runtime, we'd have to wire up the Balances Pallet
         // in the runtime/lib.rs.
         type Balances: Currency<Self::AccountId>;
+
+        /// Maximum length of the memo of `transfer_with_memo`, e.g. an invoice number.
+        #[pallet::constant]
+        type MaxMemoLen: Get<u32>;
     }
 
     // Pallets use events to inform users when important changes are made.
@@ -68,6 +76,13 @@ pub mod pallet {
             receiver: T::AccountId,
             amount: BalanceOf<T>,
         },
+        /// The memo is only kept in this event, not in storage.
+        TransferredWithMemo {
+            sender: T::AccountId,
+            receiver: T::AccountId,
+            amount: BalanceOf<T>,
+            memo: BoundedVec<u8, T::MaxMemoLen>,
+        },
     }
 
     // Errors inform users that something went wrong.
@@ -89,6 +104,22 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// The raw key the sweep of zero allowances left off at, while it is running.
+    #[pallet::storage]
+    #[pallet::unbounded]
+    pub type AllowanceSweep<T> = StorageValue<_, Vec<u8>, OptionQuery>;
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_runtime_upgrade() -> Weight {
+            migrations::v1::start::<T>()
+        }
+
+        fn on_idle(_now: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            migrations::v1::sweep::<T>(remaining_weight)
+        }
+    }
+
     // Dispatchable functions allows users to interact with the pallet and invoke state changes.
     // These functions materialize as "extrinsics", which are often compared to transactions.
     // Dispatchable functions must be annotated with a weight and must return a DispatchResult.
@@ -127,7 +158,11 @@ pub mod pallet {
             let owner = ensure_signed(_owner)?;
             let spender = T::Lookup::lookup(_spender)?;
 
-            Allowances::<T>::insert(owner.clone(), spender.clone(), amount);
+            if amount.is_zero() {
+                Allowances::<T>::remove(&owner, &spender);
+            } else {
+                Allowances::<T>::insert(&owner, &spender, amount);
+            }
 
             Self::deposit_event(Event::<T>::Approved {
                 owner,
@@ -151,16 +186,18 @@ pub mod pallet {
             let owner = T::Lookup::lookup(_owner)?;
             let recipient = T::Lookup::lookup(_recipient)?;
 
-            Allowances::<T>::try_mutate(
+            Allowances::<T>::try_mutate_exists(
                 owner.clone(),
                 spender.clone(),
-                |allowance| -> DispatchResult {
-                    ensure!(*allowance > Zero::zero(), Error::<T>::NotApproved);
-                    ensure!(*allowance >= amount, Error::<T>::InsufficientBalance);
+                |maybe_allowance| -> DispatchResult {
+                    let allowance = maybe_allowance.unwrap_or_else(Zero::zero);
+                    ensure!(allowance > Zero::zero(), Error::<T>::NotApproved);
+                    ensure!(allowance >= amount, Error::<T>::InsufficientBalance);
 
                     Self::_transfer(&owner, &recipient, amount)?;
 
-                    *allowance -= amount;
+                    let rest = allowance - amount;
+                    *maybe_allowance = if rest.is_zero() { None } else { Some(rest) };
 
                     Ok(())
                 },
@@ -175,6 +212,30 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// `transfer` with a reference for the receiver to match the payment against.
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::transfer_with_memo(memo.len() as u32))]
+        pub fn transfer_with_memo(
+            _sender: OriginFor<T>,
+            _receiver: AccountIdLookupOf<T>,
+            amount: BalanceOf<T>,
+            memo: BoundedVec<u8, T::MaxMemoLen>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(_sender)?;
+            let receiver = T::Lookup::lookup(_receiver)?;
+
+            Self::_transfer(&sender, &receiver, amount)?;
+
+            Self::deposit_event(Event::<T>::TransferredWithMemo {
+                sender,
+                receiver,
+                amount,
+                memo,
+            });
+
+            Ok(())
+        }
     }
 
     impl<T: Config> Pallet<T> {
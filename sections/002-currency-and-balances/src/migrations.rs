@@ -0,0 +1,82 @@
+//! Storage migrations.
+
+pub mod v1 {
+    //! Allowances that reach zero are removed instead of stored. Rows written as
+    //! zero before that are swept from `on_idle`, at most [`SWEEP_BATCH`] rows per
+    //! block.
+    use crate::*;
+    use frame_support::{
+        pallet_prelude::*,
+        storage::StoragePrefixedMap,
+        traits::{GetStorageVersion, StorageVersion},
+    };
+
+    /// Most rows looked at in one block.
+    pub const SWEEP_BATCH: u32 = 100;
+
+    /// Queue the sweep when upgrading from v0.
+    pub fn start<T: Config>() -> Weight {
+        let db = T::DbWeight::get();
+
+        if Pallet::<T>::on_chain_storage_version() >= 1 {
+            return db.reads(1);
+        }
+
+        // iterating from the map prefix visits every row
+        AllowanceSweep::<T>::put(Allowances::<T>::final_prefix().to_vec());
+        StorageVersion::new(1).put::<Pallet<T>>();
+
+        db.reads_writes(1, 2)
+    }
+
+    /// Sweep the next batch, if there is one and it fits in `limit`. Returns the
+    /// weight used.
+    pub fn sweep<T: Config>(limit: Weight) -> Weight {
+        let db = T::DbWeight::get();
+        // reading and writing back the cursor
+        let base = db.reads_writes(1, 1);
+        let per_row = db.reads_writes(1, 1);
+
+        if !limit.all_gte(base.saturating_add(per_row)) {
+            return Weight::zero();
+        }
+
+        let start = match AllowanceSweep::<T>::get() {
+            Some(start) => start,
+            None => return db.reads(1),
+        };
+
+        let rows = limit
+            .saturating_sub(base)
+            .checked_div_per_component(&per_row)
+            .unwrap_or(u64::MAX)
+            .min(SWEEP_BATCH as u64);
+
+        let mut iter = Allowances::<T>::iter_from(start);
+        let mut looked_at = 0;
+        let mut removed = 0;
+
+        // the iterator finds each next key from the last one it returned, so
+        // removing that row as we go is fine
+        while looked_at < rows {
+            match iter.next() {
+                Some((owner, spender, allowance)) => {
+                    looked_at += 1;
+                    if allowance.is_zero() {
+                        Allowances::<T>::remove(owner, spender);
+                        removed += 1;
+                    }
+                },
+                None => break,
+            }
+        }
+
+        if looked_at < rows {
+            AllowanceSweep::<T>::kill();
+        } else {
+            AllowanceSweep::<T>::put(iter.last_raw_key().to_vec());
+        }
+
+        base.saturating_add(db.reads_writes(looked_at, removed))
+    }
+}
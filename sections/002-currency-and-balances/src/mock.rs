@@ -53,6 +53,7 @@ impl pallet_currencydemo::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type WeightInfo = ();
     type Balances = Balances;
+    type MaxMemoLen = ConstU32<32>;
 }
 
 // Config Balances Pallet
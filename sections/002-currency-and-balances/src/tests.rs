@@ -1,6 +1,6 @@
 use std::time::SystemTime;
 
-use crate::{mock::*, Error, Event};
+use crate::{mock::*, Allowances, Error, Event};
 use frame_support::{assert_err, assert_noop, assert_ok};
 // import traits::Currency so our ::make_free_balance_be() function will be available
 use frame_support::traits::Currency;
@@ -157,6 +157,72 @@ pub mod approvals {
             // after
             let approved_amount = TemplateModule::allowances(alice.clone(), bob.clone());
             assert_eq!(approved_amount, 0);
+            // and the spent allowance is gone from storage
+            assert_eq!(Allowances::<Test>::iter().count(), 0);
+        });
+    }
+
+    #[test]
+    fn approving_zero_removes_the_allowance() {
+        new_test_ext().execute_with(|| {
+            let alice = account(1);
+            let bob = account(2);
+
+            assert_ok!(TemplateModule::approve(
+                RuntimeOrigin::signed(alice.clone()),
+                bob.clone(),
+                10
+            ));
+            assert!(Allowances::<Test>::contains_key(&alice, &bob));
+
+            assert_ok!(TemplateModule::approve(
+                RuntimeOrigin::signed(alice.clone()),
+                bob.clone(),
+                0
+            ));
+            assert!(!Allowances::<Test>::contains_key(&alice, &bob));
+        });
+    }
+}
+
+pub mod migrations {
+    use super::*;
+    use crate::{migrations::v1::SWEEP_BATCH, AllowanceSweep};
+    use frame_support::{
+        traits::{GetStorageVersion, Hooks, StorageVersion},
+        weights::Weight,
+    };
+
+    #[test]
+    fn zero_allowances_are_swept_in_batches() {
+        new_test_ext().execute_with(|| {
+            let alice = account(1);
+
+            // rows left behind by the old code
+            for id in 10..10 + SWEEP_BATCH as u8 {
+                Allowances::<Test>::insert(&alice, account(id), 0);
+            }
+            Allowances::<Test>::insert(&alice, account(2), 5);
+            StorageVersion::new(0).put::<TemplateModule>();
+
+            TemplateModule::on_runtime_upgrade();
+            assert_eq!(TemplateModule::on_chain_storage_version(), 1);
+
+            // one batch doesn't get through all of them
+            TemplateModule::on_idle(1, Weight::MAX);
+            assert!(Allowances::<Test>::iter().count() > 1);
+            assert!(AllowanceSweep::<Test>::get().is_some());
+
+            TemplateModule::on_idle(2, Weight::MAX);
+            assert_eq!(AllowanceSweep::<Test>::get(), None);
+            assert_eq!(
+                Allowances::<Test>::iter().collect::<Vec<_>>(),
+                vec![(alice, account(2), 5)]
+            );
+
+            // the sweep only runs once
+            TemplateModule::on_runtime_upgrade();
+            assert_eq!(AllowanceSweep::<Test>::get(), None);
         });
     }
 }
@@ -204,3 +270,61 @@ fn can_transfer_currency() {
         );
     });
 }
+
+pub mod memos {
+    use super::*;
+    use frame_support::{traits::ConstU32, BoundedVec};
+
+    fn memo(bytes: &[u8]) -> BoundedVec<u8, ConstU32<32>> {
+        BoundedVec::try_from(bytes.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn transfers_and_emits_the_memo() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+
+            let alice = account(1);
+            let bob = account(2);
+            Balances::make_free_balance_be(&alice, 100);
+
+            assert_ok!(TemplateModule::transfer_with_memo(
+                RuntimeOrigin::signed(alice.clone()),
+                bob.clone(),
+                50,
+                memo(b"INV-2023-0042")
+            ));
+
+            assert_eq!(Balances::free_balance(&alice), 50);
+            assert_eq!(Balances::free_balance(&bob), 50);
+            System::assert_last_event(
+                Event::TransferredWithMemo {
+                    sender: alice,
+                    receiver: bob,
+                    amount: 50,
+                    memo: memo(b"INV-2023-0042"),
+                }
+                .into(),
+            );
+        });
+    }
+
+    #[test]
+    fn fails_like_transfer() {
+        new_test_ext().execute_with(|| {
+            let alice = account(1);
+            let bob = account(2);
+            Balances::make_free_balance_be(&alice, 100);
+
+            assert_noop!(
+                TemplateModule::transfer_with_memo(
+                    RuntimeOrigin::signed(alice),
+                    bob,
+                    101,
+                    memo(b"INV-1")
+                ),
+                Error::<Test>::InsufficientBalance
+            );
+        });
+    }
+}
@@ -36,6 +36,7 @@ pub trait WeightInfo {
 	fn transfer() -> Weight;
 	fn transfer_from() -> Weight;
 	fn approve() -> Weight;
+	fn transfer_with_memo(m: u32, ) -> Weight;
 }
 
 /// Weights for pallet_currencydemo using the Substrate node and recommended hardware.
@@ -57,6 +58,15 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		Weight::from_parts(9_000_000, 0)
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+
+	/// The range of component `m` is `[0, 32]`.
+	fn transfer_with_memo(m: u32, ) -> Weight {
+		Weight::from_parts(9_000_000, 0)
+			// Standard Error: 1_000
+			.saturating_add(Weight::from_parts(2_000, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -77,4 +87,13 @@ impl WeightInfo for () {
 		Weight::from_parts(9_000_000, 0)
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+
+	/// The range of component `m` is `[0, 32]`.
+	fn transfer_with_memo(m: u32, ) -> Weight {
+		Weight::from_parts(9_000_000, 0)
+			// Standard Error: 1_000
+			.saturating_add(Weight::from_parts(2_000, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
 }
\ No newline at end of file
@@ -5,30 +5,919 @@ use super::*;
 #[allow(unused)]
 use crate::Pallet as Template;
 use frame_benchmarking::v2::*;
-use frame_system::RawOrigin;
+use frame_support::{
+    traits::{EnsureOrigin, EnsureOriginWithArg, Get, Hooks},
+    BoundedVec,
+};
+use frame_system::{pallet_prelude::BlockNumberFor, RawOrigin};
+use sp_runtime::traits::Hash;
+use sp_std::{vec, vec::Vec};
+
+const SEED: u32 = 0;
+
+fn create_token<T: Config>(owner: &T::AccountId) -> T::TokenId {
+    let name = BoundedVec::try_from(vec![b'N'; T::MaxTokenNameLen::get() as usize]).unwrap();
+    let symbol = BoundedVec::try_from(vec![b'S'; T::MaxTokenSymbolLen::get() as usize]).unwrap();
+
+    // straight to `do_mint` so this works whatever `CreateOrigin` allows
+    Template::<T>::do_mint(owner.clone(), name, symbol, 1_000_000u32.into(), None).unwrap()
+}
+
+// Worst case for `_transfer`: both sides delegate their votes and a snapshot
+// is pending, so checkpoints and snapshots are written for both accounts, and
+// the runtime's `TransferFilter` and `OnTransfer` do the most work they can.
+fn setup_transfer<T: Config>(token_id: T::TokenId, from: &T::AccountId, to: &T::AccountId) {
+    for who in [from, to] {
+        assert!(Template::<T>::delegate(
+            RawOrigin::Signed(who.clone()).into(),
+            token_id,
+            T::Lookup::unlookup(who.clone())
+        )
+        .is_ok());
+    }
+
+    assert!(Template::<T>::do_snapshot(token_id).is_ok());
+    T::BenchmarkHelper::setup_transfer_hooks(&token_id, from, to);
+}
+
+// `n` distinct accounts named `name`, as the batch calls take them.
+fn lookups<T: Config>(
+    name: &'static str,
+    n: u32,
+) -> BoundedVec<AccountIdLookupOf<T>, T::MaxAllowlistBatch> {
+    let accounts: Vec<_> = (0..n).map(|i| T::Lookup::unlookup(account(name, i, SEED))).collect();
+
+    BoundedVec::try_from(accounts).unwrap()
+}
 
 #[benchmarks]
 mod benchmarks {
     use super::*;
 
     #[benchmark]
-    fn do_something() {
-        let value = 100u32.into();
+    fn mint() -> Result<(), BenchmarkError> {
+        let token_id = T::TokenId::one();
+        let origin = T::CreateOrigin::try_successful_origin(&token_id)
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let owner = T::CreateOrigin::ensure_origin(origin.clone(), &token_id)
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let name = BoundedVec::try_from(vec![b'N'; T::MaxTokenNameLen::get() as usize]).unwrap();
+        let symbol =
+            BoundedVec::try_from(vec![b'S'; T::MaxTokenSymbolLen::get() as usize]).unwrap();
+
+        #[extrinsic_call]
+        mint(
+            origin as T::RuntimeOrigin,
+            name,
+            symbol,
+            1_000_000u32.into(),
+            None,
+        );
+
+        assert_eq!(BalanceOf::<T>::get(token_id, &owner), 1_000_000u32.into());
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn transfer() {
+        let caller: T::AccountId = whitelisted_caller();
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let token_id = create_token::<T>(&caller);
+        let amount: T::TokenBalance = 100u32.into();
+        setup_transfer::<T>(token_id, &caller, &recipient);
+
+        #[extrinsic_call]
+        transfer(
+            RawOrigin::Signed(caller),
+            T::Lookup::unlookup(recipient.clone()),
+            token_id,
+            amount,
+        );
+
+        assert_eq!(BalanceOf::<T>::get(token_id, &recipient), amount);
+    }
+
+    #[benchmark]
+    fn approve() {
+        let caller: T::AccountId = whitelisted_caller();
+        let spender: T::AccountId = account("spender", 0, SEED);
+        let token_id = create_token::<T>(&caller);
+        let amount: T::TokenBalance = 100u32.into();
+
+        #[extrinsic_call]
+        approve(
+            RawOrigin::Signed(caller.clone()),
+            T::Lookup::unlookup(spender.clone()),
+            token_id,
+            amount,
+        );
+
+        assert_eq!(Allowance::<T>::get((token_id, caller, spender)), amount);
+    }
+
+    #[benchmark]
+    fn transfer_from() {
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let caller: T::AccountId = whitelisted_caller();
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let token_id = create_token::<T>(&owner);
+        let amount: T::TokenBalance = 100u32.into();
+        setup_transfer::<T>(token_id, &owner, &recipient);
+        Allowance::<T>::insert((token_id, owner.clone(), caller.clone()), amount);
+
+        #[extrinsic_call]
+        transfer_from(
+            RawOrigin::Signed(caller),
+            token_id,
+            T::Lookup::unlookup(owner),
+            T::Lookup::unlookup(recipient.clone()),
+            amount,
+        );
+
+        assert_eq!(BalanceOf::<T>::get(token_id, &recipient), amount);
+    }
+
+    // Every processed schedule emits, so each one pays for a full `do_issue`.
+    #[benchmark]
+    fn on_initialize_emissions(n: Linear<1, { T::MaxEmissionsPerBlock::get() }>) {
+        let owner: T::AccountId = whitelisted_caller();
+        let token_id = create_token::<T>(&owner);
+
+        for i in 0..n {
+            let beneficiary: T::AccountId = account("beneficiary", i, SEED);
+
+            assert!(Template::<T>::create_emission(
+                RawOrigin::Signed(owner.clone()).into(),
+                token_id,
+                T::Lookup::unlookup(beneficiary),
+                EmissionCurve::Linear {
+                    per_block: 1u32.into()
+                },
+                Zero::zero(),
+            )
+            .is_ok());
+        }
+
+        let now: BlockNumberFor<T> = 10u32.into();
+        frame_system::Pallet::<T>::set_block_number(now);
+
+        #[block]
+        {
+            Template::<T>::on_initialize(now);
+        }
+
+        assert_eq!(EmissionSchedules::<T>::get(0).unwrap().last_emitted, now);
+    }
+
+    // Worst case: the caller spends an allowance for every token.
+    #[benchmark]
+    fn safe_batch_transfer_from(n: Linear<1, { T::MaxBatchTransfers::get() }>) {
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let caller: T::AccountId = whitelisted_caller();
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let amount: T::TokenBalance = 100u32.into();
+        let mut transfers = BoundedVec::<_, T::MaxBatchTransfers>::default();
+
+        for _ in 0..n {
+            let token_id = create_token::<T>(&owner);
+            setup_transfer::<T>(token_id, &owner, &recipient);
+            Allowance::<T>::insert((token_id, owner.clone(), caller.clone()), amount);
+            assert!(transfers.try_push((token_id, amount)).is_ok());
+        }
+
+        let last = transfers.last().map(|(token_id, _)| *token_id).unwrap();
+
+        #[extrinsic_call]
+        safe_batch_transfer_from(
+            RawOrigin::Signed(caller),
+            T::Lookup::unlookup(owner),
+            T::Lookup::unlookup(recipient.clone()),
+            transfers,
+        );
+
+        assert_eq!(BalanceOf::<T>::get(last, &recipient), amount);
+    }
+
+    // The memo only goes into the event, so `m` should barely register.
+    #[benchmark]
+    fn transfer_with_memo(m: Linear<0, { T::MaxMemoLen::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let token_id = create_token::<T>(&caller);
+        let amount: T::TokenBalance = 100u32.into();
+        let memo = BoundedVec::try_from(vec![b'M'; m as usize]).unwrap();
+        setup_transfer::<T>(token_id, &caller, &recipient);
+
+        #[extrinsic_call]
+        transfer_with_memo(
+            RawOrigin::Signed(caller),
+            T::Lookup::unlookup(recipient.clone()),
+            token_id,
+            amount,
+            memo,
+        );
+
+        assert_eq!(BalanceOf::<T>::get(token_id, &recipient), amount);
+    }
+
+    // Worst case: the target already has every schedule but one, and all of them
+    // are walked again to update the lock.
+    #[benchmark]
+    fn vested_transfer() {
+        let caller: T::AccountId = whitelisted_caller();
+        let target: T::AccountId = account("target", 0, SEED);
+        let token_id = create_token::<T>(&caller);
+        let locked = T::MinVestedTransfer::get().max(100u32.into());
+        let schedule = VestingSchedule::new(locked, 1u32.into(), 1u32.into(), 10u32.into());
+
+        for _ in 1..T::MaxVestingSchedules::get() {
+            assert!(Template::<T>::vested_transfer(
+                RawOrigin::Signed(caller.clone()).into(),
+                token_id,
+                T::Lookup::unlookup(target.clone()),
+                schedule
+            )
+            .is_ok());
+        }
+
+        #[extrinsic_call]
+        vested_transfer(
+            RawOrigin::Signed(caller),
+            token_id,
+            T::Lookup::unlookup(target.clone()),
+            schedule,
+        );
+
+        assert_eq!(
+            Vesting::<T>::get(token_id, &target).len() as u32,
+            T::MaxVestingSchedules::get()
+        );
+    }
+
+    // Worst case: every schedule has fully vested, so all of them are dropped
+    // together with the lock.
+    #[benchmark]
+    fn vest() {
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let caller: T::AccountId = whitelisted_caller();
+        let token_id = create_token::<T>(&owner);
+        let locked = T::MinVestedTransfer::get().max(100u32.into());
+        let schedule = VestingSchedule::new(locked, 1u32.into(), 1u32.into(), 10u32.into());
+
+        for _ in 0..T::MaxVestingSchedules::get() {
+            assert!(Template::<T>::vested_transfer(
+                RawOrigin::Signed(owner.clone()).into(),
+                token_id,
+                T::Lookup::unlookup(caller.clone()),
+                schedule
+            )
+            .is_ok());
+        }
+
+        frame_system::Pallet::<T>::set_block_number(1_000u32.into());
+
+        #[extrinsic_call]
+        vest(RawOrigin::Signed(caller.clone()), token_id);
+
+        assert!(!Vesting::<T>::contains_key(token_id, &caller));
+    }
+
+    #[benchmark]
+    fn snapshot() {
+        let caller: T::AccountId = whitelisted_caller();
+        let token_id = create_token::<T>(&caller);
+
+        #[extrinsic_call]
+        snapshot(RawOrigin::Signed(caller), token_id);
+
+        assert_eq!(CurrentSnapshotId::<T>::get(token_id), 1);
+    }
+
+    // Worst case: the votes move away from a previous delegatee in a later block,
+    // so both delegatees get a new checkpoint.
+    #[benchmark]
+    fn delegate() {
+        let caller: T::AccountId = whitelisted_caller();
+        let previous: T::AccountId = account("delegatee", 0, SEED);
+        let delegatee: T::AccountId = account("delegatee", 1, SEED);
+        let token_id = create_token::<T>(&caller);
+
+        assert!(Template::<T>::delegate(
+            RawOrigin::Signed(caller.clone()).into(),
+            token_id,
+            T::Lookup::unlookup(previous)
+        )
+        .is_ok());
+        frame_system::Pallet::<T>::set_block_number(2u32.into());
+
+        #[extrinsic_call]
+        delegate(
+            RawOrigin::Signed(caller.clone()),
+            token_id,
+            T::Lookup::unlookup(delegatee.clone()),
+        );
+
+        assert_eq!(Delegates::<T>::get(token_id, &caller), Some(delegatee));
+    }
+
+    #[benchmark]
+    fn set_permissioned() {
+        let caller: T::AccountId = whitelisted_caller();
+        let token_id = create_token::<T>(&caller);
+
+        #[extrinsic_call]
+        set_permissioned(RawOrigin::Signed(caller), token_id, true);
+
+        assert!(Tokens::<T>::get(token_id).unwrap().permissioned);
+    }
+
+    #[benchmark]
+    fn add_holders(n: Linear<1, { T::MaxAllowlistBatch::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let token_id = create_token::<T>(&caller);
+
+        #[extrinsic_call]
+        add_holders(RawOrigin::Signed(caller), token_id, lookups::<T>("holder", n));
+
+        let last: T::AccountId = account("holder", n - 1, SEED);
+        assert!(Holders::<T>::contains_key(token_id, &last));
+    }
+
+    #[benchmark]
+    fn remove_holders(n: Linear<1, { T::MaxAllowlistBatch::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let token_id = create_token::<T>(&caller);
+
+        assert!(Template::<T>::add_holders(
+            RawOrigin::Signed(caller.clone()).into(),
+            token_id,
+            lookups::<T>("holder", n)
+        )
+        .is_ok());
+
+        #[extrinsic_call]
+        remove_holders(RawOrigin::Signed(caller), token_id, lookups::<T>("holder", n));
+
+        let last: T::AccountId = account("holder", n - 1, SEED);
+        assert!(!Holders::<T>::contains_key(token_id, &last));
+    }
+
+    // Worst case: a permissioned token with a pending snapshot, issued to a
+    // beneficiary who delegates their votes.
+    #[benchmark]
+    fn issue() {
+        let caller: T::AccountId = whitelisted_caller();
+        let beneficiary: T::AccountId = account("beneficiary", 0, SEED);
+        let token_id = create_token::<T>(&caller);
+        let amount: T::TokenBalance = 100u32.into();
+        setup_transfer::<T>(token_id, &caller, &beneficiary);
+
+        assert!(Template::<T>::set_permissioned(
+            RawOrigin::Signed(caller.clone()).into(),
+            token_id,
+            true
+        )
+        .is_ok());
+        Holders::<T>::insert(token_id, &beneficiary, ());
+
+        #[extrinsic_call]
+        issue(
+            RawOrigin::Signed(caller),
+            token_id,
+            T::Lookup::unlookup(beneficiary.clone()),
+            amount,
+        );
+
+        assert_eq!(BalanceOf::<T>::get(token_id, &beneficiary), amount);
+    }
+
+    #[benchmark]
+    fn set_max_supply() {
+        let caller: T::AccountId = whitelisted_caller();
+        let token_id = create_token::<T>(&caller);
+        let max_supply: T::TokenBalance = 2_000_000u32.into();
+
+        #[extrinsic_call]
+        set_max_supply(RawOrigin::Signed(caller), token_id, max_supply);
+
+        assert_eq!(Tokens::<T>::get(token_id).unwrap().max_supply, Some(max_supply));
+    }
+
+    // The queue is read and written whole, so it is filled up to the last slot.
+    #[benchmark]
+    fn create_emission() {
+        let caller: T::AccountId = whitelisted_caller();
+        let beneficiary: T::AccountId = account("beneficiary", 0, SEED);
+        let token_id = create_token::<T>(&caller);
+        let queue: Vec<EmissionId> = (1..T::MaxEmissionSchedules::get()).collect();
+        EmissionQueue::<T>::put(BoundedVec::try_from(queue).unwrap());
+        NextEmissionId::<T>::put(T::MaxEmissionSchedules::get());
+
+        #[extrinsic_call]
+        create_emission(
+            RawOrigin::Signed(caller),
+            token_id,
+            T::Lookup::unlookup(beneficiary),
+            EmissionCurve::Linear {
+                per_block: 1u32.into(),
+            },
+            Zero::zero(),
+        );
+
+        assert_eq!(
+            EmissionQueue::<T>::get().len() as u32,
+            T::MaxEmissionSchedules::get()
+        );
+    }
+
+    // Worst case: the schedule is found at the end of a full queue.
+    #[benchmark]
+    fn cancel_emission() {
+        let caller: T::AccountId = whitelisted_caller();
+        let token_id = create_token::<T>(&caller);
+        let id = T::MaxEmissionSchedules::get();
+        let queue: Vec<EmissionId> = (1..id).collect();
+        EmissionQueue::<T>::put(BoundedVec::try_from(queue).unwrap());
+        NextEmissionId::<T>::put(id);
+
+        assert!(Template::<T>::create_emission(
+            RawOrigin::Signed(caller.clone()).into(),
+            token_id,
+            T::Lookup::unlookup(caller.clone()),
+            EmissionCurve::Linear {
+                per_block: 1u32.into()
+            },
+            Zero::zero(),
+        )
+        .is_ok());
+
+        #[extrinsic_call]
+        cancel_emission(RawOrigin::Signed(caller), id);
+
+        assert!(EmissionSchedules::<T>::get(id).is_none());
+    }
+
+    #[benchmark]
+    fn create_airdrop() {
+        let caller: T::AccountId = whitelisted_caller();
+        let token_id = create_token::<T>(&caller);
+        let total: T::TokenBalance = 1_000u32.into();
+        setup_transfer::<T>(token_id, &caller, &Template::<T>::account_id());
+
+        #[extrinsic_call]
+        create_airdrop(
+            RawOrigin::Signed(caller),
+            token_id,
+            T::Hash::default(),
+            total,
+            100u32.into(),
+        );
+
+        assert_eq!(BalanceOf::<T>::get(token_id, &Template::<T>::account_id()), total);
+    }
+
+    // Worst case: a proof of `p` levels, and a bitmap word that is already in use.
+    #[benchmark]
+    fn claim(p: Linear<0, { T::MaxProofLength::get() }>) {
+        let creator: T::AccountId = account("creator", 0, SEED);
+        let caller: T::AccountId = whitelisted_caller();
+        let token_id = create_token::<T>(&creator);
+        let amount: T::TokenBalance = 100u32.into();
+        let index = 1u32;
+
+        let mut root = T::Hashing::hash_of(&(index, &caller, amount));
+        let mut proof = BoundedVec::<T::Hash, T::MaxProofLength>::default();
+        for i in 0..p {
+            let sibling = T::Hashing::hash_of(&i);
+            root = if root <= sibling {
+                T::Hashing::hash_of(&(root, sibling))
+            } else {
+                T::Hashing::hash_of(&(sibling, root))
+            };
+            assert!(proof.try_push(sibling).is_ok());
+        }
+
+        assert!(Template::<T>::create_airdrop(
+            RawOrigin::Signed(creator).into(),
+            token_id,
+            root,
+            1_000u32.into(),
+            100u32.into()
+        )
+        .is_ok());
+        // leaf 0 shares its word with `index`
+        ClaimedBitmap::<T>::insert(0, 0, 1);
+        setup_transfer::<T>(token_id, &Template::<T>::account_id(), &caller);
+
+        #[extrinsic_call]
+        claim(RawOrigin::Signed(caller.clone()), 0, index, amount, proof);
+
+        assert_eq!(BalanceOf::<T>::get(token_id, &caller), amount);
+    }
+
+    // `w` bitmap words to remove, however many leaves were claimed in each.
+    #[benchmark]
+    fn reclaim(w: Linear<0, 1_000>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let token_id = create_token::<T>(&caller);
+        setup_transfer::<T>(token_id, &Template::<T>::account_id(), &caller);
+
+        assert!(Template::<T>::create_airdrop(
+            RawOrigin::Signed(caller.clone()).into(),
+            token_id,
+            T::Hash::default(),
+            1_000u32.into(),
+            100u32.into()
+        )
+        .is_ok());
+        for word in 0..w {
+            ClaimedBitmap::<T>::insert(0, word, 1);
+        }
+        Airdrops::<T>::mutate(0, |airdrop| {
+            if let Some(airdrop) = airdrop {
+                airdrop.claimed_words = w;
+            }
+        });
+        frame_system::Pallet::<T>::set_block_number(100u32.into());
+
+        #[extrinsic_call]
+        reclaim(RawOrigin::Signed(caller), 0, w);
+
+        assert!(Airdrops::<T>::get(0).is_none());
+        assert_eq!(ClaimedBitmap::<T>::iter_prefix(0).count(), 0);
+    }
+
+    #[benchmark]
+    fn escrow_transfer() {
+        let caller: T::AccountId = whitelisted_caller();
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let token_id = create_token::<T>(&caller);
+        let amount: T::TokenBalance = 100u32.into();
+        setup_transfer::<T>(token_id, &caller, &Template::<T>::account_id());
+
+        #[extrinsic_call]
+        escrow_transfer(
+            RawOrigin::Signed(caller),
+            token_id,
+            T::Lookup::unlookup(recipient),
+            amount,
+            10u32.into(),
+        );
+
+        assert_eq!(BalanceOf::<T>::get(token_id, &Template::<T>::account_id()), amount);
+    }
+
+    #[benchmark]
+    fn claim_escrow() {
+        let sender: T::AccountId = account("sender", 0, SEED);
+        let caller: T::AccountId = whitelisted_caller();
+        let token_id = create_token::<T>(&sender);
+        let amount: T::TokenBalance = 100u32.into();
+        setup_transfer::<T>(token_id, &Template::<T>::account_id(), &caller);
+
+        assert!(Template::<T>::escrow_transfer(
+            RawOrigin::Signed(sender).into(),
+            token_id,
+            T::Lookup::unlookup(caller.clone()),
+            amount,
+            10u32.into()
+        )
+        .is_ok());
+        frame_system::Pallet::<T>::set_block_number(10u32.into());
+
+        #[extrinsic_call]
+        claim_escrow(RawOrigin::Signed(caller.clone()), 0);
+
+        assert_eq!(BalanceOf::<T>::get(token_id, &caller), amount);
+    }
+
+    #[benchmark]
+    fn cancel_escrow() {
+        let caller: T::AccountId = whitelisted_caller();
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let token_id = create_token::<T>(&caller);
+        setup_transfer::<T>(token_id, &Template::<T>::account_id(), &caller);
+
+        assert!(Template::<T>::escrow_transfer(
+            RawOrigin::Signed(caller.clone()).into(),
+            token_id,
+            T::Lookup::unlookup(recipient),
+            100u32.into(),
+            10u32.into()
+        )
+        .is_ok());
+
+        #[extrinsic_call]
+        cancel_escrow(RawOrigin::Signed(caller), 0);
+
+        assert!(Escrows::<T>::get(0).is_none());
+    }
+
+    #[benchmark]
+    fn create_stream() {
+        let caller: T::AccountId = whitelisted_caller();
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let token_id = create_token::<T>(&caller);
+        setup_transfer::<T>(token_id, &caller, &Template::<T>::account_id());
+        frame_system::Pallet::<T>::set_block_number(1u32.into());
+
+        #[extrinsic_call]
+        create_stream(
+            RawOrigin::Signed(caller),
+            T::Lookup::unlookup(recipient),
+            token_id,
+            1u32.into(),
+            1u32.into(),
+            11u32.into(),
+        );
+
+        assert_eq!(
+            BalanceOf::<T>::get(token_id, &Template::<T>::account_id()),
+            10u32.into()
+        );
+    }
+
+    #[benchmark]
+    fn withdraw_from_stream() {
+        let sender: T::AccountId = account("sender", 0, SEED);
+        let caller: T::AccountId = whitelisted_caller();
+        let token_id = create_token::<T>(&sender);
+        setup_transfer::<T>(token_id, &Template::<T>::account_id(), &caller);
+        frame_system::Pallet::<T>::set_block_number(1u32.into());
+
+        assert!(Template::<T>::create_stream(
+            RawOrigin::Signed(sender).into(),
+            T::Lookup::unlookup(caller.clone()),
+            token_id,
+            1u32.into(),
+            1u32.into(),
+            11u32.into()
+        )
+        .is_ok());
+        frame_system::Pallet::<T>::set_block_number(6u32.into());
+
+        #[extrinsic_call]
+        withdraw_from_stream(RawOrigin::Signed(caller.clone()), 0);
+
+        assert_eq!(BalanceOf::<T>::get(token_id, &caller), 5u32.into());
+    }
+
+    // Worst case: halfway through, so both the recipient and the sender are paid.
+    #[benchmark]
+    fn cancel_stream() {
+        let caller: T::AccountId = whitelisted_caller();
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let token_id = create_token::<T>(&caller);
+        setup_transfer::<T>(token_id, &Template::<T>::account_id(), &recipient);
+        frame_system::Pallet::<T>::set_block_number(1u32.into());
+
+        assert!(Template::<T>::create_stream(
+            RawOrigin::Signed(caller.clone()).into(),
+            T::Lookup::unlookup(recipient.clone()),
+            token_id,
+            1u32.into(),
+            1u32.into(),
+            11u32.into()
+        )
+        .is_ok());
+        frame_system::Pallet::<T>::set_block_number(6u32.into());
+
+        #[extrinsic_call]
+        cancel_stream(RawOrigin::Signed(caller), 0);
+
+        assert_eq!(BalanceOf::<T>::get(token_id, &recipient), 5u32.into());
+    }
+
+    #[benchmark]
+    fn force_transfer() -> Result<(), BenchmarkError> {
+        let origin = T::ForceOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let source: T::AccountId = account("source", 0, SEED);
+        let dest: T::AccountId = account("dest", 0, SEED);
+        let token_id = create_token::<T>(&source);
+        let amount: T::TokenBalance = 100u32.into();
+        setup_transfer::<T>(token_id, &source, &dest);
+
+        #[extrinsic_call]
+        force_transfer(
+            origin as T::RuntimeOrigin,
+            token_id,
+            T::Lookup::unlookup(source),
+            T::Lookup::unlookup(dest.clone()),
+            amount,
+        );
+
+        assert_eq!(BalanceOf::<T>::get(token_id, &dest), amount);
+
+        Ok(())
+    }
+
+    // Worst case: a raise, which also changes the supply and the pending snapshot.
+    #[benchmark]
+    fn force_set_balance() -> Result<(), BenchmarkError> {
+        let origin = T::ForceOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let who: T::AccountId = account("who", 0, SEED);
+        let token_id = create_token::<T>(&owner);
+        let new_balance: T::TokenBalance = 100u32.into();
+        setup_transfer::<T>(token_id, &owner, &who);
+
+        #[extrinsic_call]
+        force_set_balance(
+            origin as T::RuntimeOrigin,
+            token_id,
+            T::Lookup::unlookup(who.clone()),
+            new_balance,
+        );
+
+        assert_eq!(BalanceOf::<T>::get(token_id, &who), new_balance);
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn force_set_metadata() -> Result<(), BenchmarkError> {
+        let origin = T::ForceOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let token_id = create_token::<T>(&owner);
+        let name = BoundedVec::try_from(vec![b'n'; T::MaxTokenNameLen::get() as usize]).unwrap();
+        let symbol =
+            BoundedVec::try_from(vec![b's'; T::MaxTokenSymbolLen::get() as usize]).unwrap();
+
+        #[extrinsic_call]
+        force_set_metadata(origin as T::RuntimeOrigin, token_id, name.clone(), symbol);
+
+        assert_eq!(Tokens::<T>::get(token_id).unwrap().name, name);
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn force_set_team() -> Result<(), BenchmarkError> {
+        let origin = T::ForceOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let admin: T::AccountId = account("admin", 0, SEED);
+        let token_id = create_token::<T>(&owner);
+
+        #[extrinsic_call]
+        force_set_team(
+            origin as T::RuntimeOrigin,
+            token_id,
+            T::Lookup::unlookup(owner),
+            T::Lookup::unlookup(admin.clone()),
+        );
+
+        assert_eq!(Tokens::<T>::get(token_id).unwrap().admin, admin);
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn force_cancel_approval() -> Result<(), BenchmarkError> {
+        let origin = T::ForceOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let spender: T::AccountId = account("spender", 0, SEED);
+        let token_id = create_token::<T>(&owner);
+        let amount: T::TokenBalance = 100u32.into();
+        Allowance::<T>::insert((token_id, owner.clone(), spender.clone()), amount);
+
+        #[extrinsic_call]
+        force_cancel_approval(
+            origin as T::RuntimeOrigin,
+            token_id,
+            T::Lookup::unlookup(owner.clone()),
+            T::Lookup::unlookup(spender.clone()),
+        );
+
+        assert_eq!(Allowance::<T>::get((token_id, owner, spender)), 0u32.into());
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_operator() {
         let caller: T::AccountId = whitelisted_caller();
+        let operator: T::AccountId = account("operator", 0, SEED);
+
         #[extrinsic_call]
-        do_something(RawOrigin::Signed(caller), value);
+        set_operator(
+            RawOrigin::Signed(caller.clone()),
+            T::Lookup::unlookup(operator.clone()),
+            true,
+        );
 
-        assert_eq!(Something::<T>::get(), Some(value));
+        assert!(Operators::<T>::contains_key(&caller, &operator));
     }
 
     #[benchmark]
-    fn cause_error() {
-        Something::<T>::put(100u32);
+    fn set_rate_limit() {
         let caller: T::AccountId = whitelisted_caller();
+        let token_id = create_token::<T>(&caller);
+        let limit = RateLimit {
+            amount: 1_000u32.into(),
+            window: 10u32.into(),
+        };
+
+        #[extrinsic_call]
+        set_rate_limit(RawOrigin::Signed(caller), token_id, Some(limit));
+
+        assert_eq!(RateLimits::<T>::get(token_id), Some(limit));
+    }
+
+    #[benchmark]
+    fn add_rate_limit_exemptions(n: Linear<1, { T::MaxAllowlistBatch::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let token_id = create_token::<T>(&caller);
+
+        #[extrinsic_call]
+        add_rate_limit_exemptions(RawOrigin::Signed(caller), token_id, lookups::<T>("exempt", n));
+
+        let last: T::AccountId = account("exempt", n - 1, SEED);
+        assert!(RateLimitExempt::<T>::contains_key(token_id, &last));
+    }
+
+    #[benchmark]
+    fn remove_rate_limit_exemptions(n: Linear<1, { T::MaxAllowlistBatch::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let token_id = create_token::<T>(&caller);
+
+        assert!(Template::<T>::add_rate_limit_exemptions(
+            RawOrigin::Signed(caller.clone()).into(),
+            token_id,
+            lookups::<T>("exempt", n)
+        )
+        .is_ok());
+
+        #[extrinsic_call]
+        remove_rate_limit_exemptions(
+            RawOrigin::Signed(caller),
+            token_id,
+            lookups::<T>("exempt", n),
+        );
+
+        let last: T::AccountId = account("exempt", n - 1, SEED);
+        assert!(!RateLimitExempt::<T>::contains_key(token_id, &last));
+    }
+
+    // Worst case: a permissioned token, so the beneficiary's allowlisting is checked.
+    #[benchmark]
+    fn set_transfer_fee() {
+        let caller: T::AccountId = whitelisted_caller();
+        let beneficiary: T::AccountId = account("beneficiary", 0, SEED);
+        let token_id = create_token::<T>(&caller);
+
+        assert!(Template::<T>::set_permissioned(
+            RawOrigin::Signed(caller.clone()).into(),
+            token_id,
+            true
+        )
+        .is_ok());
+        Holders::<T>::insert(token_id, &beneficiary, ());
+
+        #[extrinsic_call]
+        set_transfer_fee(
+            RawOrigin::Signed(caller),
+            token_id,
+            Some((Permill::from_percent(1), T::Lookup::unlookup(beneficiary))),
+        );
+
+        assert!(Tokens::<T>::get(token_id).unwrap().transfer_fee.is_some());
+    }
+
+    #[benchmark]
+    fn add_fee_exemptions(n: Linear<1, { T::MaxAllowlistBatch::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let token_id = create_token::<T>(&caller);
+
+        #[extrinsic_call]
+        add_fee_exemptions(RawOrigin::Signed(caller), token_id, lookups::<T>("exempt", n));
+
+        let last: T::AccountId = account("exempt", n - 1, SEED);
+        assert!(FeeExempt::<T>::contains_key(token_id, &last));
+    }
+
+    #[benchmark]
+    fn remove_fee_exemptions(n: Linear<1, { T::MaxAllowlistBatch::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let token_id = create_token::<T>(&caller);
+
+        assert!(Template::<T>::add_fee_exemptions(
+            RawOrigin::Signed(caller.clone()).into(),
+            token_id,
+            lookups::<T>("exempt", n)
+        )
+        .is_ok());
+
         #[extrinsic_call]
-        cause_error(RawOrigin::Signed(caller));
+        remove_fee_exemptions(RawOrigin::Signed(caller), token_id, lookups::<T>("exempt", n));
 
-        assert_eq!(Something::<T>::get(), Some(101u32));
+        let last: T::AccountId = account("exempt", n - 1, SEED);
+        assert!(!FeeExempt::<T>::contains_key(token_id, &last));
     }
 
     impl_benchmark_test_suite!(Template, crate::mock::new_test_ext(), crate::mock::Test);
@@ -0,0 +1,240 @@
+//! `fungibles` trait implementations so other pallets can hold erc20 tokens.
+//!
+//! `BalanceOf` is the free balance and `Holds` keeps the held balances, so
+//! anything put on hold is out of reach of `transfer`. Snapshots and voting
+//! power follow the free balance, so held funds don't vote.
+use super::*;
+use frame_support::{
+    pallet_prelude::*,
+    traits::tokens::{
+        fungibles, DepositConsequence, Fortitude, Preservation, Provenance, WithdrawConsequence,
+    },
+};
+use sp_runtime::TokenError;
+
+impl<T: Config> fungibles::Inspect<T::AccountId> for Pallet<T> {
+    type AssetId = T::TokenId;
+    type Balance = T::TokenBalance;
+
+    fn total_issuance(asset: T::TokenId) -> T::TokenBalance {
+        Tokens::<T>::get(asset)
+            .map(|details| details.supply)
+            .unwrap_or_else(Zero::zero)
+    }
+
+    fn minimum_balance(_asset: T::TokenId) -> T::TokenBalance {
+        Zero::zero()
+    }
+
+    fn total_balance(asset: T::TokenId, who: &T::AccountId) -> T::TokenBalance {
+        Pallet::<T>::balance_of(asset, who).saturating_add(
+            <Self as fungibles::hold::Inspect<T::AccountId>>::total_balance_on_hold(asset, who),
+        )
+    }
+
+    fn balance(asset: T::TokenId, who: &T::AccountId) -> T::TokenBalance {
+        Pallet::<T>::balance_of(asset, who)
+    }
+
+    fn reducible_balance(
+        asset: T::TokenId,
+        who: &T::AccountId,
+        _preservation: Preservation,
+        force: Fortitude,
+    ) -> T::TokenBalance {
+        let free = Pallet::<T>::balance_of(asset, who);
+
+        match force {
+            Fortitude::Polite => free.saturating_sub(Locked::<T>::get(asset, who)),
+            Fortitude::Force => free,
+        }
+    }
+
+    fn can_deposit(
+        asset: T::TokenId,
+        who: &T::AccountId,
+        amount: T::TokenBalance,
+        provenance: Provenance,
+    ) -> DepositConsequence {
+        let details = match Tokens::<T>::get(asset) {
+            Some(details) => details,
+            None => return DepositConsequence::UnknownAsset,
+        };
+
+        if details.permissioned && !Pallet::<T>::is_allowlisted(asset, who) {
+            return DepositConsequence::Blocked;
+        }
+
+        if provenance == Provenance::Minted {
+            match details.supply.checked_add(&amount) {
+                Some(supply) if details.max_supply.map_or(true, |max| supply <= max) => {}
+                _ => return DepositConsequence::Overflow,
+            }
+        }
+
+        if Pallet::<T>::balance_of(asset, who).checked_add(&amount).is_none() {
+            return DepositConsequence::Overflow;
+        }
+
+        DepositConsequence::Success
+    }
+
+    fn can_withdraw(
+        asset: T::TokenId,
+        who: &T::AccountId,
+        amount: T::TokenBalance,
+    ) -> WithdrawConsequence<T::TokenBalance> {
+        if !Tokens::<T>::contains_key(asset) {
+            return WithdrawConsequence::UnknownAsset;
+        }
+
+        let rest = match Pallet::<T>::balance_of(asset, who).checked_sub(&amount) {
+            Some(rest) => rest,
+            None => return WithdrawConsequence::BalanceLow,
+        };
+
+        if rest < Locked::<T>::get(asset, who) {
+            return WithdrawConsequence::Frozen;
+        }
+
+        WithdrawConsequence::Success
+    }
+
+    fn asset_exists(asset: T::TokenId) -> bool {
+        Tokens::<T>::contains_key(asset)
+    }
+}
+
+impl<T: Config> fungibles::Unbalanced<T::AccountId> for Pallet<T> {
+    fn handle_dust(_dust: fungibles::Dust<T::AccountId, Self>) {
+        // minimum_balance is zero, so there is never any dust.
+    }
+
+    fn write_balance(
+        asset: T::TokenId,
+        who: &T::AccountId,
+        amount: T::TokenBalance,
+    ) -> Result<Option<T::TokenBalance>, DispatchError> {
+        ensure!(Tokens::<T>::contains_key(asset), Error::<T>::NoneToken);
+
+        Pallet::<T>::update_account_snapshot(asset, who);
+
+        let old = Pallet::<T>::balance_of(asset, who);
+        Pallet::<T>::put_balance(asset, who, amount);
+
+        let delegate = Delegates::<T>::get(asset, who);
+        if amount > old {
+            Pallet::<T>::move_voting_power(asset, None, delegate.as_ref(), amount - old);
+        } else {
+            Pallet::<T>::move_voting_power(asset, delegate.as_ref(), None, old - amount);
+        }
+
+        Ok(None)
+    }
+
+    fn set_total_issuance(asset: T::TokenId, amount: T::TokenBalance) {
+        Pallet::<T>::update_supply_snapshot(asset);
+
+        Tokens::<T>::mutate(asset, |maybe_details| {
+            if let Some(details) = maybe_details {
+                details.supply = amount;
+            }
+        });
+    }
+}
+
+impl<T: Config> fungibles::hold::Inspect<T::AccountId> for Pallet<T> {
+    type Reason = T::RuntimeHoldReason;
+
+    fn total_balance_on_hold(asset: T::TokenId, who: &T::AccountId) -> T::TokenBalance {
+        Holds::<T>::get(asset, who)
+            .iter()
+            .fold(Zero::zero(), |acc: T::TokenBalance, hold| {
+                acc.saturating_add(hold.amount)
+            })
+    }
+
+    fn balance_on_hold(
+        asset: T::TokenId,
+        reason: &T::RuntimeHoldReason,
+        who: &T::AccountId,
+    ) -> T::TokenBalance {
+        Holds::<T>::get(asset, who)
+            .iter()
+            .find(|hold| hold.id == *reason)
+            .map(|hold| hold.amount)
+            .unwrap_or_else(Zero::zero)
+    }
+
+    fn hold_available(
+        asset: T::TokenId,
+        reason: &T::RuntimeHoldReason,
+        who: &T::AccountId,
+    ) -> bool {
+        let holds = Holds::<T>::get(asset, who);
+
+        holds.iter().any(|hold| hold.id == *reason) || !holds.is_full()
+    }
+
+    // The default checks `reducible_balance` with `Fortitude::Force`, which ignores
+    // vesting locks. Only unlocked funds may go on hold, otherwise they could leave
+    // through `transfer_on_hold`.
+    fn ensure_can_hold(
+        asset: T::TokenId,
+        reason: &T::RuntimeHoldReason,
+        who: &T::AccountId,
+        amount: T::TokenBalance,
+    ) -> DispatchResult {
+        ensure!(
+            Self::hold_available(asset, reason, who),
+            TokenError::CannotCreateHold
+        );
+        ensure!(
+            amount
+                <= <Self as fungibles::Inspect<T::AccountId>>::reducible_balance(
+                    asset,
+                    who,
+                    Preservation::Protect,
+                    Fortitude::Polite,
+                ),
+            TokenError::FundsUnavailable
+        );
+
+        Ok(())
+    }
+}
+
+impl<T: Config> fungibles::hold::Unbalanced<T::AccountId> for Pallet<T> {
+    fn set_balance_on_hold(
+        asset: T::TokenId,
+        reason: &T::RuntimeHoldReason,
+        who: &T::AccountId,
+        amount: T::TokenBalance,
+    ) -> DispatchResult {
+        let mut holds = Holds::<T>::get(asset, who);
+
+        match holds.iter().position(|hold| hold.id == *reason) {
+            Some(index) if amount.is_zero() => {
+                holds.remove(index);
+            }
+            Some(index) => holds[index].amount = amount,
+            None if amount.is_zero() => {}
+            None => holds
+                .try_push(IdAmount {
+                    id: *reason,
+                    amount,
+                })
+                .map_err(|_| Error::<T>::TooManyHolds)?,
+        }
+
+        if holds.is_empty() {
+            Holds::<T>::remove(asset, who);
+        } else {
+            Holds::<T>::insert(asset, who, holds);
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: Config> fungibles::hold::Mutate<T::AccountId> for Pallet<T> {}
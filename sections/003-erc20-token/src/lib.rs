@@ -13,10 +13,23 @@ mod tests;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+mod impl_fungibles;
+pub mod migrations;
 pub mod weights;
+pub use traits::*;
+pub mod traits;
 pub use types::*;
 pub mod types;
-use sp_runtime::traits::{CheckedAdd, CheckedSub, One, StaticLookup, Zero};
+use frame_support::traits::tokens::{
+    fungibles::hold::Mutate as MutateHold, Fortitude, Precision, Restriction,
+};
+use frame_support::PalletId;
+use sp_runtime::traits::{
+    AccountIdConversion, AtLeast32BitUnsigned, CheckedAdd, CheckedMul, CheckedSub, Convert,
+    Hash as HashT, MaybeSerializeDeserialize, One, Saturating, StaticLookup, Zero,
+};
+use sp_runtime::Permill;
+use sp_std::{borrow::Borrow, vec::Vec};
 pub use weights::*;
 
 type AccountIdLookupOf<T> = <<T as frame_system::Config>::Lookup as StaticLookup>::Source;
@@ -25,9 +38,17 @@ type AccountIdLookupOf<T> = <<T as frame_system::Config>::Lookup as StaticLookup
 pub mod pallet {
     use super::*;
     use frame_support::pallet_prelude::*;
+    use frame_support::traits::EnsureOriginWithArg;
     use frame_system::pallet_prelude::*;
 
+    /// v1 gives `TokenDetails` an owner, an admin, the allowlist switch and a supply
+    /// cap, and removes zero balance and allowance rows instead of storing them. v2
+    /// hashes their account keys with `Blake2_128Concat`, v3 adds `AccountTokens` and
+    /// v4 adds `TokenDetails::transfer_fee`.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     /// Configure the pallet by specifying the parameters and types on which it depends.
@@ -49,9 +70,11 @@ pub mod pallet {
             + Decode
             + CheckedAdd
             + MaxEncodedLen
+            + MaybeSerializeDeserialize
             + One
             + Zero;
-        type TokenBalance: Copy
+        type TokenBalance: AtLeast32BitUnsigned
+            + Copy
             + Default
             + PartialOrd
             + Member
@@ -61,7 +84,87 @@ pub mod pallet {
             + CheckedAdd
             + CheckedSub
             + MaxEncodedLen
+            + MaybeSerializeDeserialize
             + Zero;
+
+        /// Maximum number of vesting schedules an account may have per token.
+        #[pallet::constant]
+        type MaxVestingSchedules: Get<u32>;
+        /// The least a vested transfer may lock, so an account's schedules can't be
+        /// filled with dust.
+        #[pallet::constant]
+        type MinVestedTransfer: Get<Self::TokenBalance>;
+        /// Converts a number of blocks into a token amount so vesting schedules
+        /// can be evaluated at a given block.
+        type BlockNumberToBalance: Convert<BlockNumberFor<Self>, Self::TokenBalance>;
+
+        /// The reasons other pallets may hold tokens for, e.g. collateral or deposits.
+        type RuntimeHoldReason: Parameter + Member + MaxEncodedLen + Ord + Copy;
+        /// Maximum number of distinct holds an account may have per token.
+        #[pallet::constant]
+        type MaxHolds: Get<u32>;
+        /// How many of the latest snapshots of a token can be queried. Older ones
+        /// expire, which keeps the recorded balances of each account bounded.
+        #[pallet::constant]
+        type MaxSnapshots: Get<u32>;
+        /// How many blocks back voting power can be looked up, e.g. the longest voting
+        /// period of a governance pallet. Older checkpoints are pruned.
+        #[pallet::constant]
+        type CheckpointRetention: Get<BlockNumberFor<Self>>;
+
+        /// Can veto any transfer. Runs inside every call that moves tokens, so its
+        /// cost is included when those calls are benchmarked against the runtime.
+        type TransferFilter: TransferFilter<Self::TokenId, Self::AccountId, Self::TokenBalance>;
+        /// Notified after every transfer. Weight is accounted the same way as
+        /// `TransferFilter`.
+        type OnTransfer: OnTransfer<Self::TokenId, Self::AccountId, Self::TokenBalance>;
+        /// Puts `TransferFilter` and `OnTransfer` in their most expensive state for
+        /// the benchmarks.
+        #[cfg(feature = "runtime-benchmarks")]
+        type BenchmarkHelper: BenchmarkHelper<Self::TokenId, Self::AccountId>;
+
+        /// Maximum number of accounts added to or removed from an allowlist in one call.
+        #[pallet::constant]
+        type MaxAllowlistBatch: Get<u32>;
+
+        /// Maximum number of active emission schedules across all tokens.
+        #[pallet::constant]
+        type MaxEmissionSchedules: Get<u32>;
+        /// Maximum number of emission schedules processed in `on_initialize`.
+        /// Schedules are visited round-robin, so with more schedules than this
+        /// each one is processed every few blocks and catches up then.
+        #[pallet::constant]
+        type MaxEmissionsPerBlock: Get<u32>;
+
+        /// Derives the account that holds escrowed tokens, e.g. for airdrops.
+        #[pallet::constant]
+        type PalletId: Get<PalletId>;
+        /// Maximum depth of an airdrop Merkle tree.
+        #[pallet::constant]
+        type MaxProofLength: Get<u32>;
+
+        /// Can move balances and rewrite token details without the owner's consent,
+        /// e.g. to recover a lost key or remediate a hack. Usually root or a council.
+        type ForceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+        /// Who may create a token. Given the id the new token will get, returns the
+        /// account recorded as its owner.
+        type CreateOrigin: EnsureOriginWithArg<
+            Self::RuntimeOrigin,
+            Self::TokenId,
+            Success = Self::AccountId,
+        >;
+
+        /// Maximum number of tokens moved by one `safe_batch_transfer_from`.
+        #[pallet::constant]
+        type MaxBatchTransfers: Get<u32>;
+        /// Maximum length of the memo of `transfer_with_memo`, e.g. an invoice number.
+        #[pallet::constant]
+        type MaxMemoLen: Get<u32>;
+
+        /// How many buckets the outflow of an account is kept in for its rate limit.
+        /// More buckets track the window more closely, at the cost of a bigger row.
+        #[pallet::constant]
+        type MaxOutflowBuckets: Get<u32>;
     }
 
     #[pallet::storage]
@@ -73,31 +176,281 @@ pub mod pallet {
     pub type Tokens<T: Config> =
         StorageMap<_, Twox64Concat, T::TokenId, TokenDetails<T>, OptionQuery>;
 
+    /// Read through [`Pallet::balance_of`], which also finds rows the v2 migration
+    /// hasn't moved yet.
     #[pallet::storage]
-    #[pallet::getter(fn balance_of)]
     pub type BalanceOf<T: Config> = StorageDoubleMap<
         _,
         Twox64Concat,
         T::TokenId,
-        Twox64Concat,
+        Blake2_128Concat,
         T::AccountId,
         T::TokenBalance,
         ValueQuery,
     >;
 
+    /// Read through [`Pallet::allowance`], like `BalanceOf`.
     #[pallet::storage]
-    #[pallet::getter(fn allowance)]
     pub type Allowance<T: Config> = StorageNMap<
         _,
         (
             NMapKey<Twox64Concat, T::TokenId>,
-            NMapKey<Twox64Concat, T::AccountId>, // owner
-            NMapKey<Twox64Concat, T::AccountId>, // spender
+            NMapKey<Blake2_128Concat, T::AccountId>, // owner
+            NMapKey<Blake2_128Concat, T::AccountId>, // spender
         ),
         T::TokenBalance,
         ValueQuery,
     >;
 
+    /// The tokens each account has a free balance of, so its portfolio can be listed
+    /// without going through every token. Kept in step with `BalanceOf`.
+    #[pallet::storage]
+    pub type AccountTokens<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Twox64Concat, T::TokenId, ()>;
+
+    /// Vesting schedules for each (token, account). Locked funds are only released
+    /// into the spendable balance when `vest` is called.
+    #[pallet::storage]
+    #[pallet::getter(fn vesting)]
+    pub type Vesting<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::TokenId,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<VestingScheduleOf<T>, T::MaxVestingSchedules>,
+        ValueQuery,
+    >;
+
+    /// The part of `BalanceOf` that `_transfer` refuses to move.
+    #[pallet::storage]
+    #[pallet::getter(fn locked)]
+    pub type Locked<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::TokenId,
+        Blake2_128Concat,
+        T::AccountId,
+        T::TokenBalance,
+        ValueQuery,
+    >;
+
+    /// Balances held aside from `BalanceOf`, one entry per hold reason.
+    /// Held funds still belong to the account but cannot be spent with `transfer`.
+    #[pallet::storage]
+    #[pallet::getter(fn holds)]
+    pub type Holds<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::TokenId,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<IdAmount<T::RuntimeHoldReason, T::TokenBalance>, T::MaxHolds>,
+        ValueQuery,
+    >;
+
+    #[pallet::storage]
+    #[pallet::getter(fn current_snapshot_id)]
+    pub type CurrentSnapshotId<T: Config> =
+        StorageMap<_, Twox64Concat, T::TokenId, SnapshotId, ValueQuery>;
+
+    /// `(snapshot_id, balance)` pairs, written lazily the first time an account's
+    /// balance changes after a snapshot. Ordered by snapshot id.
+    #[pallet::storage]
+    pub type AccountSnapshots<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::TokenId,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<(SnapshotId, T::TokenBalance), T::MaxSnapshots>,
+        ValueQuery,
+    >;
+
+    /// Same as `AccountSnapshots`, but for the token supply.
+    #[pallet::storage]
+    pub type SupplySnapshots<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        T::TokenId,
+        BoundedVec<(SnapshotId, T::TokenBalance), T::MaxSnapshots>,
+        ValueQuery,
+    >;
+
+    /// Who an account has delegated its voting power to. Accounts that never
+    /// delegated have no voting power, not even over their own balance.
+    #[pallet::storage]
+    #[pallet::getter(fn delegates)]
+    pub type Delegates<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::TokenId,
+        Blake2_128Concat,
+        T::AccountId,
+        T::AccountId,
+        OptionQuery,
+    >;
+
+    /// Voting power history of each delegatee, one checkpoint per block its votes
+    /// changed in, numbered in block order. See `CheckpointRange`.
+    #[pallet::storage]
+    pub type Checkpoints<T: Config> = StorageNMap<
+        _,
+        (
+            NMapKey<Twox64Concat, T::TokenId>,
+            NMapKey<Blake2_128Concat, T::AccountId>,
+            NMapKey<Twox64Concat, u32>,
+        ),
+        CheckpointOf<T>,
+        OptionQuery,
+    >;
+
+    /// The `(first, end)` numbers of the checkpoints each delegatee has. `first` only
+    /// moves up as checkpoints older than `CheckpointRetention` are pruned.
+    #[pallet::storage]
+    pub type CheckpointRange<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::TokenId,
+        Blake2_128Concat,
+        T::AccountId,
+        (u32, u32),
+        ValueQuery,
+    >;
+
+    /// Accounts allowed to receive a permissioned token.
+    #[pallet::storage]
+    pub type Holders<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, T::TokenId, Blake2_128Concat, T::AccountId, ()>;
+
+    /// How much of each token may leave an account per window. Set by the admin.
+    #[pallet::storage]
+    #[pallet::getter(fn rate_limits)]
+    pub type RateLimits<T: Config> =
+        StorageMap<_, Twox64Concat, T::TokenId, RateLimitOf<T>, OptionQuery>;
+
+    /// What each account sent in the last window of its rate limit, oldest bucket
+    /// first. Rows are never cleared in bulk: buckets that have expired count as
+    /// unused, and rows of a token without a limit are ignored.
+    #[pallet::storage]
+    pub type Outflows<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::TokenId,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<OutflowOf<T>, T::MaxOutflowBuckets>,
+        ValueQuery,
+    >;
+
+    /// Accounts the rate limit of a token doesn't apply to, e.g. exchange hot wallets.
+    /// Kept apart from `Holders` because every holder of a permissioned token is on
+    /// that list, and the admin wants to limit those holders, not exempt them.
+    #[pallet::storage]
+    pub type RateLimitExempt<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, T::TokenId, Blake2_128Concat, T::AccountId, ()>;
+
+    /// Accounts that send and receive a token without paying its transfer fee. Kept
+    /// apart from `Holders` for the same reason as `RateLimitExempt`: on a
+    /// permissioned token every holder is on the allowlist, and the admin wants them
+    /// to pay.
+    #[pallet::storage]
+    pub type FeeExempt<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, T::TokenId, Blake2_128Concat, T::AccountId, ()>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn emission_schedules)]
+    pub type EmissionSchedules<T: Config> =
+        StorageMap<_, Twox64Concat, EmissionId, EmissionSchedule<T>, OptionQuery>;
+
+    #[pallet::storage]
+    pub type NextEmissionId<T: Config> = StorageValue<_, EmissionId, ValueQuery>;
+
+    /// Ids of the active emission schedules, in processing order.
+    #[pallet::storage]
+    pub type EmissionQueue<T: Config> =
+        StorageValue<_, BoundedVec<EmissionId, T::MaxEmissionSchedules>, ValueQuery>;
+
+    /// Index into `EmissionQueue` where the next block starts processing.
+    #[pallet::storage]
+    pub type EmissionCursor<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn airdrops)]
+    pub type Airdrops<T: Config> = StorageMap<_, Twox64Concat, AirdropId, Airdrop<T>, OptionQuery>;
+
+    #[pallet::storage]
+    pub type NextAirdropId<T: Config> = StorageValue<_, AirdropId, ValueQuery>;
+
+    /// Which leaves of an airdrop were claimed, 128 leaf indices per word.
+    #[pallet::storage]
+    pub type ClaimedBitmap<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, AirdropId, Twox64Concat, u32, u128, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn escrows)]
+    pub type Escrows<T: Config> = StorageMap<_, Twox64Concat, EscrowId, Escrow<T>, OptionQuery>;
+
+    #[pallet::storage]
+    pub type NextEscrowId<T: Config> = StorageValue<_, EscrowId, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn streams)]
+    pub type Streams<T: Config> = StorageMap<_, Twox64Concat, StreamId, Stream<T>, OptionQuery>;
+
+    #[pallet::storage]
+    pub type NextStreamId<T: Config> = StorageValue<_, StreamId, ValueQuery>;
+
+    /// Operators may spend every token of the owner without an allowance.
+    #[pallet::storage]
+    pub type Operators<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId, // owner
+        Blake2_128Concat,
+        T::AccountId, // operator
+        (),
+    >;
+
+    /// How far the `on_idle` migrations have got, while one is running. See
+    /// [`migrations::v2`] and [`migrations::v3`].
+    #[pallet::storage]
+    #[pallet::unbounded]
+    pub type Rehash<T: Config> = StorageValue<_, SweepCursor, OptionQuery>;
+
+    #[pallet::genesis_config]
+    #[derive(frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        /// `(owner, name, symbol, supply, max_supply)`. Ids are assigned in order,
+        /// starting at 1, and the whole supply goes to the owner.
+        pub tokens: Vec<(
+            T::AccountId,
+            Vec<u8>,
+            Vec<u8>,
+            T::TokenBalance,
+            Option<T::TokenBalance>,
+        )>,
+        /// `(token_id, who, amount)` issued on top of each token's initial supply.
+        pub balances: Vec<(T::TokenId, T::AccountId, T::TokenBalance)>,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            for (owner, name, symbol, supply, max_supply) in &self.tokens {
+                let name = BoundedVec::try_from(name.clone()).expect("token name too long");
+                let symbol = BoundedVec::try_from(symbol.clone()).expect("token symbol too long");
+
+                Pallet::<T>::do_mint(owner.clone(), name, symbol, *supply, *max_supply)
+                    .expect("invalid genesis token");
+            }
+
+            for (token_id, who, amount) in &self.balances {
+                Pallet::<T>::do_issue(*token_id, who, *amount).expect("invalid genesis balance");
+            }
+        }
+    }
+
     // Pallets use events to inform users when important changes are made.
     // https://docs.substrate.io/main-docs/build/events-errors/
     #[pallet::event]
@@ -109,6 +462,7 @@ pub mod pallet {
             token_id: T::TokenId,
             who: T::AccountId,
         },
+        /// `amount` is what `to` received, after any transfer fee.
         Transferred {
             from: T::AccountId,
             to: T::AccountId,
@@ -120,180 +474,2548 @@ pub mod pallet {
             spender: T::AccountId,
             amount: T::TokenBalance,
         },
+        /// `amount` is what `to` received, after any transfer fee.
         TransferredFrom {
             spender: T::AccountId,
             from: T::AccountId,
             to: T::AccountId,
             amount: T::TokenBalance,
         },
-    }
-
-    // Errors inform users that something went wrong.
-    #[pallet::error]
-    pub enum Error<T> {
-        /// Error names should be descriptive.
-        NoneValue,
-        /// Errors should have helpful documentation associated with them.
-        StorageOverflow,
-        NoneToken,
-        TokenExists,
-        TokenIdOverflow,
-        InsufficientBalance,
-        TokenBalanceOverflow,
-        NotApproved,
-    }
-
-    // Dispatchable functions allows users to interact with the pallet and invoke state changes.
-    // These functions materialize as "extrinsics", which are often compared to transactions.
-    // Dispatchable functions must be annotated with a weight and must return a DispatchResult.
-    #[pallet::call]
-    impl<T: Config> Pallet<T> {
-        #[pallet::call_index(0)]
-        #[pallet::weight(0)]
-        pub fn mint(
-            _minter: OriginFor<T>,
-            name: BoundedVec<u8, T::MaxTokenNameLen>,
-            symbol: BoundedVec<u8, T::MaxTokenSymbolLen>,
-            supply: T::TokenBalance,
-        ) -> DispatchResult {
-            ensure!(supply > Zero::zero(), Error::<T>::NoneValue);
-
-            let minter = ensure_signed(_minter)?;
-
-            let last_id = LastTokenId::<T>::get();
-            let token_id = last_id
-                .checked_add(&One::one())
-                .ok_or(Error::<T>::TokenIdOverflow)?;
-
-            let details = TokenDetails {
-                name,
-                symbol,
-                supply,
-            };
-
-            Tokens::<T>::insert(token_id, details);
-            BalanceOf::<T>::insert(token_id, minter.clone(), supply);
-
-            Self::deposit_event(Event::TokenMinted {
-                token_id,
-                who: minter,
-            });
-
-            Ok(())
-        }
-
-        #[pallet::call_index(1)]
-        #[pallet::weight(0)]
-        pub fn transfer(
-            _from: OriginFor<T>,
-            _to: AccountIdLookupOf<T>,
+        VestedTransferred {
+            token_id: T::TokenId,
+            from: T::AccountId,
+            to: T::AccountId,
+            schedule: VestingScheduleOf<T>,
+        },
+        /// Some funds were unlocked; `unvested` is what remains locked.
+        VestingUpdated {
             token_id: T::TokenId,
+            who: T::AccountId,
+            unvested: T::TokenBalance,
+        },
+        VestingCompleted {
+            token_id: T::TokenId,
+            who: T::AccountId,
+        },
+        Held {
+            token_id: T::TokenId,
+            who: T::AccountId,
+            reason: T::RuntimeHoldReason,
             amount: T::TokenBalance,
-        ) -> DispatchResult {
-            let from = ensure_signed(_from)?;
-            let to = T::Lookup::lookup(_to)?;
-
-            Self::_transfer(token_id, &from, &to, amount)?;
-
-            Self::deposit_event(Event::Transferred {
-                from,
-                to,
-                amount: amount,
-            });
-
-            Ok(())
-        }
-
-        #[pallet::call_index(2)]
-        #[pallet::weight(0)]
-        pub fn approve(
-            _owner: OriginFor<T>,
-            _spender: AccountIdLookupOf<T>,
+        },
+        Released {
             token_id: T::TokenId,
+            who: T::AccountId,
+            reason: T::RuntimeHoldReason,
             amount: T::TokenBalance,
-        ) -> DispatchResult {
-            let owner = ensure_signed(_owner)?;
-            let spender = T::Lookup::lookup(_spender)?;
-
-            let _token = Tokens::<T>::get(token_id).ok_or(Error::<T>::NoneToken)?;
-
-            Allowance::<T>::insert((token_id, owner.clone(), spender.clone()), amount);
-
-            Self::deposit_event(Event::Approved {
-                token_id,
-                owner,
-                spender,
-                amount,
-            });
-
-            Ok(())
-        }
-
-        #[pallet::call_index(3)]
-        #[pallet::weight(0)]
-        pub fn transfer_from(
-            _spender: OriginFor<T>,
+        },
+        /// Held funds were burned, reducing the token supply.
+        SlashedHeld {
             token_id: T::TokenId,
-            _owner: AccountIdLookupOf<T>,
-            _recipient: AccountIdLookupOf<T>,
+            who: T::AccountId,
+            reason: T::RuntimeHoldReason,
             amount: T::TokenBalance,
-        ) -> DispatchResult {
-            let spender = ensure_signed(_spender)?;
-
-            let owner = T::Lookup::lookup(_owner)?;
-            let recipient = T::Lookup::lookup(_recipient)?;
-
-            Allowance::<T>::try_mutate(
-                (token_id, owner.clone(), spender.clone()),
-                |allowance| -> DispatchResult {
-                    ensure!(*allowance > Zero::zero(), Error::<T>::NotApproved);
-                    ensure!(*allowance >= amount, Error::<T>::InsufficientBalance);
-
-                    Self::_transfer(token_id, &owner, &recipient, amount)?;
-
-                    *allowance = allowance
-                        .checked_sub(&amount)
-                        .ok_or(Error::<T>::InsufficientBalance)?;
-
-                    Ok(())
-                },
-            )?;
-
-            Self::deposit_event(Event::TransferredFrom {
-                spender,
-                from: owner,
-                to: recipient,
-                amount,
-            });
-
-            Ok(())
-        }
+        },
+        TransferredOnHold {
+            token_id: T::TokenId,
+            reason: T::RuntimeHoldReason,
+            from: T::AccountId,
+            to: T::AccountId,
+            amount: T::TokenBalance,
+        },
+        Snapshot {
+            token_id: T::TokenId,
+            id: SnapshotId,
+        },
+        DelegateChanged {
+            token_id: T::TokenId,
+            delegator: T::AccountId,
+            from_delegate: Option<T::AccountId>,
+            to_delegate: T::AccountId,
+        },
+        DelegateVotesChanged {
+            token_id: T::TokenId,
+            delegate: T::AccountId,
+            previous_votes: T::TokenBalance,
+            new_votes: T::TokenBalance,
+        },
+        PermissionedSet {
+            token_id: T::TokenId,
+            permissioned: bool,
+        },
+        HolderAdded {
+            token_id: T::TokenId,
+            who: T::AccountId,
+        },
+        HolderRemoved {
+            token_id: T::TokenId,
+            who: T::AccountId,
+        },
+        Issued {
+            token_id: T::TokenId,
+            to: T::AccountId,
+            amount: T::TokenBalance,
+        },
+        MaxSupplySet {
+            token_id: T::TokenId,
+            max_supply: T::TokenBalance,
+        },
+        EmissionCreated {
+            id: EmissionId,
+            token_id: T::TokenId,
+            beneficiary: T::AccountId,
+        },
+        Emitted {
+            id: EmissionId,
+            token_id: T::TokenId,
+            beneficiary: T::AccountId,
+            amount: T::TokenBalance,
+        },
+        /// The schedule was removed because the curve ran out or `max_supply` was reached.
+        EmissionCompleted {
+            id: EmissionId,
+        },
+        EmissionCancelled {
+            id: EmissionId,
+        },
+        AirdropCreated {
+            id: AirdropId,
+            token_id: T::TokenId,
+            creator: T::AccountId,
+            total: T::TokenBalance,
+        },
+        /// `amount` is what `who` received, after any transfer fee.
+        Claimed {
+            id: AirdropId,
+            index: u32,
+            who: T::AccountId,
+            amount: T::TokenBalance,
+        },
+        /// The creator took back what was left after expiry.
+        AirdropReclaimed {
+            id: AirdropId,
+            amount: T::TokenBalance,
+        },
+        EscrowCreated {
+            id: EscrowId,
+            token_id: T::TokenId,
+            from: T::AccountId,
+            to: T::AccountId,
+            amount: T::TokenBalance,
+            unlock_at: BlockNumberFor<T>,
+        },
+        /// `amount` is what `who` received, after any transfer fee.
+        EscrowClaimed {
+            id: EscrowId,
+            who: T::AccountId,
+            amount: T::TokenBalance,
+        },
+        EscrowCancelled {
+            id: EscrowId,
+            who: T::AccountId,
+            amount: T::TokenBalance,
+        },
+        StreamCreated {
+            id: StreamId,
+            token_id: T::TokenId,
+            sender: T::AccountId,
+            recipient: T::AccountId,
+            deposit: T::TokenBalance,
+        },
+        /// `amount` is what `recipient` received, after any transfer fee.
+        WithdrawnFromStream {
+            id: StreamId,
+            recipient: T::AccountId,
+            amount: T::TokenBalance,
+        },
+        /// Whatever accrued went to the recipient, less any transfer fee, and the rest
+        /// back to the sender.
+        StreamCancelled {
+            id: StreamId,
+            recipient_amount: T::TokenBalance,
+            sender_amount: T::TokenBalance,
+        },
+        /// `ForceOrigin` moved `amount` from `from` to `to`.
+        ForceTransferred {
+            token_id: T::TokenId,
+            from: T::AccountId,
+            to: T::AccountId,
+            amount: T::TokenBalance,
+        },
+        /// `ForceOrigin` overwrote the balance of `who`; supply moved by the difference.
+        BalanceForceSet {
+            token_id: T::TokenId,
+            who: T::AccountId,
+            old_balance: T::TokenBalance,
+            new_balance: T::TokenBalance,
+        },
+        MetadataForceSet {
+            token_id: T::TokenId,
+            name: BoundedVec<u8, T::MaxTokenNameLen>,
+            symbol: BoundedVec<u8, T::MaxTokenSymbolLen>,
+        },
+        TeamForceSet {
+            token_id: T::TokenId,
+            owner: T::AccountId,
+            admin: T::AccountId,
+        },
+        ApprovalForceCancelled {
+            token_id: T::TokenId,
+            owner: T::AccountId,
+            spender: T::AccountId,
+        },
+        OperatorSet {
+            owner: T::AccountId,
+            operator: T::AccountId,
+            approved: bool,
+        },
+        /// Each amount is what `to` received, after any transfer fee.
+        TransferredBatch {
+            operator: T::AccountId,
+            from: T::AccountId,
+            to: T::AccountId,
+            transfers: BoundedVec<(T::TokenId, T::TokenBalance), T::MaxBatchTransfers>,
+        },
+        Burned {
+            token_id: T::TokenId,
+            from: T::AccountId,
+            amount: T::TokenBalance,
+        },
+        /// The memo is only kept in this event, not in storage. `amount` is what `to`
+        /// received, after any transfer fee.
+        TransferredWithMemo {
+            token_id: T::TokenId,
+            from: T::AccountId,
+            to: T::AccountId,
+            amount: T::TokenBalance,
+            memo: BoundedVec<u8, T::MaxMemoLen>,
+        },
+        /// `limit` is `None` when the rate limit was removed.
+        RateLimitSet {
+            token_id: T::TokenId,
+            limit: Option<RateLimitOf<T>>,
+        },
+        RateLimitExemptionAdded {
+            token_id: T::TokenId,
+            who: T::AccountId,
+        },
+        RateLimitExemptionRemoved {
+            token_id: T::TokenId,
+            who: T::AccountId,
+        },
+        /// `fee` is `None` when the transfer fee was removed.
+        TransferFeeSet {
+            token_id: T::TokenId,
+            fee: Option<TransferFee<T::AccountId>>,
+        },
+        FeeExemptionAdded {
+            token_id: T::TokenId,
+            who: T::AccountId,
+        },
+        FeeExemptionRemoved {
+            token_id: T::TokenId,
+            who: T::AccountId,
+        },
+        /// `from` sent `gross`, `to` got `net` and `beneficiary` the `fee` in between.
+        TransferFeeCharged {
+            token_id: T::TokenId,
+            from: T::AccountId,
+            to: T::AccountId,
+            beneficiary: T::AccountId,
+            gross: T::TokenBalance,
+            fee: T::TokenBalance,
+            net: T::TokenBalance,
+        },
+    }
+
+    // Errors inform users that something went wrong.
+    #[pallet::error]
+    pub enum Error<T> {
+        /// Error names should be descriptive.
+        NoneValue,
+        /// Errors should have helpful documentation associated with them.
+        StorageOverflow,
+        NoneToken,
+        TokenExists,
+        TokenIdOverflow,
+        InsufficientBalance,
+        TokenBalanceOverflow,
+        NotApproved,
+        /// The transfer would dip into funds that are still vesting.
+        BalanceLocked,
+        /// A vesting schedule must lock something, release something per block,
+        /// and not have its cliff before its start.
+        InvalidVestingSchedule,
+        AtMaxVestingSchedules,
+        /// A vested transfer would lock less than `MinVestedTransfer`.
+        VestedTransferTooSmall,
+        NotVesting,
+        /// The account already has `MaxHolds` different holds on this token.
+        TooManyHolds,
+        /// Only the token owner can do this.
+        NoPermission,
+        /// The snapshot id is 0 or has not been taken yet.
+        InvalidSnapshotId,
+        /// The snapshot is older than the latest `MaxSnapshots` and has expired.
+        SnapshotExpired,
+        /// The token is permissioned and the recipient is not on its allowlist.
+        NotAllowlisted,
+        /// Issuing this amount would take the supply above the token's `max_supply`.
+        MaxSupplyExceeded,
+        /// `max_supply` can only be lowered, and not below the current supply.
+        InvalidMaxSupply,
+        InvalidEmissionCurve,
+        /// There are already `MaxEmissionSchedules` active schedules.
+        TooManyEmissions,
+        NoneEmission,
+        NoneAirdrop,
+        /// The expiry must be in the future.
+        InvalidExpiry,
+        AirdropExpired,
+        AirdropNotExpired,
+        AlreadyClaimed,
+        InvalidProof,
+        /// `claimed_words` is lower than the airdrop's.
+        BadWitness,
+        NoneEscrow,
+        /// The escrow can't be claimed before its unlock block.
+        EscrowLocked,
+        /// The escrow can't be cancelled once it unlocked.
+        EscrowUnlocked,
+        /// A stream must pay something and stop after it starts, which can't be in the past.
+        InvalidStream,
+        NoneStream,
+        NothingToWithdraw,
+        /// A rate limit must allow something and have a window of at least a block.
+        InvalidRateLimit,
+        /// The transfer would take the sender over the token's rate limit.
+        RateLimited,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+            let queue = EmissionQueue::<T>::get();
+
+            if queue.is_empty() {
+                return T::DbWeight::get().reads(1);
+            }
+
+            let len = queue.len() as u32;
+            let count = len.min(T::MaxEmissionsPerBlock::get());
+            let cursor = EmissionCursor::<T>::get() % len;
+
+            for i in 0..count {
+                Self::process_emission(queue[((cursor + i) % len) as usize], now);
+            }
+
+            EmissionCursor::<T>::put((cursor + count) % len);
+
+            T::WeightInfo::on_initialize_emissions(count)
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            migrations::v1::migrate::<T>()
+                .saturating_add(migrations::v2::start::<T>())
+                .saturating_add(migrations::v3::start::<T>())
+                .saturating_add(migrations::v4::migrate::<T>())
+        }
+
+        fn on_idle(_now: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            migrations::sweep::<T>(remaining_weight)
+        }
+    }
+
+    // Dispatchable functions allows users to interact with the pallet and invoke state changes.
+    // These functions materialize as "extrinsics", which are often compared to transactions.
+    // Dispatchable functions must be annotated with a weight and must return a DispatchResult.
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::WeightInfo::mint())]
+        pub fn mint(
+            origin: OriginFor<T>,
+            name: BoundedVec<u8, T::MaxTokenNameLen>,
+            symbol: BoundedVec<u8, T::MaxTokenSymbolLen>,
+            supply: T::TokenBalance,
+            max_supply: Option<T::TokenBalance>,
+        ) -> DispatchResult {
+            let token_id = Self::next_token_id()?;
+            let minter = T::CreateOrigin::ensure_origin(origin, &token_id)?;
+
+            Self::do_mint(minter, name, symbol, supply, max_supply)?;
+
+            Ok(())
+        }
+
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::WeightInfo::transfer())]
+        pub fn transfer(
+            _from: OriginFor<T>,
+            _to: AccountIdLookupOf<T>,
+            token_id: T::TokenId,
+            amount: T::TokenBalance,
+        ) -> DispatchResult {
+            let from = ensure_signed(_from)?;
+            let to = T::Lookup::lookup(_to)?;
+
+            let amount = Self::_transfer(token_id, &from, &to, amount)?;
+
+            Self::deposit_event(Event::Transferred {
+                from,
+                to,
+                amount: amount,
+            });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::approve())]
+        pub fn approve(
+            _owner: OriginFor<T>,
+            _spender: AccountIdLookupOf<T>,
+            token_id: T::TokenId,
+            amount: T::TokenBalance,
+        ) -> DispatchResult {
+            let owner = ensure_signed(_owner)?;
+            let spender = T::Lookup::lookup(_spender)?;
+
+            let _token = Tokens::<T>::get(token_id).ok_or(Error::<T>::NoneToken)?;
+
+            Self::put_allowance(token_id, &owner, &spender, amount);
+
+            Self::deposit_event(Event::Approved {
+                token_id,
+                owner,
+                spender,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::transfer_from())]
+        pub fn transfer_from(
+            _spender: OriginFor<T>,
+            token_id: T::TokenId,
+            _owner: AccountIdLookupOf<T>,
+            _recipient: AccountIdLookupOf<T>,
+            amount: T::TokenBalance,
+        ) -> DispatchResult {
+            let spender = ensure_signed(_spender)?;
+
+            let owner = T::Lookup::lookup(_owner)?;
+            let recipient = T::Lookup::lookup(_recipient)?;
+
+            Self::spend_allowance(token_id, &owner, &spender, amount)?;
+            let amount = Self::_transfer(token_id, &owner, &recipient, amount)?;
+
+            Self::deposit_event(Event::TransferredFrom {
+                spender,
+                from: owner,
+                to: recipient,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        /// Transfer `schedule.locked` to `target` and lock it under `schedule`. With a
+        /// transfer fee only what arrives is locked, and the event shows that amount.
+        /// What arrives must be at least `MinVestedTransfer`.
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::vested_transfer())]
+        pub fn vested_transfer(
+            _from: OriginFor<T>,
+            token_id: T::TokenId,
+            _target: AccountIdLookupOf<T>,
+            mut schedule: VestingScheduleOf<T>,
+        ) -> DispatchResult {
+            let from = ensure_signed(_from)?;
+            let target = T::Lookup::lookup(_target)?;
+
+            ensure!(schedule.is_valid(), Error::<T>::InvalidVestingSchedule);
+
+            schedule.locked = Self::_transfer(token_id, &from, &target, schedule.locked)?;
+            // a fee can take all of a tiny amount
+            ensure!(schedule.is_valid(), Error::<T>::InvalidVestingSchedule);
+            ensure!(
+                schedule.locked >= T::MinVestedTransfer::get(),
+                Error::<T>::VestedTransferTooSmall
+            );
+
+            Vesting::<T>::try_mutate(token_id, &target, |schedules| {
+                schedules
+                    .try_push(schedule)
+                    .map_err(|_| Error::<T>::AtMaxVestingSchedules)
+            })?;
+            Self::update_lock(token_id, &target);
+
+            Self::deposit_event(Event::VestedTransferred {
+                token_id,
+                from,
+                to: target,
+                schedule,
+            });
+
+            Ok(())
+        }
+
+        /// Release whatever the caller's vesting schedules have unlocked so far.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::vest())]
+        pub fn vest(_who: OriginFor<T>, token_id: T::TokenId) -> DispatchResult {
+            let who = ensure_signed(_who)?;
+
+            ensure!(
+                Vesting::<T>::contains_key(token_id, &who),
+                Error::<T>::NotVesting
+            );
+
+            let unvested = Self::update_lock(token_id, &who);
+
+            if unvested.is_zero() {
+                Self::deposit_event(Event::VestingCompleted { token_id, who });
+            } else {
+                Self::deposit_event(Event::VestingUpdated {
+                    token_id,
+                    who,
+                    unvested,
+                });
+            }
+
+            Ok(())
+        }
+
+        /// Record the current balances of `token_id` so they can be queried later
+        /// with `balance_of_at` and `total_supply_at`. Only the token owner may do this.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::snapshot())]
+        pub fn snapshot(_owner: OriginFor<T>, token_id: T::TokenId) -> DispatchResult {
+            let owner = ensure_signed(_owner)?;
+
+            let token = Tokens::<T>::get(token_id).ok_or(Error::<T>::NoneToken)?;
+            ensure!(token.owner == owner, Error::<T>::NoPermission);
+
+            Self::do_snapshot(token_id)?;
+
+            Ok(())
+        }
+
+        /// Give the voting power of the caller's `token_id` balance to `delegatee`.
+        /// Delegate to yourself to vote with your own balance.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::WeightInfo::delegate())]
+        pub fn delegate(
+            _delegator: OriginFor<T>,
+            token_id: T::TokenId,
+            _delegatee: AccountIdLookupOf<T>,
+        ) -> DispatchResult {
+            let delegator = ensure_signed(_delegator)?;
+            let delegatee = T::Lookup::lookup(_delegatee)?;
+
+            ensure!(Tokens::<T>::contains_key(token_id), Error::<T>::NoneToken);
+
+            let previous = Delegates::<T>::get(token_id, &delegator);
+            Delegates::<T>::insert(token_id, &delegator, &delegatee);
+
+            Self::deposit_event(Event::DelegateChanged {
+                token_id,
+                delegator: delegator.clone(),
+                from_delegate: previous.clone(),
+                to_delegate: delegatee.clone(),
+            });
+
+            Self::move_voting_power(
+                token_id,
+                previous.as_ref(),
+                Some(&delegatee),
+                Self::balance_of(token_id, &delegator),
+            );
+
+            Ok(())
+        }
+
+        /// Turn the holder allowlist of `token_id` on or off. Admin only.
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::WeightInfo::set_permissioned())]
+        pub fn set_permissioned(
+            _admin: OriginFor<T>,
+            token_id: T::TokenId,
+            permissioned: bool,
+        ) -> DispatchResult {
+            let admin = ensure_signed(_admin)?;
+
+            Tokens::<T>::try_mutate(token_id, |maybe_token| -> DispatchResult {
+                let token = maybe_token.as_mut().ok_or(Error::<T>::NoneToken)?;
+                ensure!(token.admin == admin, Error::<T>::NoPermission);
+
+                token.permissioned = permissioned;
+
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::PermissionedSet {
+                token_id,
+                permissioned,
+            });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::WeightInfo::add_holders(holders.len() as u32))]
+        pub fn add_holders(
+            _admin: OriginFor<T>,
+            token_id: T::TokenId,
+            holders: BoundedVec<AccountIdLookupOf<T>, T::MaxAllowlistBatch>,
+        ) -> DispatchResult {
+            let admin = ensure_signed(_admin)?;
+            Self::ensure_admin(token_id, &admin)?;
+
+            for holder in holders {
+                let who = T::Lookup::lookup(holder)?;
+
+                Holders::<T>::insert(token_id, &who, ());
+
+                Self::deposit_event(Event::HolderAdded { token_id, who });
+            }
+
+            Ok(())
+        }
+
+        /// Removing a holder does not touch their balance; they just can't receive more.
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::WeightInfo::remove_holders(holders.len() as u32))]
+        pub fn remove_holders(
+            _admin: OriginFor<T>,
+            token_id: T::TokenId,
+            holders: BoundedVec<AccountIdLookupOf<T>, T::MaxAllowlistBatch>,
+        ) -> DispatchResult {
+            let admin = ensure_signed(_admin)?;
+            Self::ensure_admin(token_id, &admin)?;
+
+            for holder in holders {
+                let who = T::Lookup::lookup(holder)?;
+
+                Holders::<T>::remove(token_id, &who);
+
+                Self::deposit_event(Event::HolderRemoved { token_id, who });
+            }
+
+            Ok(())
+        }
+
+        /// Create `amount` new tokens for `beneficiary`. Admin only.
+        #[pallet::call_index(11)]
+        #[pallet::weight(T::WeightInfo::issue())]
+        pub fn issue(
+            _admin: OriginFor<T>,
+            token_id: T::TokenId,
+            _beneficiary: AccountIdLookupOf<T>,
+            amount: T::TokenBalance,
+        ) -> DispatchResult {
+            let admin = ensure_signed(_admin)?;
+            let beneficiary = T::Lookup::lookup(_beneficiary)?;
+            Self::ensure_admin(token_id, &admin)?;
+
+            Self::do_issue(token_id, &beneficiary, amount)
+        }
+
+        /// Set or lower the supply cap of `token_id`. Owner only; a cap can never
+        /// be raised or removed once set.
+        #[pallet::call_index(12)]
+        #[pallet::weight(T::WeightInfo::set_max_supply())]
+        pub fn set_max_supply(
+            _owner: OriginFor<T>,
+            token_id: T::TokenId,
+            max_supply: T::TokenBalance,
+        ) -> DispatchResult {
+            let owner = ensure_signed(_owner)?;
+
+            Tokens::<T>::try_mutate(token_id, |maybe_token| -> DispatchResult {
+                let token = maybe_token.as_mut().ok_or(Error::<T>::NoneToken)?;
+                ensure!(token.owner == owner, Error::<T>::NoPermission);
+                ensure!(
+                    max_supply >= token.supply
+                        && token.max_supply.map_or(true, |current| max_supply <= current),
+                    Error::<T>::InvalidMaxSupply
+                );
+
+                token.max_supply = Some(max_supply);
+
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::MaxSupplySet {
+                token_id,
+                max_supply,
+            });
+
+            Ok(())
+        }
+
+        /// Emit `token_id` to `beneficiary` every block from `start` following `curve`.
+        /// Owner only. The beneficiary may be a pallet account.
+        #[pallet::call_index(13)]
+        #[pallet::weight(T::WeightInfo::create_emission())]
+        pub fn create_emission(
+            _owner: OriginFor<T>,
+            token_id: T::TokenId,
+            _beneficiary: AccountIdLookupOf<T>,
+            curve: EmissionCurveOf<T>,
+            start: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            let owner = ensure_signed(_owner)?;
+            let beneficiary = T::Lookup::lookup(_beneficiary)?;
+
+            let token = Tokens::<T>::get(token_id).ok_or(Error::<T>::NoneToken)?;
+            ensure!(token.owner == owner, Error::<T>::NoPermission);
+            ensure!(curve.is_valid(), Error::<T>::InvalidEmissionCurve);
+
+            let id = NextEmissionId::<T>::get();
+            let start = start.max(frame_system::Pallet::<T>::block_number());
+
+            EmissionQueue::<T>::try_mutate(|queue| {
+                queue
+                    .try_push(id)
+                    .map_err(|_| Error::<T>::TooManyEmissions)
+            })?;
+
+            EmissionSchedules::<T>::insert(
+                id,
+                EmissionSchedule {
+                    token_id,
+                    beneficiary: beneficiary.clone(),
+                    curve,
+                    start,
+                    last_emitted: start,
+                },
+            );
+            NextEmissionId::<T>::put(id.wrapping_add(1));
+
+            Self::deposit_event(Event::EmissionCreated {
+                id,
+                token_id,
+                beneficiary,
+            });
+
+            Ok(())
+        }
+
+        /// Stop an emission schedule. Whatever accrued since it was last processed
+        /// is not emitted. Owner only.
+        #[pallet::call_index(14)]
+        #[pallet::weight(T::WeightInfo::cancel_emission())]
+        pub fn cancel_emission(_owner: OriginFor<T>, id: EmissionId) -> DispatchResult {
+            let owner = ensure_signed(_owner)?;
+
+            let schedule = EmissionSchedules::<T>::get(id).ok_or(Error::<T>::NoneEmission)?;
+            let token = Tokens::<T>::get(schedule.token_id).ok_or(Error::<T>::NoneToken)?;
+            ensure!(token.owner == owner, Error::<T>::NoPermission);
+
+            Self::remove_emission(id);
+
+            Self::deposit_event(Event::EmissionCancelled { id });
+
+            Ok(())
+        }
+
+        /// Escrow `total` tokens for accounts listed in a Merkle tree. Each leaf is
+        /// `T::Hashing::hash_of(&(index, account, amount))`, and each parent is the
+        /// hash of its two children encoded smallest first.
+        #[pallet::call_index(15)]
+        #[pallet::weight(T::WeightInfo::create_airdrop())]
+        pub fn create_airdrop(
+            _creator: OriginFor<T>,
+            token_id: T::TokenId,
+            merkle_root: T::Hash,
+            total: T::TokenBalance,
+            expiry: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            let creator = ensure_signed(_creator)?;
+
+            ensure!(total > Zero::zero(), Error::<T>::NoneValue);
+            ensure!(
+                expiry > frame_system::Pallet::<T>::block_number(),
+                Error::<T>::InvalidExpiry
+            );
+
+            Self::_transfer(token_id, &creator, &Self::account_id(), total)?;
+
+            let id = NextAirdropId::<T>::get();
+            NextAirdropId::<T>::put(id.wrapping_add(1));
+
+            Airdrops::<T>::insert(
+                id,
+                Airdrop {
+                    creator: creator.clone(),
+                    token_id,
+                    merkle_root,
+                    remaining: total,
+                    expiry,
+                    claimed_words: 0,
+                },
+            );
+
+            Self::deposit_event(Event::AirdropCreated {
+                id,
+                token_id,
+                creator,
+                total,
+            });
+
+            Ok(())
+        }
+
+        /// Claim leaf `index` of an airdrop for the caller.
+        #[pallet::call_index(16)]
+        #[pallet::weight(T::WeightInfo::claim(proof.len() as u32))]
+        pub fn claim(
+            _who: OriginFor<T>,
+            airdrop_id: AirdropId,
+            index: u32,
+            amount: T::TokenBalance,
+            proof: BoundedVec<T::Hash, T::MaxProofLength>,
+        ) -> DispatchResult {
+            let who = ensure_signed(_who)?;
+
+            let mut airdrop = Airdrops::<T>::get(airdrop_id).ok_or(Error::<T>::NoneAirdrop)?;
+
+            ensure!(
+                frame_system::Pallet::<T>::block_number() < airdrop.expiry,
+                Error::<T>::AirdropExpired
+            );
+            ensure!(
+                !Self::is_claimed(airdrop_id, index),
+                Error::<T>::AlreadyClaimed
+            );
+
+            let leaf = T::Hashing::hash_of(&(index, &who, amount));
+            ensure!(
+                Self::verify_proof(airdrop.merkle_root, leaf, &proof),
+                Error::<T>::InvalidProof
+            );
+
+            airdrop.remaining = airdrop
+                .remaining
+                .checked_sub(&amount)
+                .ok_or(Error::<T>::InsufficientBalance)?;
+
+            let amount = Self::pay_out(airdrop.token_id, &airdrop.creator, &who, amount)?;
+
+            ClaimedBitmap::<T>::mutate(airdrop_id, index / 128, |word| {
+                if *word == 0 {
+                    airdrop.claimed_words = airdrop.claimed_words.saturating_add(1);
+                }
+
+                *word |= 1u128 << (index % 128)
+            });
+            Airdrops::<T>::insert(airdrop_id, airdrop);
+
+            Self::deposit_event(Event::Claimed {
+                id: airdrop_id,
+                index,
+                who,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        /// Return the unclaimed tokens to the creator once the airdrop expired.
+        /// `claimed_words` must be at least the airdrop's own `claimed_words`, the
+        /// number of bitmap entries this removes and is weighed by.
+        #[pallet::call_index(17)]
+        #[pallet::weight(T::WeightInfo::reclaim(*claimed_words))]
+        pub fn reclaim(
+            _creator: OriginFor<T>,
+            airdrop_id: AirdropId,
+            claimed_words: u32,
+        ) -> DispatchResult {
+            let creator = ensure_signed(_creator)?;
+
+            let airdrop = Airdrops::<T>::get(airdrop_id).ok_or(Error::<T>::NoneAirdrop)?;
+
+            ensure!(airdrop.creator == creator, Error::<T>::NoPermission);
+            ensure!(
+                airdrop.claimed_words <= claimed_words,
+                Error::<T>::BadWitness
+            );
+            ensure!(
+                frame_system::Pallet::<T>::block_number() >= airdrop.expiry,
+                Error::<T>::AirdropNotExpired
+            );
+
+            if !airdrop.remaining.is_zero() {
+                Self::_transfer(
+                    airdrop.token_id,
+                    &Self::account_id(),
+                    &creator,
+                    airdrop.remaining,
+                )?;
+            }
+
+            Airdrops::<T>::remove(airdrop_id);
+            let _ = ClaimedBitmap::<T>::clear_prefix(airdrop_id, airdrop.claimed_words, None);
+
+            Self::deposit_event(Event::AirdropReclaimed {
+                id: airdrop_id,
+                amount: airdrop.remaining,
+            });
+
+            Ok(())
+        }
+
+        /// Send `amount` to `to`, claimable from block `unlock_at`.
+        #[pallet::call_index(18)]
+        #[pallet::weight(T::WeightInfo::escrow_transfer())]
+        pub fn escrow_transfer(
+            _from: OriginFor<T>,
+            token_id: T::TokenId,
+            _to: AccountIdLookupOf<T>,
+            amount: T::TokenBalance,
+            unlock_at: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            let from = ensure_signed(_from)?;
+            let to = T::Lookup::lookup(_to)?;
+
+            ensure!(amount > Zero::zero(), Error::<T>::NoneValue);
+            ensure!(
+                unlock_at > frame_system::Pallet::<T>::block_number(),
+                Error::<T>::InvalidExpiry
+            );
+            // checked up front so the recipient can actually claim it later
+            Self::ensure_can_receive(token_id, &to)?;
+
+            Self::_transfer(token_id, &from, &Self::account_id(), amount)?;
+
+            let id = NextEscrowId::<T>::get();
+            NextEscrowId::<T>::put(id.wrapping_add(1));
+
+            Escrows::<T>::insert(
+                id,
+                Escrow {
+                    sender: from.clone(),
+                    recipient: to.clone(),
+                    token_id,
+                    amount,
+                    unlock_at,
+                },
+            );
+
+            Self::deposit_event(Event::EscrowCreated {
+                id,
+                token_id,
+                from,
+                to,
+                amount,
+                unlock_at,
+            });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(19)]
+        #[pallet::weight(T::WeightInfo::claim_escrow())]
+        pub fn claim_escrow(_recipient: OriginFor<T>, escrow_id: EscrowId) -> DispatchResult {
+            let recipient = ensure_signed(_recipient)?;
+
+            let escrow = Escrows::<T>::get(escrow_id).ok_or(Error::<T>::NoneEscrow)?;
+
+            ensure!(escrow.recipient == recipient, Error::<T>::NoPermission);
+            ensure!(
+                frame_system::Pallet::<T>::block_number() >= escrow.unlock_at,
+                Error::<T>::EscrowLocked
+            );
+
+            let amount = Self::pay_out(escrow.token_id, &escrow.sender, &recipient, escrow.amount)?;
+            Escrows::<T>::remove(escrow_id);
+
+            Self::deposit_event(Event::EscrowClaimed {
+                id: escrow_id,
+                who: recipient,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(20)]
+        #[pallet::weight(T::WeightInfo::cancel_escrow())]
+        pub fn cancel_escrow(_sender: OriginFor<T>, escrow_id: EscrowId) -> DispatchResult {
+            let sender = ensure_signed(_sender)?;
+
+            let escrow = Escrows::<T>::get(escrow_id).ok_or(Error::<T>::NoneEscrow)?;
+
+            ensure!(escrow.sender == sender, Error::<T>::NoPermission);
+            ensure!(
+                frame_system::Pallet::<T>::block_number() < escrow.unlock_at,
+                Error::<T>::EscrowUnlocked
+            );
+
+            Self::_transfer(escrow.token_id, &Self::account_id(), &sender, escrow.amount)?;
+            Escrows::<T>::remove(escrow_id);
+
+            Self::deposit_event(Event::EscrowCancelled {
+                id: escrow_id,
+                who: sender,
+                amount: escrow.amount,
+            });
+
+            Ok(())
+        }
+
+        /// Stream `rate_per_block` to `recipient` from `start` until `stop`. The
+        /// full amount is taken from the caller now.
+        #[pallet::call_index(21)]
+        #[pallet::weight(T::WeightInfo::create_stream())]
+        pub fn create_stream(
+            _sender: OriginFor<T>,
+            _recipient: AccountIdLookupOf<T>,
+            token_id: T::TokenId,
+            rate_per_block: T::TokenBalance,
+            start: BlockNumberFor<T>,
+            stop: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(_sender)?;
+            let recipient = T::Lookup::lookup(_recipient)?;
+
+            ensure!(
+                rate_per_block > Zero::zero()
+                    && start >= frame_system::Pallet::<T>::block_number()
+                    && stop > start,
+                Error::<T>::InvalidStream
+            );
+            Self::ensure_can_receive(token_id, &recipient)?;
+
+            let deposit = T::BlockNumberToBalance::convert(stop - start)
+                .checked_mul(&rate_per_block)
+                .ok_or(Error::<T>::TokenBalanceOverflow)?;
+
+            Self::_transfer(token_id, &sender, &Self::account_id(), deposit)?;
+
+            let id = NextStreamId::<T>::get();
+            NextStreamId::<T>::put(id.wrapping_add(1));
+
+            Streams::<T>::insert(
+                id,
+                Stream {
+                    sender: sender.clone(),
+                    recipient: recipient.clone(),
+                    token_id,
+                    rate_per_block,
+                    start,
+                    stop,
+                    deposit,
+                    withdrawn: Zero::zero(),
+                },
+            );
+
+            Self::deposit_event(Event::StreamCreated {
+                id,
+                token_id,
+                sender,
+                recipient,
+                deposit,
+            });
+
+            Ok(())
+        }
+
+        /// Pay the recipient everything the stream accrued so far.
+        #[pallet::call_index(22)]
+        #[pallet::weight(T::WeightInfo::withdraw_from_stream())]
+        pub fn withdraw_from_stream(_recipient: OriginFor<T>, stream_id: StreamId) -> DispatchResult {
+            let recipient = ensure_signed(_recipient)?;
+
+            let mut stream = Streams::<T>::get(stream_id).ok_or(Error::<T>::NoneStream)?;
+            ensure!(stream.recipient == recipient, Error::<T>::NoPermission);
+
+            let amount = stream.available_at(frame_system::Pallet::<T>::block_number());
+            ensure!(amount > Zero::zero(), Error::<T>::NothingToWithdraw);
+
+            let received = Self::pay_out(stream.token_id, &stream.sender, &recipient, amount)?;
+
+            stream.withdrawn = stream.withdrawn.saturating_add(amount);
+
+            if stream.withdrawn >= stream.deposit {
+                Streams::<T>::remove(stream_id);
+            } else {
+                Streams::<T>::insert(stream_id, stream);
+            }
+
+            Self::deposit_event(Event::WithdrawnFromStream {
+                id: stream_id,
+                recipient,
+                amount: received,
+            });
+
+            Ok(())
+        }
+
+        /// End the stream now. Either party may cancel.
+        #[pallet::call_index(23)]
+        #[pallet::weight(T::WeightInfo::cancel_stream())]
+        pub fn cancel_stream(_who: OriginFor<T>, stream_id: StreamId) -> DispatchResult {
+            let who = ensure_signed(_who)?;
+
+            let stream = Streams::<T>::get(stream_id).ok_or(Error::<T>::NoneStream)?;
+            ensure!(
+                who == stream.sender || who == stream.recipient,
+                Error::<T>::NoPermission
+            );
+
+            let now = frame_system::Pallet::<T>::block_number();
+            let mut recipient_amount = stream.available_at(now);
+            let sender_amount = stream.deposit.saturating_sub(stream.accrued_at(now));
+
+            if !recipient_amount.is_zero() {
+                recipient_amount = Self::pay_out(
+                    stream.token_id,
+                    &stream.sender,
+                    &stream.recipient,
+                    recipient_amount,
+                )?;
+            }
+
+            if !sender_amount.is_zero() {
+                Self::_transfer(
+                    stream.token_id,
+                    &Self::account_id(),
+                    &stream.sender,
+                    sender_amount,
+                )?;
+            }
+
+            Streams::<T>::remove(stream_id);
+
+            Self::deposit_event(Event::StreamCancelled {
+                id: stream_id,
+                recipient_amount,
+                sender_amount,
+            });
+
+            Ok(())
+        }
+
+        /// Move `amount` of `token_id` from `source` to `dest` without `source`'s consent.
+        /// Only balances, snapshots and votes change: locks, the allowlist, rate limits,
+        /// fees and the transfer hooks are all bypassed.
+        #[pallet::call_index(24)]
+        #[pallet::weight(T::WeightInfo::force_transfer())]
+        pub fn force_transfer(
+            origin: OriginFor<T>,
+            token_id: T::TokenId,
+            _source: AccountIdLookupOf<T>,
+            _dest: AccountIdLookupOf<T>,
+            amount: T::TokenBalance,
+        ) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+            let source = T::Lookup::lookup(_source)?;
+            let dest = T::Lookup::lookup(_dest)?;
+
+            ensure!(Tokens::<T>::contains_key(token_id), Error::<T>::NoneToken);
+
+            Self::move_balance(token_id, &source, &dest, amount)?;
+
+            Self::deposit_event(Event::ForceTransferred {
+                token_id,
+                from: source,
+                to: dest,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        /// Overwrite the free balance of `who`, adjusting the supply by the difference.
+        /// An increase must stay within `max_supply`.
+        #[pallet::call_index(25)]
+        #[pallet::weight(T::WeightInfo::force_set_balance())]
+        pub fn force_set_balance(
+            origin: OriginFor<T>,
+            token_id: T::TokenId,
+            _who: AccountIdLookupOf<T>,
+            new_balance: T::TokenBalance,
+        ) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+            let who = T::Lookup::lookup(_who)?;
+
+            let old_balance = Self::do_set_balance(token_id, &who, new_balance)?;
+
+            Self::deposit_event(Event::BalanceForceSet {
+                token_id,
+                who,
+                old_balance,
+                new_balance,
+            });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(26)]
+        #[pallet::weight(T::WeightInfo::force_set_metadata())]
+        pub fn force_set_metadata(
+            origin: OriginFor<T>,
+            token_id: T::TokenId,
+            name: BoundedVec<u8, T::MaxTokenNameLen>,
+            symbol: BoundedVec<u8, T::MaxTokenSymbolLen>,
+        ) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+
+            Tokens::<T>::try_mutate(token_id, |maybe_token| -> DispatchResult {
+                let token = maybe_token.as_mut().ok_or(Error::<T>::NoneToken)?;
+
+                token.name = name.clone();
+                token.symbol = symbol.clone();
+
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::MetadataForceSet {
+                token_id,
+                name,
+                symbol,
+            });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(27)]
+        #[pallet::weight(T::WeightInfo::force_set_team())]
+        pub fn force_set_team(
+            origin: OriginFor<T>,
+            token_id: T::TokenId,
+            _owner: AccountIdLookupOf<T>,
+            _admin: AccountIdLookupOf<T>,
+        ) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+            let owner = T::Lookup::lookup(_owner)?;
+            let admin = T::Lookup::lookup(_admin)?;
+
+            Tokens::<T>::try_mutate(token_id, |maybe_token| -> DispatchResult {
+                let token = maybe_token.as_mut().ok_or(Error::<T>::NoneToken)?;
+
+                token.owner = owner.clone();
+                token.admin = admin.clone();
+
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::TeamForceSet {
+                token_id,
+                owner,
+                admin,
+            });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(28)]
+        #[pallet::weight(T::WeightInfo::force_cancel_approval())]
+        pub fn force_cancel_approval(
+            origin: OriginFor<T>,
+            token_id: T::TokenId,
+            _owner: AccountIdLookupOf<T>,
+            _spender: AccountIdLookupOf<T>,
+        ) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+            let owner = T::Lookup::lookup(_owner)?;
+            let spender = T::Lookup::lookup(_spender)?;
+
+            ensure!(
+                !Self::allowance((token_id, &owner, &spender)).is_zero(),
+                Error::<T>::NotApproved
+            );
+
+            Self::put_allowance(token_id, &owner, &spender, Zero::zero());
+
+            Self::deposit_event(Event::ApprovalForceCancelled {
+                token_id,
+                owner,
+                spender,
+            });
+
+            Ok(())
+        }
+
+        /// Let `operator` spend every token of the caller through `transfer_from`,
+        /// or revoke that with `approved: false`. Allowances are left untouched.
+        #[pallet::call_index(29)]
+        #[pallet::weight(T::WeightInfo::set_operator())]
+        pub fn set_operator(
+            _owner: OriginFor<T>,
+            _operator: AccountIdLookupOf<T>,
+            approved: bool,
+        ) -> DispatchResult {
+            let owner = ensure_signed(_owner)?;
+            let operator = T::Lookup::lookup(_operator)?;
+
+            if approved {
+                Operators::<T>::insert(&owner, &operator, ());
+            } else {
+                Operators::<T>::remove(&owner, &operator);
+            }
+
+            Self::deposit_event(Event::OperatorSet {
+                owner,
+                operator,
+                approved,
+            });
+
+            Ok(())
+        }
+
+        /// Move several tokens from `from` to `to` at once; either all of them move
+        /// or none do. The caller needs to be `from`, an operator of `from`, or hold
+        /// an allowance for each token.
+        #[pallet::call_index(30)]
+        #[pallet::weight(T::WeightInfo::safe_batch_transfer_from(transfers.len() as u32))]
+        pub fn safe_batch_transfer_from(
+            _operator: OriginFor<T>,
+            _from: AccountIdLookupOf<T>,
+            _to: AccountIdLookupOf<T>,
+            transfers: BoundedVec<(T::TokenId, T::TokenBalance), T::MaxBatchTransfers>,
+        ) -> DispatchResult {
+            let operator = ensure_signed(_operator)?;
+            let from = T::Lookup::lookup(_from)?;
+            let to = T::Lookup::lookup(_to)?;
+
+            let mut transfers = transfers;
+
+            for (token_id, amount) in transfers.iter_mut() {
+                if operator != from {
+                    Self::spend_allowance(*token_id, &from, &operator, *amount)?;
+                }
+
+                *amount = Self::_transfer(*token_id, &from, &to, *amount)?;
+            }
+
+            Self::deposit_event(Event::TransferredBatch {
+                operator,
+                from,
+                to,
+                transfers,
+            });
+
+            Ok(())
+        }
+
+        /// `transfer` with a reference for the recipient to match the payment
+        /// against, e.g. an invoice number.
+        #[pallet::call_index(31)]
+        #[pallet::weight(T::WeightInfo::transfer_with_memo(memo.len() as u32))]
+        pub fn transfer_with_memo(
+            _from: OriginFor<T>,
+            _to: AccountIdLookupOf<T>,
+            token_id: T::TokenId,
+            amount: T::TokenBalance,
+            memo: BoundedVec<u8, T::MaxMemoLen>,
+        ) -> DispatchResult {
+            let from = ensure_signed(_from)?;
+            let to = T::Lookup::lookup(_to)?;
+
+            let amount = Self::_transfer(token_id, &from, &to, amount)?;
+
+            Self::deposit_event(Event::TransferredWithMemo {
+                token_id,
+                from,
+                to,
+                amount,
+                memo,
+            });
+
+            Ok(())
+        }
+
+        /// Limit how much of `token_id` may leave any one account, or lift the limit
+        /// with `None`. Admin only. Usage is kept until its window ends, so lifting
+        /// and setting a limit again doesn't reset it.
+        #[pallet::call_index(32)]
+        #[pallet::weight(T::WeightInfo::set_rate_limit())]
+        pub fn set_rate_limit(
+            _admin: OriginFor<T>,
+            token_id: T::TokenId,
+            limit: Option<RateLimitOf<T>>,
+        ) -> DispatchResult {
+            let admin = ensure_signed(_admin)?;
+            Self::ensure_admin(token_id, &admin)?;
+
+            match limit {
+                Some(limit) => {
+                    ensure!(limit.is_valid(), Error::<T>::InvalidRateLimit);
+                    RateLimits::<T>::insert(token_id, limit);
+                },
+                None => RateLimits::<T>::remove(token_id),
+            }
+
+            Self::deposit_event(Event::RateLimitSet { token_id, limit });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(33)]
+        #[pallet::weight(T::WeightInfo::add_rate_limit_exemptions(accounts.len() as u32))]
+        pub fn add_rate_limit_exemptions(
+            _admin: OriginFor<T>,
+            token_id: T::TokenId,
+            accounts: BoundedVec<AccountIdLookupOf<T>, T::MaxAllowlistBatch>,
+        ) -> DispatchResult {
+            let admin = ensure_signed(_admin)?;
+            Self::ensure_admin(token_id, &admin)?;
+
+            for account in accounts {
+                let who = T::Lookup::lookup(account)?;
+
+                RateLimitExempt::<T>::insert(token_id, &who, ());
+
+                Self::deposit_event(Event::RateLimitExemptionAdded { token_id, who });
+            }
+
+            Ok(())
+        }
+
+        #[pallet::call_index(34)]
+        #[pallet::weight(T::WeightInfo::remove_rate_limit_exemptions(accounts.len() as u32))]
+        pub fn remove_rate_limit_exemptions(
+            _admin: OriginFor<T>,
+            token_id: T::TokenId,
+            accounts: BoundedVec<AccountIdLookupOf<T>, T::MaxAllowlistBatch>,
+        ) -> DispatchResult {
+            let admin = ensure_signed(_admin)?;
+            Self::ensure_admin(token_id, &admin)?;
+
+            for account in accounts {
+                let who = T::Lookup::lookup(account)?;
+
+                RateLimitExempt::<T>::remove(token_id, &who);
+
+                Self::deposit_event(Event::RateLimitExemptionRemoved { token_id, who });
+            }
+
+            Ok(())
+        }
+
+        /// Take `rate` of every transfer of `token_id` for `beneficiary`, or stop with
+        /// `None`. Admin only. On a permissioned token the beneficiary must be a holder.
+        #[pallet::call_index(35)]
+        #[pallet::weight(T::WeightInfo::set_transfer_fee())]
+        pub fn set_transfer_fee(
+            _admin: OriginFor<T>,
+            token_id: T::TokenId,
+            fee: Option<(Permill, AccountIdLookupOf<T>)>,
+        ) -> DispatchResult {
+            let admin = ensure_signed(_admin)?;
+
+            let fee = match fee {
+                Some((rate, beneficiary)) => Some(TransferFee {
+                    rate,
+                    beneficiary: T::Lookup::lookup(beneficiary)?,
+                }),
+                None => None,
+            };
+
+            Tokens::<T>::try_mutate(token_id, |maybe_token| -> DispatchResult {
+                let token = maybe_token.as_mut().ok_or(Error::<T>::NoneToken)?;
+                ensure!(token.admin == admin, Error::<T>::NoPermission);
+
+                if let Some(fee) = fee.as_ref() {
+                    ensure!(
+                        !token.permissioned || Self::is_allowlisted(token_id, &fee.beneficiary),
+                        Error::<T>::NotAllowlisted
+                    );
+                }
+
+                token.transfer_fee = fee.clone();
+
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::TransferFeeSet { token_id, fee });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(36)]
+        #[pallet::weight(T::WeightInfo::add_fee_exemptions(accounts.len() as u32))]
+        pub fn add_fee_exemptions(
+            _admin: OriginFor<T>,
+            token_id: T::TokenId,
+            accounts: BoundedVec<AccountIdLookupOf<T>, T::MaxAllowlistBatch>,
+        ) -> DispatchResult {
+            let admin = ensure_signed(_admin)?;
+            Self::ensure_admin(token_id, &admin)?;
+
+            for account in accounts {
+                let who = T::Lookup::lookup(account)?;
+
+                FeeExempt::<T>::insert(token_id, &who, ());
+
+                Self::deposit_event(Event::FeeExemptionAdded { token_id, who });
+            }
+
+            Ok(())
+        }
+
+        #[pallet::call_index(37)]
+        #[pallet::weight(T::WeightInfo::remove_fee_exemptions(accounts.len() as u32))]
+        pub fn remove_fee_exemptions(
+            _admin: OriginFor<T>,
+            token_id: T::TokenId,
+            accounts: BoundedVec<AccountIdLookupOf<T>, T::MaxAllowlistBatch>,
+        ) -> DispatchResult {
+            let admin = ensure_signed(_admin)?;
+            Self::ensure_admin(token_id, &admin)?;
+
+            for account in accounts {
+                let who = T::Lookup::lookup(account)?;
+
+                FeeExempt::<T>::remove(token_id, &who);
+
+                Self::deposit_event(Event::FeeExemptionRemoved { token_id, who });
+            }
+
+            Ok(())
+        }
     }
 
-    impl<T: Config> Pallet<T> {
-        pub fn _transfer(
+    impl<T: Config> Pallet<T> {
+        pub fn do_mint(
+            owner: T::AccountId,
+            name: BoundedVec<u8, T::MaxTokenNameLen>,
+            symbol: BoundedVec<u8, T::MaxTokenSymbolLen>,
+            supply: T::TokenBalance,
+            max_supply: Option<T::TokenBalance>,
+        ) -> Result<T::TokenId, DispatchError> {
+            ensure!(supply > Zero::zero(), Error::<T>::NoneValue);
+            ensure!(
+                max_supply.map_or(true, |max| supply <= max),
+                Error::<T>::MaxSupplyExceeded
+            );
+
+            let token_id = Self::next_token_id()?;
+
+            ensure!(
+                !Tokens::<T>::contains_key(token_id),
+                Error::<T>::TokenExists
+            );
+
+            let details = TokenDetails::new(owner.clone(), name, symbol, supply, max_supply);
+
+            LastTokenId::<T>::put(token_id);
+            Tokens::<T>::insert(token_id, details);
+            Self::put_balance(token_id, &owner, supply);
+            Self::move_voting_power(
+                token_id,
+                None,
+                Delegates::<T>::get(token_id, &owner).as_ref(),
+                supply,
+            );
+
+            Self::deposit_event(Event::TokenMinted {
+                token_id,
+                who: owner,
+            });
+
+            Ok(token_id)
+        }
+
+        /// Register a token with no supply. It only grows through `do_issue`.
+        pub fn do_create(
+            owner: T::AccountId,
+            name: BoundedVec<u8, T::MaxTokenNameLen>,
+            symbol: BoundedVec<u8, T::MaxTokenSymbolLen>,
+        ) -> Result<T::TokenId, DispatchError> {
+            let token_id = Self::next_token_id()?;
+
+            ensure!(
+                !Tokens::<T>::contains_key(token_id),
+                Error::<T>::TokenExists
+            );
+
+            let details = TokenDetails::new(owner.clone(), name, symbol, Zero::zero(), None);
+
+            LastTokenId::<T>::put(token_id);
+            Tokens::<T>::insert(token_id, details);
+
+            Self::deposit_event(Event::TokenMinted {
+                token_id,
+                who: owner,
+            });
+
+            Ok(token_id)
+        }
+
+        /// The id the next minted token will get.
+        pub fn next_token_id() -> Result<T::TokenId, DispatchError> {
+            LastTokenId::<T>::get()
+                .checked_add(&One::one())
+                .ok_or(Error::<T>::TokenIdOverflow.into())
+        }
+
+        /// Every path that creates tokens of an existing token id goes through here,
+        /// so the allowlist and `max_supply` are always enforced.
+        pub fn do_issue(
+            token_id: T::TokenId,
+            to: &T::AccountId,
+            amount: T::TokenBalance,
+        ) -> DispatchResult {
+            Self::ensure_can_receive(token_id, to)?;
+
+            let token = Tokens::<T>::get(token_id).ok_or(Error::<T>::NoneToken)?;
+            let supply = token
+                .supply
+                .checked_add(&amount)
+                .ok_or(Error::<T>::TokenBalanceOverflow)?;
+
+            ensure!(
+                token.max_supply.map_or(true, |max| supply <= max),
+                Error::<T>::MaxSupplyExceeded
+            );
+
+            Self::update_supply_snapshot(token_id);
+            Self::update_account_snapshot(token_id, to);
+
+            let balance = Self::balance_of(token_id, to)
+                .checked_add(&amount)
+                .ok_or(Error::<T>::TokenBalanceOverflow)?;
+
+            Self::put_balance(token_id, to, balance);
+            Tokens::<T>::mutate(token_id, |maybe_token| {
+                if let Some(token) = maybe_token {
+                    token.supply = supply;
+                }
+            });
+
+            Self::move_voting_power(
+                token_id,
+                None,
+                Delegates::<T>::get(token_id, to).as_ref(),
+                amount,
+            );
+
+            Self::deposit_event(Event::Issued {
+                token_id,
+                to: to.clone(),
+                amount,
+            });
+
+            Ok(())
+        }
+
+        /// Destroy `amount` of the free balance of `from`, lowering the supply.
+        /// Locked tokens can't be burned.
+        pub fn do_burn(
+            token_id: T::TokenId,
+            from: &T::AccountId,
+            amount: T::TokenBalance,
+        ) -> DispatchResult {
+            ensure!(Tokens::<T>::contains_key(token_id), Error::<T>::NoneToken);
+
+            let locked = Locked::<T>::get(token_id, from);
+
+            Self::update_supply_snapshot(token_id);
+            Self::update_account_snapshot(token_id, from);
+
+            let balance = Self::balance_of(token_id, from)
+                .checked_sub(&amount)
+                .ok_or(Error::<T>::InsufficientBalance)?;
+
+            ensure!(balance >= locked, Error::<T>::BalanceLocked);
+
+            Self::put_balance(token_id, from, balance);
+            Tokens::<T>::mutate(token_id, |maybe_token| {
+                if let Some(token) = maybe_token {
+                    token.supply = token.supply.saturating_sub(amount);
+                }
+            });
+
+            Self::move_voting_power(
+                token_id,
+                Delegates::<T>::get(token_id, from).as_ref(),
+                None,
+                amount,
+            );
+
+            Self::deposit_event(Event::Burned {
+                token_id,
+                from: from.clone(),
+                amount,
+            });
+
+            Ok(())
+        }
+
+        /// Set the free balance of `who` to `new_balance` and move the supply by the
+        /// difference. Returns the previous balance.
+        fn do_set_balance(
+            token_id: T::TokenId,
+            who: &T::AccountId,
+            new_balance: T::TokenBalance,
+        ) -> Result<T::TokenBalance, DispatchError> {
+            let token = Tokens::<T>::get(token_id).ok_or(Error::<T>::NoneToken)?;
+            let old_balance = Self::balance_of(token_id, who);
+            let delegate = Delegates::<T>::get(token_id, who);
+
+            let supply = if new_balance >= old_balance {
+                let increase = new_balance - old_balance;
+                let supply = token
+                    .supply
+                    .checked_add(&increase)
+                    .ok_or(Error::<T>::TokenBalanceOverflow)?;
+
+                ensure!(
+                    token.max_supply.map_or(true, |max| supply <= max),
+                    Error::<T>::MaxSupplyExceeded
+                );
+
+                Self::move_voting_power(token_id, None, delegate.as_ref(), increase);
+
+                supply
+            } else {
+                let decrease = old_balance - new_balance;
+
+                Self::move_voting_power(token_id, delegate.as_ref(), None, decrease);
+
+                token.supply.saturating_sub(decrease)
+            };
+
+            Self::update_supply_snapshot(token_id);
+            Self::update_account_snapshot(token_id, who);
+
+            Self::put_balance(token_id, who, new_balance);
+            Tokens::<T>::mutate(token_id, |maybe_token| {
+                if let Some(token) = maybe_token {
+                    token.supply = supply;
+                }
+            });
+
+            Ok(old_balance)
+        }
+
+        /// The supply cap of `token_id`, if it has one.
+        pub fn max_supply(token_id: T::TokenId) -> Option<T::TokenBalance> {
+            Tokens::<T>::get(token_id).and_then(|token| token.max_supply)
+        }
+
+        /// Move `amount` from `from` to `to`, less the token's transfer fee unless one
+        /// of them is exempt. Returns what `to` received.
+        pub fn _transfer(
+            token_id: T::TokenId,
+            from: &T::AccountId,
+            to: &T::AccountId,
+            amount: T::TokenBalance,
+        ) -> Result<T::TokenBalance, DispatchError> {
+            let fee = Self::transfer_fee(token_id, from, to);
+
+            Self::do_transfer(token_id, from, to, amount, fee)
+        }
+
+        /// Pay `amount` that `payer` parked in the pallet's account out to `to`. This
+        /// is where the fee of an escrow, stream or airdrop is charged, between the
+        /// two people on either end of it. Returns what `to` received.
+        fn pay_out(
+            token_id: T::TokenId,
+            payer: &T::AccountId,
+            to: &T::AccountId,
+            amount: T::TokenBalance,
+        ) -> Result<T::TokenBalance, DispatchError> {
+            let fee = Self::transfer_fee(token_id, payer, to);
+
+            Self::do_transfer(token_id, &Self::account_id(), to, amount, fee)
+        }
+
+        /// `_transfer` with the fee already worked out.
+        fn do_transfer(
             token_id: T::TokenId,
             from: &T::AccountId,
             to: &T::AccountId,
             amount: T::TokenBalance,
-        ) -> Result<(), DispatchError> {
-            BalanceOf::<T>::try_mutate(token_id, &from, |balance| -> DispatchResult {
-                *balance = balance
-                    .checked_sub(&amount)
-                    .ok_or(Error::<T>::InsufficientBalance)?;
+            fee: Option<TransferFee<T::AccountId>>,
+        ) -> Result<T::TokenBalance, DispatchError> {
+            T::TransferFilter::filter(&token_id, from, to, &amount)?;
+            Self::ensure_can_receive(token_id, to)?;
+            Self::track_outflow(token_id, from, amount)?;
 
-                Ok(())
-            })?;
+            let locked = Locked::<T>::get(token_id, from);
 
-            BalanceOf::<T>::try_mutate(token_id, &to, |balance| -> DispatchResult {
-                *balance = balance
-                    .checked_add(&amount)
-                    .ok_or(Error::<T>::TokenBalanceOverflow)?;
+            let from_balance = Self::balance_of(token_id, from)
+                .checked_sub(&amount)
+                .ok_or(Error::<T>::InsufficientBalance)?;
 
-                Ok(())
+            ensure!(from_balance >= locked, Error::<T>::BalanceLocked);
+
+            let fee_amount = fee.as_ref().map_or_else(Zero::zero, |fee| fee.fee_on(amount));
+            let net = amount - fee_amount;
+
+            // the fee is a transfer to the beneficiary too
+            if let Some(TransferFee { beneficiary, .. }) = fee.as_ref() {
+                if !fee_amount.is_zero() {
+                    T::TransferFilter::filter(&token_id, from, beneficiary, &fee_amount)?;
+                    Self::ensure_can_receive(token_id, beneficiary)?;
+                }
+            }
+
+            Self::move_balance(token_id, from, to, net)?;
+
+            T::OnTransfer::on_transfer(&token_id, from, to, &net);
+
+            if let Some(TransferFee { beneficiary, .. }) = fee {
+                if !fee_amount.is_zero() {
+                    Self::move_balance(token_id, from, &beneficiary, fee_amount)?;
+
+                    T::OnTransfer::on_transfer(&token_id, from, &beneficiary, &fee_amount);
+                }
+
+                Self::deposit_event(Event::TransferFeeCharged {
+                    token_id,
+                    from: from.clone(),
+                    to: to.clone(),
+                    beneficiary,
+                    gross: amount,
+                    fee: fee_amount,
+                    net,
+                });
+            }
+
+            Ok(net)
+        }
+
+        /// Move `amount` of the free balance of `from` to `to`, keeping snapshots and
+        /// voting power in step. Nothing else is checked, see `_transfer` for that.
+        fn move_balance(
+            token_id: T::TokenId,
+            from: &T::AccountId,
+            to: &T::AccountId,
+            amount: T::TokenBalance,
+        ) -> DispatchResult {
+            Self::update_account_snapshot(token_id, from);
+            Self::update_account_snapshot(token_id, to);
+
+            let from_balance = Self::balance_of(token_id, from)
+                .checked_sub(&amount)
+                .ok_or(Error::<T>::InsufficientBalance)?;
+
+            Self::put_balance(token_id, from, from_balance);
+
+            // read after the write above, so a transfer to self nets out
+            let to_balance = Self::balance_of(token_id, to)
+                .checked_add(&amount)
+                .ok_or(Error::<T>::TokenBalanceOverflow)?;
+
+            Self::put_balance(token_id, to, to_balance);
+
+            Self::move_voting_power(
+                token_id,
+                Delegates::<T>::get(token_id, from).as_ref(),
+                Delegates::<T>::get(token_id, to).as_ref(),
+                amount,
+            );
+
+            Ok(())
+        }
+
+        /// The fee a transfer from `from` to `to` pays, if any. Exempt accounts don't
+        /// pay, and neither do transfers into or back out of the pallet's account:
+        /// escrows, streams and airdrops pay once, in `pay_out`.
+        pub fn transfer_fee(
+            token_id: T::TokenId,
+            from: &T::AccountId,
+            to: &T::AccountId,
+        ) -> Option<TransferFee<T::AccountId>> {
+            let fee = Tokens::<T>::get(token_id)?.transfer_fee?;
+            let pallet = Self::account_id();
+
+            let exempt = [from, to]
+                .into_iter()
+                .any(|who| *who == pallet || FeeExempt::<T>::contains_key(token_id, who));
+
+            (!exempt).then_some(fee)
+        }
+
+        /// Count `amount` against the rate limit of `from`, if `token_id` has one.
+        /// Exempt accounts and the pallet's own account, whose payouts are already
+        /// bounded by escrows, airdrops and streams, aren't tracked.
+        fn track_outflow(
+            token_id: T::TokenId,
+            from: &T::AccountId,
+            amount: T::TokenBalance,
+        ) -> DispatchResult {
+            let limit = match RateLimits::<T>::get(token_id) {
+                Some(limit) => limit,
+                None => return Ok(()),
+            };
+
+            if RateLimitExempt::<T>::contains_key(token_id, from) || *from == Self::account_id() {
+                return Ok(());
+            }
+
+            let now = frame_system::Pallet::<T>::block_number();
+            let outflows = limit
+                .track(Outflows::<T>::get(token_id, from), amount, now)
+                .ok_or(Error::<T>::RateLimited)?;
+
+            Outflows::<T>::insert(token_id, from, outflows);
+
+            Ok(())
+        }
+
+        /// Recompute the vesting lock of `who` at the current block, dropping
+        /// schedules that have fully vested. Returns the amount still locked.
+        pub fn update_lock(token_id: T::TokenId, who: &T::AccountId) -> T::TokenBalance {
+            let now = frame_system::Pallet::<T>::block_number();
+            let mut schedules = Vesting::<T>::get(token_id, who);
+
+            schedules.retain(|s| !s.locked_at::<T::BlockNumberToBalance>(now).is_zero());
+
+            let locked = schedules.iter().fold(Zero::zero(), |acc: T::TokenBalance, s| {
+                acc.saturating_add(s.locked_at::<T::BlockNumberToBalance>(now))
+            });
+
+            if schedules.is_empty() {
+                Vesting::<T>::remove(token_id, who);
+                Locked::<T>::remove(token_id, who);
+            } else {
+                Vesting::<T>::insert(token_id, who, schedules);
+                Locked::<T>::insert(token_id, who, locked);
+            }
+
+            locked
+        }
+
+        /// Move `amount` of `who`'s spendable balance on hold under `reason`.
+        pub fn hold(
+            token_id: T::TokenId,
+            who: &T::AccountId,
+            reason: &T::RuntimeHoldReason,
+            amount: T::TokenBalance,
+        ) -> DispatchResult {
+            <Self as MutateHold<T::AccountId>>::hold(token_id, reason, who, amount)?;
+
+            Self::deposit_event(Event::Held {
+                token_id,
+                who: who.clone(),
+                reason: *reason,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        /// Return `amount` held under `reason` to `who`'s spendable balance.
+        pub fn release(
+            token_id: T::TokenId,
+            who: &T::AccountId,
+            reason: &T::RuntimeHoldReason,
+            amount: T::TokenBalance,
+        ) -> Result<T::TokenBalance, DispatchError> {
+            let released = <Self as MutateHold<T::AccountId>>::release(
+                token_id,
+                reason,
+                who,
+                amount,
+                Precision::Exact,
+            )?;
+
+            Self::deposit_event(Event::Released {
+                token_id,
+                who: who.clone(),
+                reason: *reason,
+                amount: released,
+            });
+
+            Ok(released)
+        }
+
+        /// Burn up to `amount` held under `reason`. Returns what was actually burned.
+        pub fn slash_held(
+            token_id: T::TokenId,
+            who: &T::AccountId,
+            reason: &T::RuntimeHoldReason,
+            amount: T::TokenBalance,
+        ) -> Result<T::TokenBalance, DispatchError> {
+            let slashed = <Self as MutateHold<T::AccountId>>::burn_held(
+                token_id,
+                reason,
+                who,
+                amount,
+                Precision::BestEffort,
+                Fortitude::Force,
+            )?;
+
+            Self::deposit_event(Event::SlashedHeld {
+                token_id,
+                who: who.clone(),
+                reason: *reason,
+                amount: slashed,
+            });
+
+            Ok(slashed)
+        }
+
+        /// Move `amount` held under `reason` from `source` to `dest`. With `on_hold`
+        /// the funds stay held under the same reason, otherwise they land in `dest`'s
+        /// spendable balance.
+        pub fn transfer_on_hold(
+            token_id: T::TokenId,
+            reason: &T::RuntimeHoldReason,
+            source: &T::AccountId,
+            dest: &T::AccountId,
+            amount: T::TokenBalance,
+            on_hold: bool,
+        ) -> Result<T::TokenBalance, DispatchError> {
+            Self::ensure_can_receive(token_id, dest)?;
+
+            let mode = if on_hold {
+                Restriction::OnHold
+            } else {
+                Restriction::Free
+            };
+
+            let transferred = <Self as MutateHold<T::AccountId>>::transfer_on_hold(
+                token_id,
+                reason,
+                source,
+                dest,
+                amount,
+                Precision::Exact,
+                mode,
+                Fortitude::Polite,
+            )?;
+
+            Self::deposit_event(Event::TransferredOnHold {
+                token_id,
+                reason: *reason,
+                from: source.clone(),
+                to: dest.clone(),
+                amount: transferred,
+            });
+
+            Ok(transferred)
+        }
+
+        /// Take a new snapshot of `token_id`. Other pallets (e.g. governance) can
+        /// call this directly when a proposal is created.
+        pub fn do_snapshot(token_id: T::TokenId) -> Result<SnapshotId, DispatchError> {
+            ensure!(Tokens::<T>::contains_key(token_id), Error::<T>::NoneToken);
+
+            let id = CurrentSnapshotId::<T>::try_mutate(token_id, |id| {
+                *id = id.checked_add(1).ok_or(Error::<T>::StorageOverflow)?;
+
+                Ok::<_, DispatchError>(*id)
             })?;
 
+            Self::deposit_event(Event::Snapshot { token_id, id });
+
+            Ok(id)
+        }
+
+        /// The free balance `who` had when `snapshot_id`, one of the latest
+        /// `MaxSnapshots`, was taken.
+        pub fn balance_of_at(
+            token_id: T::TokenId,
+            who: &T::AccountId,
+            snapshot_id: SnapshotId,
+        ) -> Result<T::TokenBalance, DispatchError> {
+            Self::ensure_snapshot_taken(token_id, snapshot_id)?;
+
+            let snapshots = AccountSnapshots::<T>::get(token_id, who);
+
+            Ok(Self::value_at(&snapshots, snapshot_id)
+                .unwrap_or_else(|| Self::balance_of(token_id, who)))
+        }
+
+        /// The supply of `token_id` when `snapshot_id` was taken.
+        pub fn total_supply_at(
+            token_id: T::TokenId,
+            snapshot_id: SnapshotId,
+        ) -> Result<T::TokenBalance, DispatchError> {
+            Self::ensure_snapshot_taken(token_id, snapshot_id)?;
+
+            let snapshots = SupplySnapshots::<T>::get(token_id);
+
+            match Self::value_at(&snapshots, snapshot_id) {
+                Some(supply) => Ok(supply),
+                None => Ok(Tokens::<T>::get(token_id)
+                    .ok_or(Error::<T>::NoneToken)?
+                    .supply),
+            }
+        }
+
+        /// Must be called before `who`'s balance of `token_id` changes.
+        pub(crate) fn update_account_snapshot(token_id: T::TokenId, who: &T::AccountId) {
+            let current = CurrentSnapshotId::<T>::get(token_id);
+
+            if current == 0 {
+                return;
+            }
+
+            AccountSnapshots::<T>::mutate(token_id, who, |snapshots| {
+                if snapshots.last().map_or(true, |(id, _)| *id < current) {
+                    Self::prune_snapshots(snapshots, current);
+                    let _ = snapshots.try_push((current, Self::balance_of(token_id, who)));
+                }
+            });
+        }
+
+        /// Must be called before the supply of `token_id` changes.
+        pub(crate) fn update_supply_snapshot(token_id: T::TokenId) {
+            let current = CurrentSnapshotId::<T>::get(token_id);
+
+            if current == 0 {
+                return;
+            }
+
+            SupplySnapshots::<T>::mutate(token_id, |snapshots| {
+                if snapshots.last().map_or(true, |(id, _)| *id < current) {
+                    let supply = Tokens::<T>::get(token_id)
+                        .map(|details| details.supply)
+                        .unwrap_or_else(Zero::zero);
+
+                    Self::prune_snapshots(snapshots, current);
+                    let _ = snapshots.try_push((current, supply));
+                }
+            });
+        }
+
+        fn ensure_snapshot_taken(token_id: T::TokenId, snapshot_id: SnapshotId) -> DispatchResult {
+            let current = CurrentSnapshotId::<T>::get(token_id);
+
+            ensure!(
+                snapshot_id > 0 && snapshot_id <= current,
+                Error::<T>::InvalidSnapshotId
+            );
+            ensure!(
+                snapshot_id > current.saturating_sub(T::MaxSnapshots::get()),
+                Error::<T>::SnapshotExpired
+            );
+
+            Ok(())
+        }
+
+        // Entries recorded before the oldest snapshot that can still be queried are
+        // never looked at again. What is left has one entry per snapshot id at most,
+        // so there is room for the one about to be recorded at `current`.
+        fn prune_snapshots(
+            snapshots: &mut BoundedVec<(SnapshotId, T::TokenBalance), T::MaxSnapshots>,
+            current: SnapshotId,
+        ) {
+            let expired = current.saturating_sub(T::MaxSnapshots::get());
+
+            snapshots.retain(|(id, _)| *id > expired);
+        }
+
+        // The first entry recorded at or after `snapshot_id` holds the value as of
+        // `snapshot_id`. No such entry means nothing changed since.
+        fn value_at(
+            snapshots: &[(SnapshotId, T::TokenBalance)],
+            snapshot_id: SnapshotId,
+        ) -> Option<T::TokenBalance> {
+            let index = snapshots.partition_point(|(id, _)| *id < snapshot_id);
+
+            snapshots.get(index).map(|(_, value)| *value)
+        }
+
+        /// Current voting power of `who`.
+        pub fn get_votes(token_id: T::TokenId, who: &T::AccountId) -> T::TokenBalance {
+            Self::last_checkpoint(token_id, who)
+                .map(|checkpoint| checkpoint.votes)
+                .unwrap_or_else(Zero::zero)
+        }
+
+        /// Voting power of `who` at the end of `block`, which must be in the past.
+        ///
+        /// Blocks within `CheckpointRetention` of the current one can always be
+        /// looked up. Returns `None` for the current or a future block, and for
+        /// older blocks whose checkpoints were pruned.
+        pub fn get_past_votes(
+            token_id: T::TokenId,
+            who: &T::AccountId,
+            block: BlockNumberFor<T>,
+        ) -> Option<T::TokenBalance> {
+            if block >= frame_system::Pallet::<T>::block_number() {
+                return None;
+            }
+
+            // binary search for the first checkpoint after `block`
+            let (first, end) = CheckpointRange::<T>::get(token_id, who);
+            let (mut low, mut high) = (first, end);
+
+            while low < high {
+                let mid = low + (high - low) / 2;
+
+                match Checkpoints::<T>::get((token_id, who, mid)) {
+                    Some(checkpoint) if checkpoint.from_block <= block => low = mid + 1,
+                    _ => high = mid,
+                }
+            }
+
+            if low == first {
+                // nothing before `block`, unless it was pruned
+                return (first == 0).then(Zero::zero);
+            }
+
+            Checkpoints::<T>::get((token_id, who, low - 1)).map(|checkpoint| checkpoint.votes)
+        }
+
+        /// Every checkpoint `who` still has, oldest first.
+        pub fn checkpoints(token_id: T::TokenId, who: &T::AccountId) -> Vec<CheckpointOf<T>> {
+            let (first, end) = CheckpointRange::<T>::get(token_id, who);
+
+            (first..end)
+                .filter_map(|index| Checkpoints::<T>::get((token_id, who, index)))
+                .collect()
+        }
+
+        fn last_checkpoint(token_id: T::TokenId, who: &T::AccountId) -> Option<CheckpointOf<T>> {
+            let (first, end) = CheckpointRange::<T>::get(token_id, who);
+
+            if end > first {
+                Checkpoints::<T>::get((token_id, who, end - 1))
+            } else {
+                None
+            }
+        }
+
+        /// Must be called after `amount` moved between accounts delegating to `src`
+        /// and `dst`. `None` stands for tokens entering or leaving circulation.
+        pub(crate) fn move_voting_power(
+            token_id: T::TokenId,
+            src: Option<&T::AccountId>,
+            dst: Option<&T::AccountId>,
+            amount: T::TokenBalance,
+        ) {
+            if src == dst || amount.is_zero() {
+                return;
+            }
+
+            if let Some(src) = src {
+                Self::write_checkpoint(token_id, src, |votes| votes.saturating_sub(amount));
+            }
+
+            if let Some(dst) = dst {
+                Self::write_checkpoint(token_id, dst, |votes| votes.saturating_add(amount));
+            }
+        }
+
+        fn write_checkpoint(
+            token_id: T::TokenId,
+            delegate: &T::AccountId,
+            op: impl FnOnce(T::TokenBalance) -> T::TokenBalance,
+        ) {
+            let now = frame_system::Pallet::<T>::block_number();
+            let (first, end) = CheckpointRange::<T>::get(token_id, delegate);
+            let last = Self::last_checkpoint(token_id, delegate);
+
+            let previous_votes = last
+                .map(|checkpoint| checkpoint.votes)
+                .unwrap_or_else(Zero::zero);
+            let new_votes = op(previous_votes);
+            let checkpoint = Checkpoint {
+                from_block: now,
+                votes: new_votes,
+            };
+
+            match last {
+                // one checkpoint per block
+                Some(last) if last.from_block == now => {
+                    Checkpoints::<T>::insert((token_id, delegate, end - 1), checkpoint);
+                },
+                _ => {
+                    Checkpoints::<T>::insert((token_id, delegate, end), checkpoint);
+
+                    let end = end.saturating_add(1);
+                    let first = Self::prune_checkpoints(token_id, delegate, first, end, now);
+                    CheckpointRange::<T>::insert(token_id, delegate, (first, end));
+                },
+            }
+
+            Self::deposit_event(Event::DelegateVotesChanged {
+                token_id,
+                delegate: delegate.clone(),
+                previous_votes,
+                new_votes,
+            });
+        }
+
+        // Drops up to two checkpoints that no block within `CheckpointRetention` of
+        // `now` needs any more: those followed by a checkpoint from the start of that
+        // period or before. Each new checkpoint prunes at most two, which is enough
+        // to keep up. Returns the new `first`.
+        fn prune_checkpoints(
+            token_id: T::TokenId,
+            delegate: &T::AccountId,
+            mut first: u32,
+            end: u32,
+            now: BlockNumberFor<T>,
+        ) -> u32 {
+            let horizon = now.saturating_sub(T::CheckpointRetention::get());
+
+            for _ in 0..2 {
+                if first + 1 >= end {
+                    break;
+                }
+
+                match Checkpoints::<T>::get((token_id, delegate, first + 1)) {
+                    Some(next) if next.from_block <= horizon => {
+                        Checkpoints::<T>::remove((token_id, delegate, first));
+                        first += 1;
+                    },
+                    _ => break,
+                }
+            }
+
+            first
+        }
+
+        /// Checks that `spender` may move `amount` of `owner`'s `token_id`, using up
+        /// that much allowance unless `spender` is an operator of `owner`.
+        fn spend_allowance(
+            token_id: T::TokenId,
+            owner: &T::AccountId,
+            spender: &T::AccountId,
+            amount: T::TokenBalance,
+        ) -> DispatchResult {
+            if Self::is_operator(owner, spender) {
+                return Ok(());
+            }
+
+            let allowance = Self::allowance((token_id, owner, spender));
+
+            ensure!(allowance > Zero::zero(), Error::<T>::NotApproved);
+
+            let allowance = allowance
+                .checked_sub(&amount)
+                .ok_or(Error::<T>::InsufficientBalance)?;
+
+            Self::put_allowance(token_id, owner, spender, allowance);
+
+            Ok(())
+        }
+
+        /// The free balance of `who`. While the v2 migration runs, rows it hasn't
+        /// moved yet are still found under their old key.
+        pub fn balance_of(
+            token_id: T::TokenId,
+            who: impl Borrow<T::AccountId>,
+        ) -> T::TokenBalance {
+            let who = who.borrow();
+
+            BalanceOf::<T>::try_get(token_id, who).unwrap_or_else(|()| {
+                if Rehash::<T>::exists() {
+                    migrations::v2::old::BalanceOf::<T>::get(token_id, who)
+                } else {
+                    Zero::zero()
+                }
+            })
+        }
+
+        /// How much of `owner`'s `token_id` `spender` may move, like `balance_of`.
+        pub fn allowance<O: Borrow<T::AccountId>, S: Borrow<T::AccountId>>(
+            (token_id, owner, spender): (T::TokenId, O, S),
+        ) -> T::TokenBalance {
+            let key = (token_id, owner.borrow(), spender.borrow());
+
+            Allowance::<T>::try_get(key).unwrap_or_else(|()| {
+                if Rehash::<T>::exists() {
+                    migrations::v2::old::Allowance::<T>::get(key)
+                } else {
+                    Zero::zero()
+                }
+            })
+        }
+
+        /// Up to `limit` tokens with their total supply, in storage order. The last id
+        /// of a page is the `start_after` of the next.
+        pub fn token_page(
+            start_after: Option<T::TokenId>,
+            limit: u32,
+        ) -> Vec<(T::TokenId, TokenDetails<T>, T::TokenBalance)> {
+            let iter = match start_after {
+                Some(token_id) => Tokens::<T>::iter_from(Tokens::<T>::hashed_key_for(token_id)),
+                None => Tokens::<T>::iter(),
+            };
+
+            iter.take(limit as usize)
+                .map(|(token_id, details)| {
+                    let supply = details.supply;
+                    (token_id, details, supply)
+                })
+                .collect()
+        }
+
+        /// Up to `limit` of the tokens `who` has a free balance of, with that balance,
+        /// paged like `token_page`. Only complete once the v3 migration is done.
+        pub fn portfolio(
+            who: &T::AccountId,
+            start_after: Option<T::TokenId>,
+            limit: u32,
+        ) -> Vec<(T::TokenId, TokenDetails<T>, T::TokenBalance)> {
+            let iter = match start_after {
+                Some(token_id) => AccountTokens::<T>::iter_key_prefix_from(
+                    who,
+                    AccountTokens::<T>::hashed_key_for(who, token_id),
+                ),
+                None => AccountTokens::<T>::iter_key_prefix(who),
+            };
+
+            iter.filter_map(|token_id| {
+                Tokens::<T>::get(token_id)
+                    .map(|details| (token_id, details, Self::balance_of(token_id, who)))
+            })
+            .take(limit as usize)
+            .collect()
+        }
+
+        /// Write the free balance of `who`. Zero balances are removed instead of
+        /// stored, so drained accounts leave no rows behind.
+        pub(crate) fn put_balance(
+            token_id: T::TokenId,
+            who: &T::AccountId,
+            balance: T::TokenBalance,
+        ) {
+            // the row now lives under the new key
+            if Rehash::<T>::exists() {
+                migrations::v2::old::BalanceOf::<T>::remove(token_id, who);
+            }
+
+            if balance.is_zero() {
+                BalanceOf::<T>::remove(token_id, who);
+                AccountTokens::<T>::remove(who, token_id);
+            } else {
+                BalanceOf::<T>::insert(token_id, who, balance);
+                AccountTokens::<T>::insert(who, token_id, ());
+            }
+        }
+
+        /// Like `put_balance`, for allowances.
+        pub(crate) fn put_allowance(
+            token_id: T::TokenId,
+            owner: &T::AccountId,
+            spender: &T::AccountId,
+            allowance: T::TokenBalance,
+        ) {
+            if Rehash::<T>::exists() {
+                migrations::v2::old::Allowance::<T>::remove((token_id, owner, spender));
+            }
+
+            if allowance.is_zero() {
+                Allowance::<T>::remove((token_id, owner, spender));
+            } else {
+                Allowance::<T>::insert((token_id, owner, spender), allowance);
+            }
+        }
+
+        pub fn is_operator(owner: &T::AccountId, operator: &T::AccountId) -> bool {
+            Operators::<T>::contains_key(owner, operator)
+        }
+
+        pub fn is_allowlisted(token_id: T::TokenId, who: &T::AccountId) -> bool {
+            Holders::<T>::contains_key(token_id, who)
+        }
+
+        /// Fails if `token_id` is permissioned and `who` is not on its allowlist.
+        pub(crate) fn ensure_can_receive(token_id: T::TokenId, who: &T::AccountId) -> DispatchResult {
+            let token = Tokens::<T>::get(token_id).ok_or(Error::<T>::NoneToken)?;
+
+            ensure!(
+                !token.permissioned || Self::is_allowlisted(token_id, who),
+                Error::<T>::NotAllowlisted
+            );
+
+            Ok(())
+        }
+
+        fn ensure_admin(token_id: T::TokenId, who: &T::AccountId) -> DispatchResult {
+            let token = Tokens::<T>::get(token_id).ok_or(Error::<T>::NoneToken)?;
+            ensure!(token.admin == *who, Error::<T>::NoPermission);
+
             Ok(())
         }
+
+        /// Issue everything `id` accrued since it was last processed, capped by the
+        /// token's `max_supply`.
+        fn process_emission(id: EmissionId, now: BlockNumberFor<T>) {
+            let mut schedule = match EmissionSchedules::<T>::get(id) {
+                Some(schedule) => schedule,
+                None => return,
+            };
+
+            if now <= schedule.last_emitted {
+                return;
+            }
+
+            let token = match Tokens::<T>::get(schedule.token_id) {
+                Some(token) => token,
+                None => return,
+            };
+
+            let mut amount = schedule.curve.emitted_between::<T::BlockNumberToBalance>(
+                schedule.last_emitted - schedule.start,
+                now - schedule.start,
+            );
+            let mut capped = false;
+
+            if let Some(max_supply) = token.max_supply {
+                let available = max_supply.saturating_sub(token.supply);
+
+                if amount >= available {
+                    amount = available;
+                    capped = true;
+                }
+            }
+
+            if !amount.is_zero() {
+                // e.g. the beneficiary was taken off the allowlist. Leave `last_emitted`
+                // alone so the amount is emitted once the problem is fixed.
+                if Self::do_issue(schedule.token_id, &schedule.beneficiary, amount).is_err() {
+                    return;
+                }
+
+                Self::deposit_event(Event::Emitted {
+                    id,
+                    token_id: schedule.token_id,
+                    beneficiary: schedule.beneficiary.clone(),
+                    amount,
+                });
+            }
+
+            if capped || schedule.curve.is_exhausted(now - schedule.start) {
+                Self::remove_emission(id);
+                Self::deposit_event(Event::EmissionCompleted { id });
+            } else {
+                schedule.last_emitted = now;
+                EmissionSchedules::<T>::insert(id, schedule);
+            }
+        }
+
+        fn remove_emission(id: EmissionId) {
+            EmissionSchedules::<T>::remove(id);
+            EmissionQueue::<T>::mutate(|queue| queue.retain(|queued| *queued != id));
+        }
+
+        /// The account holding escrowed tokens.
+        pub fn account_id() -> T::AccountId {
+            T::PalletId::get().into_account_truncating()
+        }
+
+        pub fn is_claimed(airdrop_id: AirdropId, index: u32) -> bool {
+            ClaimedBitmap::<T>::get(airdrop_id, index / 128) & (1u128 << (index % 128)) != 0
+        }
+
+        fn verify_proof(root: T::Hash, leaf: T::Hash, proof: &[T::Hash]) -> bool {
+            let computed = proof.iter().fold(leaf, |node, sibling| {
+                if node <= *sibling {
+                    T::Hashing::hash_of(&(node, sibling))
+                } else {
+                    T::Hashing::hash_of(&(sibling, node))
+                }
+            });
+
+            computed == root
+        }
     }
 }
@@ -0,0 +1,353 @@
+//! Storage migrations.
+//!
+//! Migrations that touch every balance run from `on_idle` a batch at a time, with
+//! the step they are on kept in [`Rehash`].
+use crate::*;
+use frame_support::pallet_prelude::*;
+
+/// Most rows looked at in one block.
+pub const MIGRATION_BATCH: u32 = 100;
+
+/// Run the next batch of the running migration, if there is one and it fits in
+/// `limit`. Returns the weight used.
+pub fn sweep<T: Config>(limit: Weight) -> Weight {
+    let db = T::DbWeight::get();
+    // reading and writing back the cursor
+    let base = db.reads_writes(1, 1);
+    // at most reading the row, removing it and writing it under a new key
+    let per_row = db.reads_writes(1, 2);
+
+    if !limit.all_gte(base.saturating_add(per_row)) {
+        return Weight::zero();
+    }
+
+    let cursor = match Rehash::<T>::get() {
+        Some(cursor) => cursor,
+        None => return db.reads(1),
+    };
+
+    let rows = limit
+        .saturating_sub(base)
+        .checked_div_per_component(&per_row)
+        .unwrap_or(u64::MAX)
+        .min(MIGRATION_BATCH as u64);
+
+    let (next, looked_at, written) = match cursor {
+        SweepCursor::Balances(start) => v2::migrate_balances::<T>(start, rows),
+        SweepCursor::Allowances(start) => v2::migrate_allowances::<T>(start, rows),
+        SweepCursor::AccountTokens(start) => v3::index_balances::<T>(start, rows),
+    };
+
+    match next {
+        Some(next) => Rehash::<T>::put(next),
+        None => Rehash::<T>::kill(),
+    }
+
+    base.saturating_add(db.reads_writes(looked_at, written))
+}
+
+pub mod v1 {
+    //! The baseline `TokenDetails` only had a name, symbol and supply. v1 adds the
+    //! owner and admin, the allowlist switch and the supply cap. Nobody is known to
+    //! own existing tokens, so both roles go to the pallet account until
+    //! `ForceOrigin` hands them over with `force_set_team`. The tokens start out
+    //! unpermissioned and uncapped, like before.
+    use super::*;
+    use codec::{Decode, Encode};
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+
+    pub mod old {
+        use super::*;
+
+        /// The baseline layout.
+        #[derive(Encode, Decode)]
+        pub struct TokenDetails<T: Config> {
+            pub name: BoundedVec<u8, T::MaxTokenNameLen>,
+            pub symbol: BoundedVec<u8, T::MaxTokenSymbolLen>,
+            pub supply: T::TokenBalance,
+        }
+    }
+
+    pub fn migrate<T: Config>() -> Weight {
+        let db = T::DbWeight::get();
+
+        if Pallet::<T>::on_chain_storage_version() >= 1 {
+            return db.reads(1);
+        }
+
+        let owner = Pallet::<T>::account_id();
+        let mut translated = 0u64;
+        v4::old::Tokens::<T>::translate::<old::TokenDetails<T>, _>(|_, old| {
+            translated += 1;
+
+            Some(v4::old::TokenDetails {
+                owner: owner.clone(),
+                admin: owner.clone(),
+                name: old.name,
+                symbol: old.symbol,
+                supply: old.supply,
+                permissioned: false,
+                max_supply: None,
+            })
+        });
+        StorageVersion::new(1).put::<Pallet<T>>();
+
+        db.reads_writes(translated + 1, translated + 1)
+    }
+}
+
+pub mod v2 {
+    //! `BalanceOf` and `Allowance` used to hash account ids with `Twox64Concat`,
+    //! which lets anyone pick accounts that pile up under one trie branch. v2
+    //! hashes them with `Blake2_128Concat`. Existing rows are moved from `on_idle`,
+    //! at most [`MIGRATION_BATCH`] per block, and zero rows are dropped on the way
+    //! (they are no longer stored, see v1).
+    //!
+    //! Old and new keys share the map prefix until the migration is done, so
+    //! [`Pallet::balance_of`] and [`Pallet::allowance`] fall back to the old key,
+    //! and writes remove it.
+    use super::*;
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+
+    /// The v1 layout.
+    pub mod old {
+        use crate::{Config, Pallet, SweepCursor};
+        use frame_support::{pallet_prelude::*, storage_alias};
+
+        #[storage_alias]
+        pub type BalanceOf<T: Config> = StorageDoubleMap<
+            Pallet<T>,
+            Twox64Concat,
+            <T as Config>::TokenId,
+            Twox64Concat,
+            <T as frame_system::Config>::AccountId,
+            <T as Config>::TokenBalance,
+            ValueQuery,
+        >;
+
+        #[storage_alias]
+        pub type Allowance<T: Config> = StorageNMap<
+            Pallet<T>,
+            (
+                NMapKey<Twox64Concat, <T as Config>::TokenId>,
+                NMapKey<Twox64Concat, <T as frame_system::Config>::AccountId>,
+                NMapKey<Twox64Concat, <T as frame_system::Config>::AccountId>,
+            ),
+            <T as Config>::TokenBalance,
+            ValueQuery,
+        >;
+
+        /// The v1 sweep of zero rows, which this migration takes over.
+        #[storage_alias]
+        pub type ZeroSweep<T: Config> = StorageValue<Pallet<T>, SweepCursor, OptionQuery>;
+    }
+
+    /// Queue the migration when upgrading from an earlier version.
+    pub fn start<T: Config>() -> Weight {
+        let db = T::DbWeight::get();
+
+        if Pallet::<T>::on_chain_storage_version() >= 2 {
+            return db.reads(1);
+        }
+
+        old::ZeroSweep::<T>::kill();
+        Rehash::<T>::put(SweepCursor::Balances(None));
+        StorageVersion::new(2).put::<Pallet<T>>();
+
+        db.reads_writes(1, 3)
+    }
+
+    pub(super) fn migrate_balances<T: Config>(
+        start: Option<Vec<u8>>,
+        rows: u64,
+    ) -> (Option<SweepCursor>, u64, u64) {
+        let mut iter = match start {
+            Some(key) => old::BalanceOf::<T>::iter_from(key),
+            None => old::BalanceOf::<T>::iter(),
+        };
+        let mut looked_at = 0;
+        let mut written = 0;
+
+        // the iterator finds each next key from the last one it returned, so rows
+        // can be moved as we go. Moved rows can turn up again further on, read as
+        // if they had the old layout. Only rows whose key is really the old one
+        // are moved.
+        while looked_at < rows {
+            match iter.next() {
+                Some((token_id, who, balance)) => {
+                    looked_at += 1;
+                    if iter.last_raw_key() == old::BalanceOf::<T>::hashed_key_for(token_id, &who) {
+                        old::BalanceOf::<T>::remove(token_id, &who);
+                        if !balance.is_zero() {
+                            BalanceOf::<T>::insert(token_id, &who, balance);
+                        }
+                        written += 2;
+                    }
+                },
+                None => break,
+            }
+        }
+
+        let next = if looked_at < rows {
+            Some(SweepCursor::Allowances(None))
+        } else {
+            Some(SweepCursor::Balances(Some(iter.last_raw_key().to_vec())))
+        };
+
+        (next, looked_at, written)
+    }
+
+    pub(super) fn migrate_allowances<T: Config>(
+        start: Option<Vec<u8>>,
+        rows: u64,
+    ) -> (Option<SweepCursor>, u64, u64) {
+        let mut iter = match start {
+            Some(key) => old::Allowance::<T>::iter_from(key),
+            None => old::Allowance::<T>::iter(),
+        };
+        let mut looked_at = 0;
+        let mut written = 0;
+
+        while looked_at < rows {
+            match iter.next() {
+                Some((key, allowance)) => {
+                    looked_at += 1;
+                    if iter.last_raw_key() == old::Allowance::<T>::hashed_key_for(key.clone()) {
+                        old::Allowance::<T>::remove(key.clone());
+                        if !allowance.is_zero() {
+                            Allowance::<T>::insert(key, allowance);
+                        }
+                        written += 2;
+                    }
+                },
+                None => break,
+            }
+        }
+
+        // the v3 index is built from the moved balances
+        let next = if looked_at < rows {
+            Some(SweepCursor::AccountTokens(None))
+        } else {
+            Some(SweepCursor::Allowances(Some(iter.last_raw_key().to_vec())))
+        };
+
+        (next, looked_at, written)
+    }
+}
+
+pub mod v3 {
+    //! Adds the [`AccountTokens`] index of which tokens each account holds. It is
+    //! built from `BalanceOf` in `on_idle`, after the v2 migration if that is still
+    //! running.
+    use super::*;
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+
+    /// Queue building the index when upgrading from an earlier version.
+    pub fn start<T: Config>() -> Weight {
+        let db = T::DbWeight::get();
+
+        if Pallet::<T>::on_chain_storage_version() >= 3 {
+            return db.reads(1);
+        }
+
+        // a running v2 migration goes on to build the index when it is done
+        if !Rehash::<T>::exists() {
+            Rehash::<T>::put(SweepCursor::AccountTokens(None));
+        }
+        StorageVersion::new(3).put::<Pallet<T>>();
+
+        db.reads_writes(2, 2)
+    }
+
+    pub(super) fn index_balances<T: Config>(
+        start: Option<Vec<u8>>,
+        rows: u64,
+    ) -> (Option<SweepCursor>, u64, u64) {
+        let mut iter = match start {
+            Some(key) => BalanceOf::<T>::iter_keys_from(key),
+            None => BalanceOf::<T>::iter_keys(),
+        };
+        let mut looked_at = 0;
+
+        // inserting doesn't touch `BalanceOf`, so it is fine while iterating
+        while looked_at < rows {
+            match iter.next() {
+                Some((token_id, who)) => {
+                    looked_at += 1;
+                    AccountTokens::<T>::insert(who, token_id, ());
+                },
+                None => break,
+            }
+        }
+
+        // `None` once every balance is indexed
+        let next = if looked_at < rows {
+            None
+        } else {
+            Some(SweepCursor::AccountTokens(Some(iter.last_raw_key().to_vec())))
+        };
+
+        (next, looked_at, looked_at)
+    }
+}
+
+pub mod v4 {
+    //! Adds `TokenDetails::transfer_fee`, off for every existing token. There are
+    //! far fewer tokens than balances, so they are all translated in the upgrade.
+    use super::*;
+    use codec::{Decode, Encode};
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+
+    pub mod old {
+        use super::*;
+        use frame_support::storage_alias;
+
+        /// The v1 to v3 layout.
+        #[derive(Encode, Decode)]
+        pub struct TokenDetails<T: Config> {
+            pub owner: T::AccountId,
+            pub admin: T::AccountId,
+            pub name: BoundedVec<u8, T::MaxTokenNameLen>,
+            pub symbol: BoundedVec<u8, T::MaxTokenSymbolLen>,
+            pub supply: T::TokenBalance,
+            pub permissioned: bool,
+            pub max_supply: Option<T::TokenBalance>,
+        }
+
+        #[storage_alias]
+        pub type Tokens<T: Config> = StorageMap<
+            Pallet<T>,
+            Twox64Concat,
+            <T as Config>::TokenId,
+            TokenDetails<T>,
+            OptionQuery,
+        >;
+    }
+
+    pub fn migrate<T: Config>() -> Weight {
+        let db = T::DbWeight::get();
+
+        if Pallet::<T>::on_chain_storage_version() >= 4 {
+            return db.reads(1);
+        }
+
+        let mut translated = 0u64;
+        Tokens::<T>::translate::<old::TokenDetails<T>, _>(|_, old| {
+            translated += 1;
+
+            Some(TokenDetails {
+                owner: old.owner,
+                admin: old.admin,
+                name: old.name,
+                symbol: old.symbol,
+                supply: old.supply,
+                permissioned: old.permissioned,
+                max_supply: old.max_supply,
+                transfer_fee: None,
+            })
+        });
+        StorageVersion::new(4).put::<Pallet<T>>();
+
+        db.reads_writes(translated + 1, translated + 1)
+    }
+}
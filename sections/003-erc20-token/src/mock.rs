@@ -1,10 +1,17 @@
 pub(crate) use crate as pallet_erc20;
-use frame_support::traits::{ConstU16, ConstU32, ConstU64};
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{
+    parameter_types,
+    traits::{ConstU16, ConstU32, ConstU64, EnsureOriginWithArg},
+    PalletId,
+};
+use frame_system::{EnsureRoot, RawOrigin};
 use sp_core::H256;
 use sp_runtime::{
-    traits::{BlakeTwo256, IdentityLookup},
-    BuildStorage,
+    traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+    BuildStorage, DispatchError, DispatchResult, RuntimeDebug,
 };
+use scale_info::TypeInfo;
 
 type Block = frame_system::mocking::MockBlock<Test>;
 
@@ -43,6 +50,97 @@ impl frame_system::Config for Test {
     type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+// Stands in for the hold reasons other pallets in a runtime would declare.
+#[derive(
+    Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug,
+)]
+pub enum HoldReason {
+    Collateral,
+    Deposit,
+}
+
+parameter_types! {
+    pub const Erc20PalletId: PalletId = PalletId(*b"py/erc20");
+    pub static BlockedSenders: Vec<u64> = vec![];
+    pub static BlockedRecipients: Vec<u64> = vec![];
+    pub static TransfersSeen: Vec<(u64, u64, u64, u64)> = vec![];
+    pub static CreateOriginMode: CreateMode = CreateMode::Anyone;
+    pub static Creators: Vec<u64> = vec![];
+    pub const RootTokenOwner: u64 = 100;
+}
+
+// The token creation policies a runtime might pick, switchable per test.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CreateMode {
+    Anyone,
+    // Tokens created by root are owned by `RootTokenOwner`.
+    RootOnly,
+    // Only accounts in `Creators`.
+    Allowlisted,
+}
+
+pub struct MockCreateOrigin;
+impl EnsureOriginWithArg<RuntimeOrigin, u64> for MockCreateOrigin {
+    type Success = u64;
+
+    fn try_origin(o: RuntimeOrigin, _token_id: &u64) -> Result<u64, RuntimeOrigin> {
+        let raw: Result<RawOrigin<u64>, RuntimeOrigin> = o.into();
+
+        raw.and_then(|o| match (CreateOriginMode::get(), o) {
+            (CreateMode::Anyone, RawOrigin::Signed(who)) => Ok(who),
+            (CreateMode::RootOnly, RawOrigin::Root) => Ok(RootTokenOwner::get()),
+            (CreateMode::Allowlisted, RawOrigin::Signed(who))
+                if Creators::get().contains(&who) =>
+            {
+                Ok(who)
+            },
+            (_, o) => Err(o.into()),
+        })
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn try_successful_origin(_token_id: &u64) -> Result<RuntimeOrigin, ()> {
+        match CreateOriginMode::get() {
+            CreateMode::Anyone => Ok(RuntimeOrigin::signed(1)),
+            CreateMode::RootOnly => Ok(RuntimeOrigin::root()),
+            CreateMode::Allowlisted => Creators::get()
+                .first()
+                .map(|who| RuntimeOrigin::signed(*who))
+                .ok_or(()),
+        }
+    }
+}
+
+// Two filters combined as a tuple, the way a runtime would wire several pallets.
+pub struct BlockSenders;
+impl pallet_erc20::TransferFilter<u64, u64, u64> for BlockSenders {
+    fn filter(_token_id: &u64, from: &u64, _to: &u64, _amount: &u64) -> DispatchResult {
+        if BlockedSenders::get().contains(from) {
+            return Err(DispatchError::Other("sender blocked"));
+        }
+
+        Ok(())
+    }
+}
+
+pub struct BlockRecipients;
+impl pallet_erc20::TransferFilter<u64, u64, u64> for BlockRecipients {
+    fn filter(_token_id: &u64, _from: &u64, to: &u64, _amount: &u64) -> DispatchResult {
+        if BlockedRecipients::get().contains(to) {
+            return Err(DispatchError::Other("recipient blocked"));
+        }
+
+        Ok(())
+    }
+}
+
+pub struct RecordTransfers;
+impl pallet_erc20::OnTransfer<u64, u64, u64> for RecordTransfers {
+    fn on_transfer(token_id: &u64, from: &u64, to: &u64, amount: &u64) {
+        TransfersSeen::mutate(|seen| seen.push((*token_id, *from, *to, *amount)));
+    }
+}
+
 impl pallet_erc20::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type WeightInfo = ();
@@ -51,6 +149,27 @@ impl pallet_erc20::Config for Test {
     type MaxTokenNameLen = ConstU32<20>;
     type MaxTokenSymbolLen = ConstU32<5>;
     type MaxLength = ConstU32<20>;
+    type MaxVestingSchedules = ConstU32<3>;
+    type MinVestedTransfer = ConstU64<10>;
+    type BlockNumberToBalance = ConvertInto;
+    type RuntimeHoldReason = HoldReason;
+    type MaxHolds = ConstU32<2>;
+    type MaxSnapshots = ConstU32<10>;
+    type CheckpointRetention = ConstU64<5>;
+    type TransferFilter = (BlockSenders, BlockRecipients);
+    type OnTransfer = (RecordTransfers, ());
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = ();
+    type MaxAllowlistBatch = ConstU32<2>;
+    type MaxEmissionSchedules = ConstU32<4>;
+    type MaxEmissionsPerBlock = ConstU32<2>;
+    type PalletId = Erc20PalletId;
+    type MaxProofLength = ConstU32<8>;
+    type ForceOrigin = EnsureRoot<u64>;
+    type CreateOrigin = MockCreateOrigin;
+    type MaxBatchTransfers = ConstU32<3>;
+    type MaxMemoLen = ConstU32<32>;
+    type MaxOutflowBuckets = ConstU32<10>;
 }
 
 // Build genesis storage according to the mock runtime.
@@ -60,3 +179,13 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
         .unwrap()
         .into()
 }
+
+pub fn new_test_ext_with(erc20: pallet_erc20::GenesisConfig<Test>) -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+
+    erc20.assimilate_storage(&mut t).unwrap();
+
+    t.into()
+}
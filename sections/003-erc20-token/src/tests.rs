@@ -1,6 +1,8 @@
 use crate::types::*;
 use crate::{mock::*, Error, Event};
 use frame_support::traits::{ConstU16, ConstU32, ConstU64};
+use frame_support::traits::fungibles::{hold::Inspect as InspectHold, Inspect};
+use frame_support::traits::Hooks;
 use frame_support::{assert_err, assert_noop, assert_ok, BoundedVec};
 
 type MaxNameLen<T> = <T as pallet_erc20::Config>::MaxTokenNameLen;
@@ -16,7 +18,7 @@ fn create_token(supply: TokenBalance<Test>) -> TokenDetails<Test> {
     let symbol =
         BoundedVec::<u8, MaxSymbolLen<Test>>::try_from("MTKN".as_bytes().to_vec()).unwrap();
 
-    TokenDetails::new(name, symbol, supply)
+    TokenDetails::new(ALICE, name, symbol, supply, None)
 }
 
 #[test]
@@ -31,7 +33,8 @@ fn can_mint_token() {
             RuntimeOrigin::signed(ALICE),
             token.name.clone(),
             token.symbol.clone(),
-            token.supply
+            token.supply,
+            token.max_supply
         ));
 
         System::assert_last_event(
@@ -68,7 +71,8 @@ fn test_can_transfer_tokens() {
             RuntimeOrigin::signed(ALICE),
             token.name.clone(),
             token.symbol.clone(),
-            token.supply
+            token.supply,
+            token.max_supply
         ));
 
         assert_ok!(TemplateModule::transfer(
@@ -124,7 +128,8 @@ fn can_approve_tokens_for_transfer() {
             RuntimeOrigin::signed(ALICE),
             token.name.clone(),
             token.symbol.clone(),
-            token.supply
+            token.supply,
+            token.max_supply
         ));
 
         assert_ok!(TemplateModule::approve(
@@ -165,7 +170,8 @@ fn can_transfer_from() {
             RuntimeOrigin::signed(ALICE),
             token.name.clone(),
             token.symbol.clone(),
-            token.supply
+            token.supply,
+            token.max_supply
         ));
 
         assert_ok!(TemplateModule::approve(
@@ -203,3 +209,3440 @@ fn can_transfer_from() {
         assert_eq!(TemplateModule::balance_of(token_id, DAVE), approved_amount);
     });
 }
+
+fn mint_to_alice(supply: TokenBalance<Test>) -> u64 {
+    let token = create_token(supply);
+
+    assert_ok!(TemplateModule::mint(
+        RuntimeOrigin::signed(ALICE),
+        token.name,
+        token.symbol,
+        token.supply,
+        token.max_supply
+    ));
+
+    TemplateModule::last_token_id()
+}
+
+#[test]
+fn can_vested_transfer() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        // 100 tokens, 10 per block from block 1, nothing before block 5
+        let schedule = VestingSchedule::new(100, 10, 1, 5);
+
+        assert_ok!(TemplateModule::vested_transfer(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            BOB,
+            schedule
+        ));
+
+        System::assert_last_event(
+            Event::VestedTransferred {
+                token_id,
+                from: ALICE,
+                to: BOB,
+                schedule,
+            }
+            .into(),
+        );
+
+        assert_eq!(TemplateModule::balance_of(token_id, BOB), 100);
+        assert_eq!(TemplateModule::locked(token_id, BOB), 100);
+        assert_eq!(TemplateModule::vesting(token_id, BOB).len(), 1);
+    });
+}
+
+#[test]
+fn cannot_transfer_locked_funds() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        assert_ok!(TemplateModule::vested_transfer(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            BOB,
+            VestingSchedule::new(100, 10, 1, 1)
+        ));
+
+        // some free funds on top of the locked ones
+        assert_ok!(TemplateModule::transfer(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            token_id,
+            50
+        ));
+
+        assert_noop!(
+            TemplateModule::transfer(RuntimeOrigin::signed(BOB), DAVE, token_id, 51),
+            Error::<Test>::BalanceLocked
+        );
+
+        assert_ok!(TemplateModule::transfer(
+            RuntimeOrigin::signed(BOB),
+            DAVE,
+            token_id,
+            50
+        ));
+    });
+}
+
+#[test]
+fn vest_releases_unlocked_funds() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        assert_ok!(TemplateModule::vested_transfer(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            BOB,
+            VestingSchedule::new(100, 10, 1, 5)
+        ));
+
+        // before the cliff nothing is released
+        System::set_block_number(4);
+        assert_ok!(TemplateModule::vest(RuntimeOrigin::signed(BOB), token_id));
+        assert_eq!(TemplateModule::locked(token_id, BOB), 100);
+
+        // at the cliff everything accrued since the start is released
+        System::set_block_number(5);
+        assert_ok!(TemplateModule::vest(RuntimeOrigin::signed(BOB), token_id));
+        assert_eq!(TemplateModule::locked(token_id, BOB), 60);
+
+        System::assert_last_event(
+            Event::VestingUpdated {
+                token_id,
+                who: BOB,
+                unvested: 60,
+            }
+            .into(),
+        );
+
+        // the lock only moves when `vest` is called
+        System::set_block_number(11);
+        assert_noop!(
+            TemplateModule::transfer(RuntimeOrigin::signed(BOB), DAVE, token_id, 41),
+            Error::<Test>::BalanceLocked
+        );
+
+        assert_ok!(TemplateModule::vest(RuntimeOrigin::signed(BOB), token_id));
+        System::assert_last_event(
+            Event::VestingCompleted {
+                token_id,
+                who: BOB,
+            }
+            .into(),
+        );
+
+        assert_eq!(TemplateModule::locked(token_id, BOB), 0);
+        assert_eq!(TemplateModule::vesting(token_id, BOB).len(), 0);
+
+        assert_ok!(TemplateModule::transfer(
+            RuntimeOrigin::signed(BOB),
+            DAVE,
+            token_id,
+            100
+        ));
+
+        assert_noop!(
+            TemplateModule::vest(RuntimeOrigin::signed(BOB), token_id),
+            Error::<Test>::NotVesting
+        );
+    });
+}
+
+#[test]
+fn vesting_schedules_are_bounded() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+        let schedule = VestingSchedule::new(10, 1, 1, 1);
+
+        // MaxVestingSchedules is 3 in the mock
+        for _ in 0..3 {
+            assert_ok!(TemplateModule::vested_transfer(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                BOB,
+                schedule
+            ));
+        }
+
+        assert_noop!(
+            TemplateModule::vested_transfer(RuntimeOrigin::signed(ALICE), token_id, BOB, schedule),
+            Error::<Test>::AtMaxVestingSchedules
+        );
+
+        assert_noop!(
+            TemplateModule::vested_transfer(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                DAVE,
+                VestingSchedule::new(10, 1, 5, 1)
+            ),
+            Error::<Test>::InvalidVestingSchedule
+        );
+    });
+}
+
+#[test]
+fn vested_transfers_have_a_minimum() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        // MinVestedTransfer is 10 in the mock
+        assert_noop!(
+            TemplateModule::vested_transfer(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                BOB,
+                VestingSchedule::new(9, 1, 1, 1)
+            ),
+            Error::<Test>::VestedTransferTooSmall
+        );
+
+        assert_ok!(TemplateModule::vested_transfer(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            BOB,
+            VestingSchedule::new(10, 1, 1, 1)
+        ));
+        assert_eq!(TemplateModule::locked(token_id, BOB), 10);
+    });
+}
+
+#[test]
+fn held_funds_cannot_be_transferred() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        assert_ok!(TemplateModule::hold(
+            token_id,
+            &ALICE,
+            &HoldReason::Collateral,
+            600
+        ));
+
+        System::assert_last_event(
+            Event::Held {
+                token_id,
+                who: ALICE,
+                reason: HoldReason::Collateral,
+                amount: 600,
+            }
+            .into(),
+        );
+
+        assert_eq!(TemplateModule::balance_of(token_id, ALICE), 400);
+        assert_eq!(
+            <TemplateModule as InspectHold<u64>>::balance_on_hold(
+                token_id,
+                &HoldReason::Collateral,
+                &ALICE
+            ),
+            600
+        );
+        assert_eq!(
+            <TemplateModule as Inspect<u64>>::total_balance(token_id, &ALICE),
+            1000
+        );
+
+        assert_noop!(
+            TemplateModule::transfer(RuntimeOrigin::signed(ALICE), BOB, token_id, 401),
+            Error::<Test>::InsufficientBalance
+        );
+    });
+}
+
+#[test]
+fn can_release_held_funds() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        assert_ok!(TemplateModule::hold(
+            token_id,
+            &ALICE,
+            &HoldReason::Deposit,
+            600
+        ));
+        assert_ok!(TemplateModule::release(
+            token_id,
+            &ALICE,
+            &HoldReason::Deposit,
+            600
+        ));
+
+        assert_eq!(TemplateModule::balance_of(token_id, ALICE), 1000);
+        assert_eq!(TemplateModule::holds(token_id, ALICE).len(), 0);
+
+        assert_noop!(
+            TemplateModule::release(token_id, &ALICE, &HoldReason::Deposit, 1),
+            sp_runtime::TokenError::FundsUnavailable
+        );
+    });
+}
+
+#[test]
+fn slashing_held_funds_reduces_supply() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        assert_ok!(TemplateModule::hold(
+            token_id,
+            &ALICE,
+            &HoldReason::Collateral,
+            300
+        ));
+
+        // best effort: only what is held can be slashed
+        assert_eq!(
+            TemplateModule::slash_held(token_id, &ALICE, &HoldReason::Collateral, 500),
+            Ok(300)
+        );
+
+        assert_eq!(TemplateModule::tokens(token_id).unwrap().supply, 700);
+        assert_eq!(TemplateModule::balance_of(token_id, ALICE), 700);
+        assert_eq!(
+            <TemplateModule as Inspect<u64>>::total_balance(token_id, &ALICE),
+            700
+        );
+    });
+}
+
+#[test]
+fn can_transfer_on_hold() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        assert_ok!(TemplateModule::hold(
+            token_id,
+            &ALICE,
+            &HoldReason::Collateral,
+            300
+        ));
+
+        assert_ok!(TemplateModule::transfer_on_hold(
+            token_id,
+            &HoldReason::Collateral,
+            &ALICE,
+            &BOB,
+            100,
+            false
+        ));
+        assert_eq!(TemplateModule::balance_of(token_id, BOB), 100);
+
+        assert_ok!(TemplateModule::transfer_on_hold(
+            token_id,
+            &HoldReason::Collateral,
+            &ALICE,
+            &DAVE,
+            100,
+            true
+        ));
+        assert_eq!(TemplateModule::balance_of(token_id, DAVE), 0);
+        assert_eq!(
+            <TemplateModule as InspectHold<u64>>::balance_on_hold(
+                token_id,
+                &HoldReason::Collateral,
+                &DAVE
+            ),
+            100
+        );
+
+        System::assert_last_event(
+            Event::TransferredOnHold {
+                token_id,
+                reason: HoldReason::Collateral,
+                from: ALICE,
+                to: DAVE,
+                amount: 100,
+            }
+            .into(),
+        );
+
+        assert_eq!(
+            <TemplateModule as InspectHold<u64>>::total_balance_on_hold(token_id, &ALICE),
+            100
+        );
+    });
+}
+
+#[test]
+fn cannot_hold_locked_funds() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        assert_ok!(TemplateModule::vested_transfer(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            BOB,
+            VestingSchedule::new(100, 10, 1, 1)
+        ));
+        assert_ok!(TemplateModule::transfer(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            token_id,
+            50
+        ));
+
+        assert_noop!(
+            TemplateModule::hold(token_id, &BOB, &HoldReason::Collateral, 51),
+            sp_runtime::TokenError::FundsUnavailable
+        );
+        assert!(!<TemplateModule as InspectHold<u64>>::can_hold(
+            token_id,
+            &HoldReason::Collateral,
+            &BOB,
+            51
+        ));
+
+        // the free funds on top of the lock can be held, and paid out from there
+        assert_ok!(TemplateModule::hold(
+            token_id,
+            &BOB,
+            &HoldReason::Collateral,
+            50
+        ));
+        assert_ok!(TemplateModule::transfer_on_hold(
+            token_id,
+            &HoldReason::Collateral,
+            &BOB,
+            &DAVE,
+            50,
+            false
+        ));
+        assert_eq!(TemplateModule::balance_of(token_id, BOB), 100);
+        assert_eq!(TemplateModule::locked(token_id, BOB), 100);
+    });
+}
+
+#[test]
+fn only_owner_can_snapshot() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        assert_noop!(
+            TemplateModule::snapshot(RuntimeOrigin::signed(BOB), token_id),
+            Error::<Test>::NoPermission
+        );
+
+        assert_ok!(TemplateModule::snapshot(RuntimeOrigin::signed(ALICE), token_id));
+        System::assert_last_event(Event::Snapshot { token_id, id: 1 }.into());
+
+        assert_eq!(TemplateModule::current_snapshot_id(token_id), 1);
+    });
+}
+
+#[test]
+fn balances_are_recorded_at_snapshot() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        assert_ok!(TemplateModule::transfer(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            token_id,
+            100
+        ));
+
+        let first = TemplateModule::do_snapshot(token_id).unwrap();
+
+        assert_ok!(TemplateModule::transfer(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            token_id,
+            200
+        ));
+        assert_ok!(TemplateModule::transfer(
+            RuntimeOrigin::signed(BOB),
+            DAVE,
+            token_id,
+            50
+        ));
+
+        let second = TemplateModule::do_snapshot(token_id).unwrap();
+        // nothing changes between the second and third snapshot
+        let third = TemplateModule::do_snapshot(token_id).unwrap();
+
+        assert_ok!(TemplateModule::transfer(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            token_id,
+            250
+        ));
+
+        assert_eq!(TemplateModule::balance_of_at(token_id, &ALICE, first), Ok(900));
+        assert_eq!(TemplateModule::balance_of_at(token_id, &BOB, first), Ok(100));
+        assert_eq!(TemplateModule::balance_of_at(token_id, &DAVE, first), Ok(0));
+
+        assert_eq!(TemplateModule::balance_of_at(token_id, &ALICE, second), Ok(700));
+        assert_eq!(TemplateModule::balance_of_at(token_id, &BOB, second), Ok(250));
+        assert_eq!(TemplateModule::balance_of_at(token_id, &DAVE, second), Ok(50));
+
+        assert_eq!(TemplateModule::balance_of_at(token_id, &ALICE, third), Ok(700));
+        assert_eq!(TemplateModule::balance_of_at(token_id, &BOB, third), Ok(250));
+
+        // untouched since the last snapshot, so the live balance is used
+        assert_eq!(TemplateModule::balance_of_at(token_id, &DAVE, third), Ok(50));
+
+        assert_eq!(TemplateModule::balance_of(token_id, ALICE), 950);
+    });
+}
+
+#[test]
+fn supply_is_recorded_at_snapshot() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        let snapshot_id = TemplateModule::do_snapshot(token_id).unwrap();
+
+        assert_ok!(TemplateModule::hold(
+            token_id,
+            &ALICE,
+            &HoldReason::Collateral,
+            300
+        ));
+        assert_ok!(TemplateModule::slash_held(
+            token_id,
+            &ALICE,
+            &HoldReason::Collateral,
+            300
+        ));
+
+        assert_eq!(TemplateModule::total_supply_at(token_id, snapshot_id), Ok(1000));
+        assert_eq!(TemplateModule::balance_of_at(token_id, &ALICE, snapshot_id), Ok(1000));
+        assert_eq!(TemplateModule::tokens(token_id).unwrap().supply, 700);
+    });
+}
+
+#[test]
+fn cannot_query_invalid_snapshot() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        assert_noop!(
+            TemplateModule::balance_of_at(token_id, &ALICE, 0),
+            Error::<Test>::InvalidSnapshotId
+        );
+        assert_noop!(
+            TemplateModule::balance_of_at(token_id, &ALICE, 1),
+            Error::<Test>::InvalidSnapshotId
+        );
+
+        // MaxSnapshots is 10 in the mock, so the first two expire
+        for _ in 0..12 {
+            assert_ok!(TemplateModule::do_snapshot(token_id));
+        }
+
+        assert_noop!(
+            TemplateModule::balance_of_at(token_id, &ALICE, 2),
+            Error::<Test>::SnapshotExpired
+        );
+        assert_noop!(
+            TemplateModule::total_supply_at(token_id, 2),
+            Error::<Test>::SnapshotExpired
+        );
+        assert_eq!(TemplateModule::balance_of_at(token_id, &ALICE, 3), Ok(1000));
+    });
+}
+
+#[test]
+fn snapshots_never_run_out() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        // BOB's balance changes after every snapshot, far more often than MaxSnapshots
+        for id in 1..=25u32 {
+            assert_eq!(TemplateModule::do_snapshot(token_id), Ok(id));
+            assert_ok!(TemplateModule::transfer(
+                RuntimeOrigin::signed(ALICE),
+                BOB,
+                token_id,
+                10
+            ));
+        }
+
+        assert_eq!(
+            pallet_erc20::AccountSnapshots::<Test>::get(token_id, BOB).len(),
+            10
+        );
+
+        for id in 16..=25u32 {
+            let sent = 10 * (id as u64 - 1);
+
+            assert_eq!(TemplateModule::balance_of_at(token_id, &BOB, id), Ok(sent));
+            assert_eq!(
+                TemplateModule::balance_of_at(token_id, &ALICE, id),
+                Ok(1000 - sent)
+            );
+        }
+        assert_noop!(
+            TemplateModule::balance_of_at(token_id, &BOB, 15),
+            Error::<Test>::SnapshotExpired
+        );
+    });
+}
+
+#[test]
+fn delegating_moves_voting_power() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        // no delegate, no votes
+        assert_eq!(TemplateModule::get_votes(token_id, &ALICE), 0);
+
+        assert_ok!(TemplateModule::delegate(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            ALICE
+        ));
+        assert_eq!(TemplateModule::get_votes(token_id, &ALICE), 1000);
+
+        assert_ok!(TemplateModule::delegate(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            BOB
+        ));
+
+        System::assert_has_event(
+            Event::DelegateChanged {
+                token_id,
+                delegator: ALICE,
+                from_delegate: Some(ALICE),
+                to_delegate: BOB,
+            }
+            .into(),
+        );
+        System::assert_last_event(
+            Event::DelegateVotesChanged {
+                token_id,
+                delegate: BOB,
+                previous_votes: 0,
+                new_votes: 1000,
+            }
+            .into(),
+        );
+
+        assert_eq!(TemplateModule::get_votes(token_id, &ALICE), 0);
+        assert_eq!(TemplateModule::get_votes(token_id, &BOB), 1000);
+        assert_eq!(TemplateModule::delegates(token_id, ALICE), Some(BOB));
+    });
+}
+
+#[test]
+fn transfers_update_checkpoints() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        assert_ok!(TemplateModule::delegate(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            ALICE
+        ));
+        assert_ok!(TemplateModule::delegate(
+            RuntimeOrigin::signed(BOB),
+            token_id,
+            DAVE
+        ));
+
+        System::set_block_number(2);
+        assert_ok!(TemplateModule::transfer(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            token_id,
+            100
+        ));
+
+        // same block, so the checkpoint is overwritten
+        assert_ok!(TemplateModule::transfer(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            token_id,
+            100
+        ));
+
+        System::set_block_number(5);
+        assert_ok!(TemplateModule::transfer(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            token_id,
+            50
+        ));
+
+        System::set_block_number(6);
+
+        assert_eq!(TemplateModule::get_past_votes(token_id, &ALICE, 1), Some(1000));
+        assert_eq!(TemplateModule::get_past_votes(token_id, &ALICE, 2), Some(800));
+        assert_eq!(TemplateModule::get_past_votes(token_id, &ALICE, 4), Some(800));
+        assert_eq!(TemplateModule::get_past_votes(token_id, &ALICE, 5), Some(850));
+        assert_eq!(TemplateModule::get_past_votes(token_id, &DAVE, 1), Some(0));
+        assert_eq!(TemplateModule::get_past_votes(token_id, &DAVE, 3), Some(200));
+        assert_eq!(TemplateModule::get_past_votes(token_id, &DAVE, 5), Some(150));
+
+        // BOB delegated to DAVE, so BOB holds no votes
+        assert_eq!(TemplateModule::get_past_votes(token_id, &BOB, 5), Some(0));
+
+        // only past blocks can be queried
+        assert_eq!(TemplateModule::get_past_votes(token_id, &ALICE, 6), None);
+    });
+}
+
+#[test]
+fn holds_remove_voting_power() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        assert_ok!(TemplateModule::delegate(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            ALICE
+        ));
+        assert_ok!(TemplateModule::hold(
+            token_id,
+            &ALICE,
+            &HoldReason::Collateral,
+            400
+        ));
+        assert_eq!(TemplateModule::get_votes(token_id, &ALICE), 600);
+
+        assert_ok!(TemplateModule::release(
+            token_id,
+            &ALICE,
+            &HoldReason::Collateral,
+            400
+        ));
+        assert_eq!(TemplateModule::get_votes(token_id, &ALICE), 1000);
+    });
+}
+
+#[test]
+fn checkpoints_are_kept_for_the_retention_period() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        assert_ok!(TemplateModule::delegate(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            ALICE
+        ));
+
+        // a checkpoint every block, e.g. dust sent to a delegator to wipe history
+        for block in 2..12 {
+            System::set_block_number(block);
+            assert_ok!(TemplateModule::transfer(
+                RuntimeOrigin::signed(ALICE),
+                BOB,
+                token_id,
+                10
+            ));
+        }
+
+        System::set_block_number(12);
+
+        // CheckpointRetention is 5 in the mock, so blocks 6 to 11 are still needed
+        assert_eq!(TemplateModule::checkpoints(token_id, &ALICE).len(), 6);
+        for block in 6..12 {
+            assert_eq!(
+                TemplateModule::get_past_votes(token_id, &ALICE, block),
+                Some(1000 - 10 * (block - 1))
+            );
+        }
+
+        // older blocks were pruned
+        assert_eq!(TemplateModule::get_past_votes(token_id, &ALICE, 5), None);
+        assert_eq!(TemplateModule::get_past_votes(token_id, &ALICE, 1), None);
+        assert_eq!(TemplateModule::get_votes(token_id, &ALICE), 900);
+    });
+}
+
+#[test]
+fn transfer_filters_can_veto() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        BlockedRecipients::set(vec![BOB]);
+
+        assert_noop!(
+            TemplateModule::transfer(RuntimeOrigin::signed(ALICE), BOB, token_id, 100),
+            sp_runtime::DispatchError::Other("recipient blocked")
+        );
+
+        assert_ok!(TemplateModule::approve(
+            RuntimeOrigin::signed(ALICE),
+            DAVE,
+            token_id,
+            100
+        ));
+        assert_noop!(
+            TemplateModule::transfer_from(RuntimeOrigin::signed(DAVE), token_id, ALICE, BOB, 100),
+            sp_runtime::DispatchError::Other("recipient blocked")
+        );
+
+        BlockedSenders::set(vec![ALICE]);
+
+        assert_noop!(
+            TemplateModule::transfer(RuntimeOrigin::signed(ALICE), DAVE, token_id, 100),
+            sp_runtime::DispatchError::Other("sender blocked")
+        );
+    });
+}
+
+#[test]
+fn transfer_hooks_are_notified() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        assert_ok!(TemplateModule::transfer(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            token_id,
+            100
+        ));
+        assert_ok!(TemplateModule::approve(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            token_id,
+            50
+        ));
+        assert_ok!(TemplateModule::transfer_from(
+            RuntimeOrigin::signed(BOB),
+            token_id,
+            ALICE,
+            DAVE,
+            50
+        ));
+
+        // a failed transfer is not reported
+        assert!(TemplateModule::transfer(RuntimeOrigin::signed(DAVE), BOB, token_id, 51).is_err());
+
+        assert_eq!(
+            TransfersSeen::get(),
+            vec![(token_id, ALICE, BOB, 100), (token_id, ALICE, DAVE, 50)]
+        );
+    });
+}
+
+fn holders(accounts: Vec<u64>) -> BoundedVec<u64, ConstU32<2>> {
+    BoundedVec::try_from(accounts).unwrap()
+}
+
+#[test]
+fn only_admin_manages_allowlist() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        assert_noop!(
+            TemplateModule::set_permissioned(RuntimeOrigin::signed(BOB), token_id, true),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            TemplateModule::add_holders(RuntimeOrigin::signed(BOB), token_id, holders(vec![BOB])),
+            Error::<Test>::NoPermission
+        );
+
+        assert_ok!(TemplateModule::set_permissioned(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            true
+        ));
+        System::assert_last_event(
+            Event::PermissionedSet {
+                token_id,
+                permissioned: true,
+            }
+            .into(),
+        );
+
+        assert!(TemplateModule::tokens(token_id).unwrap().permissioned);
+    });
+}
+
+#[test]
+fn permissioned_tokens_only_go_to_allowlisted_accounts() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        assert_ok!(TemplateModule::set_permissioned(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            true
+        ));
+
+        assert_noop!(
+            TemplateModule::transfer(RuntimeOrigin::signed(ALICE), BOB, token_id, 100),
+            Error::<Test>::NotAllowlisted
+        );
+
+        assert_ok!(TemplateModule::approve(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            token_id,
+            100
+        ));
+        assert_noop!(
+            TemplateModule::transfer_from(RuntimeOrigin::signed(BOB), token_id, ALICE, DAVE, 100),
+            Error::<Test>::NotAllowlisted
+        );
+
+        assert_ok!(TemplateModule::add_holders(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            holders(vec![BOB, DAVE])
+        ));
+        System::assert_last_event(Event::HolderAdded { token_id, who: DAVE }.into());
+
+        assert_ok!(TemplateModule::transfer(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            token_id,
+            100
+        ));
+        assert_ok!(TemplateModule::transfer_from(
+            RuntimeOrigin::signed(BOB),
+            token_id,
+            ALICE,
+            DAVE,
+            100
+        ));
+
+        assert_ok!(TemplateModule::remove_holders(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            holders(vec![DAVE])
+        ));
+        assert!(!TemplateModule::is_allowlisted(token_id, &DAVE));
+
+        // DAVE keeps the balance but can't receive more
+        assert_eq!(TemplateModule::balance_of(token_id, DAVE), 100);
+        assert_noop!(
+            TemplateModule::transfer(RuntimeOrigin::signed(BOB), DAVE, token_id, 10),
+            Error::<Test>::NotAllowlisted
+        );
+
+        // issuance through the fungibles traits is blocked too
+        assert_eq!(
+            <TemplateModule as Inspect<u64>>::can_deposit(
+                token_id,
+                &DAVE,
+                10,
+                frame_support::traits::tokens::Provenance::Minted
+            ),
+            frame_support::traits::tokens::DepositConsequence::Blocked
+        );
+    });
+}
+
+fn mint_capped(supply: TokenBalance<Test>, max_supply: TokenBalance<Test>) -> u64 {
+    let token = create_token(supply);
+
+    assert_ok!(TemplateModule::mint(
+        RuntimeOrigin::signed(ALICE),
+        token.name,
+        token.symbol,
+        token.supply,
+        Some(max_supply)
+    ));
+
+    TemplateModule::last_token_id()
+}
+
+#[test]
+fn minting_assigns_new_token_ids() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+
+        assert_eq!(mint_to_alice(1000), 1);
+        assert_eq!(mint_capped(500, 1000), 2);
+
+        assert_eq!(TemplateModule::tokens(1).unwrap().supply, 1000);
+        assert_eq!(TemplateModule::tokens(2).unwrap().supply, 500);
+    });
+}
+
+#[test]
+fn cannot_mint_above_max_supply() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token = create_token(1001);
+
+        assert_noop!(
+            TemplateModule::mint(
+                RuntimeOrigin::signed(ALICE),
+                token.name,
+                token.symbol,
+                token.supply,
+                Some(1000)
+            ),
+            Error::<Test>::MaxSupplyExceeded
+        );
+    });
+}
+
+#[test]
+fn cannot_issue_above_max_supply() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_capped(900, 1000);
+
+        assert_eq!(TemplateModule::max_supply(token_id), Some(1000));
+
+        assert_noop!(
+            TemplateModule::issue(RuntimeOrigin::signed(BOB), token_id, BOB, 100),
+            Error::<Test>::NoPermission
+        );
+
+        assert_ok!(TemplateModule::issue(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            BOB,
+            100
+        ));
+        System::assert_last_event(
+            Event::Issued {
+                token_id,
+                to: BOB,
+                amount: 100,
+            }
+            .into(),
+        );
+
+        assert_eq!(TemplateModule::tokens(token_id).unwrap().supply, 1000);
+        assert_eq!(TemplateModule::balance_of(token_id, BOB), 100);
+
+        assert_noop!(
+            TemplateModule::issue(RuntimeOrigin::signed(ALICE), token_id, BOB, 1),
+            Error::<Test>::MaxSupplyExceeded
+        );
+    });
+}
+
+#[test]
+fn max_supply_can_only_be_lowered_by_owner() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_capped(500, 1000);
+
+        assert_noop!(
+            TemplateModule::set_max_supply(RuntimeOrigin::signed(BOB), token_id, 800),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            TemplateModule::set_max_supply(RuntimeOrigin::signed(ALICE), token_id, 1001),
+            Error::<Test>::InvalidMaxSupply
+        );
+        assert_noop!(
+            TemplateModule::set_max_supply(RuntimeOrigin::signed(ALICE), token_id, 499),
+            Error::<Test>::InvalidMaxSupply
+        );
+
+        assert_ok!(TemplateModule::set_max_supply(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            500
+        ));
+        System::assert_last_event(
+            Event::MaxSupplySet {
+                token_id,
+                max_supply: 500,
+            }
+            .into(),
+        );
+
+        assert_noop!(
+            TemplateModule::issue(RuntimeOrigin::signed(ALICE), token_id, ALICE, 1),
+            Error::<Test>::MaxSupplyExceeded
+        );
+
+        // an uncapped token can be capped
+        let uncapped = mint_to_alice(1000);
+        assert_ok!(TemplateModule::set_max_supply(
+            RuntimeOrigin::signed(ALICE),
+            uncapped,
+            2000
+        ));
+    });
+}
+
+#[test]
+fn genesis_respects_max_supply() {
+    new_test_ext_with(pallet_erc20::GenesisConfig {
+        tokens: vec![
+            (ALICE, b"CAPPED".to_vec(), b"CAP".to_vec(), 500, Some(1000)),
+            (BOB, b"OPEN".to_vec(), b"OPN".to_vec(), 100, None),
+        ],
+        balances: vec![(1, DAVE, 500), (2, DAVE, 10)],
+    })
+    .execute_with(|| {
+        assert_eq!(TemplateModule::last_token_id(), 2);
+        assert_eq!(TemplateModule::tokens(1).unwrap().supply, 1000);
+        assert_eq!(TemplateModule::tokens(1).unwrap().max_supply, Some(1000));
+        assert_eq!(TemplateModule::balance_of(1, ALICE), 500);
+        assert_eq!(TemplateModule::balance_of(1, DAVE), 500);
+        assert_eq!(TemplateModule::tokens(2).unwrap().owner, BOB);
+        assert_eq!(TemplateModule::balance_of(2, DAVE), 10);
+
+        // the next token created after genesis doesn't clobber genesis tokens
+        System::set_block_number(1);
+        assert_eq!(mint_to_alice(1), 3);
+    });
+}
+
+#[test]
+#[should_panic(expected = "invalid genesis balance")]
+fn genesis_cannot_issue_above_max_supply() {
+    new_test_ext_with(pallet_erc20::GenesisConfig {
+        tokens: vec![(ALICE, b"CAPPED".to_vec(), b"CAP".to_vec(), 500, Some(1000))],
+        balances: vec![(1, DAVE, 501)],
+    });
+}
+
+#[test]
+#[should_panic(expected = "invalid genesis token")]
+fn genesis_cannot_mint_above_max_supply() {
+    new_test_ext_with(pallet_erc20::GenesisConfig {
+        tokens: vec![(ALICE, b"CAPPED".to_vec(), b"CAP".to_vec(), 1001, Some(1000))],
+        balances: vec![],
+    });
+}
+
+fn run_to_block(n: u64) {
+    while System::block_number() < n {
+        let next = System::block_number() + 1;
+        System::set_block_number(next);
+        TemplateModule::on_initialize(next);
+    }
+}
+
+#[test]
+fn linear_emission_issues_every_block() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        assert_noop!(
+            TemplateModule::create_emission(
+                RuntimeOrigin::signed(BOB),
+                token_id,
+                BOB,
+                EmissionCurve::Linear { per_block: 10 },
+                2
+            ),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            TemplateModule::create_emission(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                BOB,
+                EmissionCurve::Linear { per_block: 0 },
+                2
+            ),
+            Error::<Test>::InvalidEmissionCurve
+        );
+
+        assert_ok!(TemplateModule::create_emission(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            BOB,
+            EmissionCurve::Linear { per_block: 10 },
+            2
+        ));
+        System::assert_last_event(
+            Event::EmissionCreated {
+                id: 0,
+                token_id,
+                beneficiary: BOB,
+            }
+            .into(),
+        );
+
+        run_to_block(2);
+        assert_eq!(TemplateModule::balance_of(token_id, BOB), 0);
+
+        run_to_block(5);
+        assert_eq!(TemplateModule::balance_of(token_id, BOB), 30);
+        assert_eq!(TemplateModule::tokens(token_id).unwrap().supply, 1030);
+        System::assert_last_event(
+            Event::Emitted {
+                id: 0,
+                token_id,
+                beneficiary: BOB,
+                amount: 10,
+            }
+            .into(),
+        );
+
+        assert_ok!(TemplateModule::cancel_emission(RuntimeOrigin::signed(ALICE), 0));
+        run_to_block(8);
+        assert_eq!(TemplateModule::balance_of(token_id, BOB), 30);
+        assert!(TemplateModule::emission_schedules(0).is_none());
+    });
+}
+
+#[test]
+fn halving_emission_halves_every_period() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+
+        assert_ok!(TemplateModule::create_emission(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            BOB,
+            EmissionCurve::Halving {
+                initial_per_block: 100,
+                halving_period: 2
+            },
+            1
+        ));
+
+        // 100 + 100 + 50 + 50 + 25 + 25
+        run_to_block(7);
+        assert_eq!(TemplateModule::balance_of(token_id, BOB), 350);
+    });
+}
+
+#[test]
+fn emission_stops_at_max_supply() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_capped(900, 1000);
+
+        assert_ok!(TemplateModule::create_emission(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            BOB,
+            EmissionCurve::Linear { per_block: 40 },
+            1
+        ));
+
+        run_to_block(4);
+        assert_eq!(TemplateModule::balance_of(token_id, BOB), 100);
+        assert_eq!(TemplateModule::tokens(token_id).unwrap().supply, 1000);
+        System::assert_last_event(Event::EmissionCompleted { id: 0 }.into());
+        assert!(TemplateModule::emission_schedules(0).is_none());
+    });
+}
+
+#[test]
+fn emissions_per_block_are_bounded() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let token_id = mint_to_alice(1000);
+        const EVE: u64 = 4;
+
+        for beneficiary in [BOB, DAVE, EVE] {
+            assert_ok!(TemplateModule::create_emission(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                beneficiary,
+                EmissionCurve::Linear { per_block: 1 },
+                1
+            ));
+        }
+
+        // MaxEmissionsPerBlock is 2 in the mock, so schedules take turns and
+        // catch up on whatever accrued in the blocks they were skipped
+        run_to_block(4);
+        assert_eq!(TemplateModule::balance_of(token_id, BOB), 2);
+        assert_eq!(TemplateModule::balance_of(token_id, DAVE), 3);
+        assert_eq!(TemplateModule::balance_of(token_id, EVE), 3);
+
+        run_to_block(5);
+        assert_eq!(TemplateModule::balance_of(token_id, BOB), 4);
+        assert_eq!(TemplateModule::balance_of(token_id, DAVE), 4);
+        assert_eq!(TemplateModule::balance_of(token_id, EVE), 3);
+    });
+}
+
+mod airdrops {
+    use super::*;
+    use sp_core::H256;
+    use sp_runtime::traits::{BlakeTwo256, Hash};
+
+    const EVE: u64 = 4;
+    const FERDIE: u64 = 5;
+
+    fn leaf(index: u32, who: u64, amount: u64) -> H256 {
+        BlakeTwo256::hash_of(&(index, who, amount))
+    }
+
+    fn parent(a: H256, b: H256) -> H256 {
+        if a <= b {
+            BlakeTwo256::hash_of(&(a, b))
+        } else {
+            BlakeTwo256::hash_of(&(b, a))
+        }
+    }
+
+    fn proof(hashes: Vec<H256>) -> BoundedVec<H256, ConstU32<8>> {
+        BoundedVec::try_from(hashes).unwrap()
+    }
+
+    // BOB 100, DAVE 200, EVE 300, FERDIE 400
+    struct Tree {
+        leaves: [H256; 4],
+        left: H256,
+        right: H256,
+        root: H256,
+    }
+
+    fn tree() -> Tree {
+        let leaves = [
+            leaf(0, BOB, 100),
+            leaf(1, DAVE, 200),
+            leaf(2, EVE, 300),
+            leaf(3, FERDIE, 400),
+        ];
+        let left = parent(leaves[0], leaves[1]);
+        let right = parent(leaves[2], leaves[3]);
+
+        Tree {
+            leaves,
+            left,
+            right,
+            root: parent(left, right),
+        }
+    }
+
+    fn create_airdrop(expiry: u64) -> (u64, Tree) {
+        let token_id = mint_to_alice(10_000);
+        let tree = tree();
+
+        assert_ok!(TemplateModule::create_airdrop(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            tree.root,
+            1000,
+            expiry
+        ));
+
+        (token_id, tree)
+    }
+
+    #[test]
+    fn creating_an_airdrop_escrows_tokens() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let (token_id, tree) = create_airdrop(10);
+
+            System::assert_last_event(
+                Event::AirdropCreated {
+                    id: 0,
+                    token_id,
+                    creator: ALICE,
+                    total: 1000,
+                }
+                .into(),
+            );
+
+            assert_eq!(TemplateModule::balance_of(token_id, ALICE), 9000);
+            assert_eq!(
+                TemplateModule::balance_of(token_id, TemplateModule::account_id()),
+                1000
+            );
+            assert_eq!(TemplateModule::airdrops(0).unwrap().merkle_root, tree.root);
+
+            assert_noop!(
+                TemplateModule::create_airdrop(
+                    RuntimeOrigin::signed(ALICE),
+                    token_id,
+                    tree.root,
+                    1000,
+                    1
+                ),
+                Error::<Test>::InvalidExpiry
+            );
+        });
+    }
+
+    #[test]
+    fn can_claim_with_valid_proof() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let (token_id, tree) = create_airdrop(10);
+
+            assert_ok!(TemplateModule::claim(
+                RuntimeOrigin::signed(DAVE),
+                0,
+                1,
+                200,
+                proof(vec![tree.leaves[0], tree.right])
+            ));
+            System::assert_last_event(
+                Event::Claimed {
+                    id: 0,
+                    index: 1,
+                    who: DAVE,
+                    amount: 200,
+                }
+                .into(),
+            );
+
+            assert_ok!(TemplateModule::claim(
+                RuntimeOrigin::signed(FERDIE),
+                0,
+                3,
+                400,
+                proof(vec![tree.leaves[2], tree.left])
+            ));
+
+            assert_eq!(TemplateModule::balance_of(token_id, DAVE), 200);
+            assert_eq!(TemplateModule::balance_of(token_id, FERDIE), 400);
+            assert_eq!(TemplateModule::airdrops(0).unwrap().remaining, 400);
+            assert!(TemplateModule::is_claimed(0, 1));
+            assert!(!TemplateModule::is_claimed(0, 0));
+            // leaves 1 and 3 share a bitmap word
+            assert_eq!(TemplateModule::airdrops(0).unwrap().claimed_words, 1);
+
+            assert_noop!(
+                TemplateModule::claim(
+                    RuntimeOrigin::signed(DAVE),
+                    0,
+                    1,
+                    200,
+                    proof(vec![tree.leaves[0], tree.right])
+                ),
+                Error::<Test>::AlreadyClaimed
+            );
+        });
+    }
+
+    #[test]
+    fn cannot_claim_with_invalid_proof() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let (_token_id, tree) = create_airdrop(10);
+
+            // wrong amount
+            assert_noop!(
+                TemplateModule::claim(
+                    RuntimeOrigin::signed(BOB),
+                    0,
+                    0,
+                    1000,
+                    proof(vec![tree.leaves[1], tree.right])
+                ),
+                Error::<Test>::InvalidProof
+            );
+
+            // someone else's leaf
+            assert_noop!(
+                TemplateModule::claim(
+                    RuntimeOrigin::signed(ALICE),
+                    0,
+                    0,
+                    100,
+                    proof(vec![tree.leaves[1], tree.right])
+                ),
+                Error::<Test>::InvalidProof
+            );
+
+            // wrong siblings
+            assert_noop!(
+                TemplateModule::claim(
+                    RuntimeOrigin::signed(BOB),
+                    0,
+                    0,
+                    100,
+                    proof(vec![tree.leaves[2], tree.right])
+                ),
+                Error::<Test>::InvalidProof
+            );
+        });
+    }
+
+    #[test]
+    fn creator_can_reclaim_after_expiry() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let (token_id, tree) = create_airdrop(10);
+
+            assert_ok!(TemplateModule::claim(
+                RuntimeOrigin::signed(BOB),
+                0,
+                0,
+                100,
+                proof(vec![tree.leaves[1], tree.right])
+            ));
+
+            assert_noop!(
+                TemplateModule::reclaim(RuntimeOrigin::signed(ALICE), 0, 1),
+                Error::<Test>::AirdropNotExpired
+            );
+
+            System::set_block_number(10);
+
+            assert_noop!(
+                TemplateModule::claim(
+                    RuntimeOrigin::signed(DAVE),
+                    0,
+                    1,
+                    200,
+                    proof(vec![tree.leaves[0], tree.right])
+                ),
+                Error::<Test>::AirdropExpired
+            );
+            assert_noop!(
+                TemplateModule::reclaim(RuntimeOrigin::signed(BOB), 0, 1),
+                Error::<Test>::NoPermission
+            );
+            // the one claim wrote one bitmap word
+            assert_noop!(
+                TemplateModule::reclaim(RuntimeOrigin::signed(ALICE), 0, 0),
+                Error::<Test>::BadWitness
+            );
+
+            assert_ok!(TemplateModule::reclaim(RuntimeOrigin::signed(ALICE), 0, 1));
+            System::assert_last_event(Event::AirdropReclaimed { id: 0, amount: 900 }.into());
+
+            assert_eq!(TemplateModule::balance_of(token_id, ALICE), 9900);
+            assert!(TemplateModule::airdrops(0).is_none());
+            assert!(!TemplateModule::is_claimed(0, 0));
+        });
+    }
+}
+
+mod escrows {
+    use super::*;
+
+    fn escrow_to_bob(unlock_at: u64) -> u64 {
+        let token_id = mint_to_alice(1000);
+
+        assert_ok!(TemplateModule::escrow_transfer(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            BOB,
+            300,
+            unlock_at
+        ));
+
+        token_id
+    }
+
+    #[test]
+    fn escrowed_tokens_leave_the_sender() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = escrow_to_bob(10);
+
+            System::assert_last_event(
+                Event::EscrowCreated {
+                    id: 0,
+                    token_id,
+                    from: ALICE,
+                    to: BOB,
+                    amount: 300,
+                    unlock_at: 10,
+                }
+                .into(),
+            );
+
+            assert_eq!(TemplateModule::balance_of(token_id, ALICE), 700);
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 0);
+            assert_eq!(
+                TemplateModule::balance_of(token_id, TemplateModule::account_id()),
+                300
+            );
+
+            assert_noop!(
+                TemplateModule::escrow_transfer(RuntimeOrigin::signed(ALICE), token_id, BOB, 1, 1),
+                Error::<Test>::InvalidExpiry
+            );
+        });
+    }
+
+    #[test]
+    fn recipient_can_claim_after_unlock() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = escrow_to_bob(10);
+
+            assert_noop!(
+                TemplateModule::claim_escrow(RuntimeOrigin::signed(BOB), 0),
+                Error::<Test>::EscrowLocked
+            );
+
+            System::set_block_number(10);
+
+            assert_noop!(
+                TemplateModule::claim_escrow(RuntimeOrigin::signed(DAVE), 0),
+                Error::<Test>::NoPermission
+            );
+            assert_noop!(
+                TemplateModule::cancel_escrow(RuntimeOrigin::signed(ALICE), 0),
+                Error::<Test>::EscrowUnlocked
+            );
+
+            assert_ok!(TemplateModule::claim_escrow(RuntimeOrigin::signed(BOB), 0));
+            System::assert_last_event(
+                Event::EscrowClaimed {
+                    id: 0,
+                    who: BOB,
+                    amount: 300,
+                }
+                .into(),
+            );
+
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 300);
+            assert!(TemplateModule::escrows(0).is_none());
+            assert_noop!(
+                TemplateModule::claim_escrow(RuntimeOrigin::signed(BOB), 0),
+                Error::<Test>::NoneEscrow
+            );
+        });
+    }
+
+    #[test]
+    fn sender_can_cancel_before_unlock() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = escrow_to_bob(10);
+
+            assert_noop!(
+                TemplateModule::cancel_escrow(RuntimeOrigin::signed(BOB), 0),
+                Error::<Test>::NoPermission
+            );
+
+            System::set_block_number(9);
+            assert_ok!(TemplateModule::cancel_escrow(RuntimeOrigin::signed(ALICE), 0));
+            System::assert_last_event(
+                Event::EscrowCancelled {
+                    id: 0,
+                    who: ALICE,
+                    amount: 300,
+                }
+                .into(),
+            );
+
+            assert_eq!(TemplateModule::balance_of(token_id, ALICE), 1000);
+            assert!(TemplateModule::escrows(0).is_none());
+        });
+    }
+}
+
+mod streams {
+    use super::*;
+
+    // 10 per block from block 10 to block 20
+    fn stream_to_bob() -> u64 {
+        let token_id = mint_to_alice(1000);
+
+        assert_ok!(TemplateModule::create_stream(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            token_id,
+            10,
+            10,
+            20
+        ));
+
+        token_id
+    }
+
+    #[test]
+    fn creating_a_stream_escrows_the_deposit() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = stream_to_bob();
+
+            System::assert_last_event(
+                Event::StreamCreated {
+                    id: 0,
+                    token_id,
+                    sender: ALICE,
+                    recipient: BOB,
+                    deposit: 100,
+                }
+                .into(),
+            );
+
+            assert_eq!(TemplateModule::balance_of(token_id, ALICE), 900);
+            assert_eq!(
+                TemplateModule::balance_of(token_id, TemplateModule::account_id()),
+                100
+            );
+
+            assert_noop!(
+                TemplateModule::create_stream(RuntimeOrigin::signed(ALICE), BOB, token_id, 10, 5, 5),
+                Error::<Test>::InvalidStream
+            );
+            assert_noop!(
+                TemplateModule::create_stream(RuntimeOrigin::signed(ALICE), BOB, token_id, 10, 0, 5),
+                Error::<Test>::InvalidStream
+            );
+        });
+    }
+
+    #[test]
+    fn recipient_withdraws_what_accrued() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = stream_to_bob();
+
+            System::set_block_number(10);
+            assert_noop!(
+                TemplateModule::withdraw_from_stream(RuntimeOrigin::signed(BOB), 0),
+                Error::<Test>::NothingToWithdraw
+            );
+
+            System::set_block_number(13);
+            assert_noop!(
+                TemplateModule::withdraw_from_stream(RuntimeOrigin::signed(ALICE), 0),
+                Error::<Test>::NoPermission
+            );
+            assert_ok!(TemplateModule::withdraw_from_stream(
+                RuntimeOrigin::signed(BOB),
+                0
+            ));
+            System::assert_last_event(
+                Event::WithdrawnFromStream {
+                    id: 0,
+                    recipient: BOB,
+                    amount: 30,
+                }
+                .into(),
+            );
+
+            System::set_block_number(15);
+            assert_ok!(TemplateModule::withdraw_from_stream(
+                RuntimeOrigin::signed(BOB),
+                0
+            ));
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 50);
+            assert_eq!(TemplateModule::streams(0).unwrap().withdrawn, 50);
+
+            // nothing accrues after stop, and the stream is gone once drained
+            System::set_block_number(100);
+            assert_ok!(TemplateModule::withdraw_from_stream(
+                RuntimeOrigin::signed(BOB),
+                0
+            ));
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 100);
+            assert!(TemplateModule::streams(0).is_none());
+        });
+    }
+
+    #[test]
+    fn cancelling_splits_the_deposit() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = stream_to_bob();
+
+            System::set_block_number(12);
+            assert_ok!(TemplateModule::withdraw_from_stream(
+                RuntimeOrigin::signed(BOB),
+                0
+            ));
+
+            System::set_block_number(16);
+            assert_noop!(
+                TemplateModule::cancel_stream(RuntimeOrigin::signed(DAVE), 0),
+                Error::<Test>::NoPermission
+            );
+            assert_ok!(TemplateModule::cancel_stream(RuntimeOrigin::signed(ALICE), 0));
+            System::assert_last_event(
+                Event::StreamCancelled {
+                    id: 0,
+                    recipient_amount: 40,
+                    sender_amount: 40,
+                }
+                .into(),
+            );
+
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 60);
+            assert_eq!(TemplateModule::balance_of(token_id, ALICE), 940);
+            assert_eq!(
+                TemplateModule::balance_of(token_id, TemplateModule::account_id()),
+                0
+            );
+            assert!(TemplateModule::streams(0).is_none());
+        });
+    }
+}
+
+mod force {
+    use super::*;
+    use sp_runtime::DispatchError::BadOrigin;
+
+    fn name(name: &[u8]) -> BoundedVec<u8, ConstU32<20>> {
+        name.to_vec().try_into().unwrap()
+    }
+
+    fn symbol(symbol: &[u8]) -> BoundedVec<u8, ConstU32<5>> {
+        symbol.to_vec().try_into().unwrap()
+    }
+
+    #[test]
+    fn force_calls_require_force_origin() {
+        new_test_ext().execute_with(|| {
+            let token_id = mint_to_alice(1000);
+
+            assert_noop!(
+                TemplateModule::force_transfer(RuntimeOrigin::signed(ALICE), token_id, ALICE, BOB, 1),
+                BadOrigin
+            );
+            assert_noop!(
+                TemplateModule::force_set_balance(RuntimeOrigin::signed(ALICE), token_id, BOB, 1),
+                BadOrigin
+            );
+            assert_noop!(
+                TemplateModule::force_set_metadata(
+                    RuntimeOrigin::signed(ALICE),
+                    token_id,
+                    name(b"Name"),
+                    symbol(b"SYM")
+                ),
+                BadOrigin
+            );
+            assert_noop!(
+                TemplateModule::force_set_team(RuntimeOrigin::signed(ALICE), token_id, BOB, BOB),
+                BadOrigin
+            );
+            assert_noop!(
+                TemplateModule::force_cancel_approval(
+                    RuntimeOrigin::signed(ALICE),
+                    token_id,
+                    ALICE,
+                    BOB
+                ),
+                BadOrigin
+            );
+        });
+    }
+
+    #[test]
+    fn can_force_transfer() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(1000);
+
+            assert_ok!(TemplateModule::force_transfer(
+                RuntimeOrigin::root(),
+                token_id,
+                ALICE,
+                BOB,
+                400
+            ));
+            System::assert_last_event(
+                Event::ForceTransferred {
+                    token_id,
+                    from: ALICE,
+                    to: BOB,
+                    amount: 400,
+                }
+                .into(),
+            );
+
+            assert_eq!(TemplateModule::balance_of(token_id, ALICE), 600);
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 400);
+
+            assert_noop!(
+                TemplateModule::force_transfer(RuntimeOrigin::root(), token_id, ALICE, BOB, 601),
+                Error::<Test>::InsufficientBalance
+            );
+        });
+    }
+
+    #[test]
+    fn force_transfer_skips_transfer_rules() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(1000);
+
+            assert_ok!(TemplateModule::vested_transfer(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                DAVE,
+                VestingSchedule::new(100, 10, 1, 5)
+            ));
+            assert_ok!(TemplateModule::delegate(
+                RuntimeOrigin::signed(BOB),
+                token_id,
+                BOB
+            ));
+            assert_ok!(TemplateModule::set_permissioned(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                true
+            ));
+            assert_ok!(TemplateModule::set_rate_limit(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                Some(crate::RateLimit { amount: 1, window: 10 })
+            ));
+            assert_ok!(TemplateModule::set_transfer_fee(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                Some((sp_runtime::Permill::from_percent(10), ALICE))
+            ));
+            BlockedSenders::set(vec![DAVE]);
+            TransfersSeen::set(vec![]);
+
+            assert_ok!(TemplateModule::force_transfer(
+                RuntimeOrigin::root(),
+                token_id,
+                DAVE,
+                BOB,
+                100
+            ));
+
+            assert_eq!(TemplateModule::balance_of(token_id, DAVE), 0);
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 100);
+            assert_eq!(TemplateModule::balance_of(token_id, ALICE), 900);
+            assert_eq!(TemplateModule::get_votes(token_id, &BOB), 100);
+            assert!(TransfersSeen::get().is_empty());
+        });
+    }
+
+    #[test]
+    fn force_set_balance_moves_supply_and_votes() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_capped(1000, 1500);
+
+            assert_ok!(TemplateModule::delegate(
+                RuntimeOrigin::signed(BOB),
+                token_id,
+                BOB
+            ));
+
+            assert_ok!(TemplateModule::force_set_balance(
+                RuntimeOrigin::root(),
+                token_id,
+                BOB,
+                300
+            ));
+            System::assert_last_event(
+                Event::BalanceForceSet {
+                    token_id,
+                    who: BOB,
+                    old_balance: 0,
+                    new_balance: 300,
+                }
+                .into(),
+            );
+            assert_eq!(TemplateModule::tokens(token_id).unwrap().supply, 1300);
+            assert_eq!(TemplateModule::get_votes(token_id, &BOB), 300);
+
+            assert_noop!(
+                TemplateModule::force_set_balance(RuntimeOrigin::root(), token_id, BOB, 501),
+                Error::<Test>::MaxSupplyExceeded
+            );
+
+            assert_ok!(TemplateModule::force_set_balance(
+                RuntimeOrigin::root(),
+                token_id,
+                BOB,
+                100
+            ));
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 100);
+            assert_eq!(TemplateModule::tokens(token_id).unwrap().supply, 1100);
+            assert_eq!(TemplateModule::get_votes(token_id, &BOB), 100);
+
+            assert_noop!(
+                TemplateModule::force_set_balance(RuntimeOrigin::root(), 99, BOB, 1),
+                Error::<Test>::NoneToken
+            );
+        });
+    }
+
+    #[test]
+    fn can_force_set_metadata_and_team() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(1000);
+
+            assert_ok!(TemplateModule::force_set_metadata(
+                RuntimeOrigin::root(),
+                token_id,
+                name(b"Renamed"),
+                symbol(b"RNM")
+            ));
+            System::assert_last_event(
+                Event::MetadataForceSet {
+                    token_id,
+                    name: name(b"Renamed"),
+                    symbol: symbol(b"RNM"),
+                }
+                .into(),
+            );
+
+            assert_ok!(TemplateModule::force_set_team(
+                RuntimeOrigin::root(),
+                token_id,
+                BOB,
+                DAVE
+            ));
+            System::assert_last_event(
+                Event::TeamForceSet {
+                    token_id,
+                    owner: BOB,
+                    admin: DAVE,
+                }
+                .into(),
+            );
+
+            let token = TemplateModule::tokens(token_id).unwrap();
+            assert_eq!(token.name, name(b"Renamed"));
+            assert_eq!(token.symbol, symbol(b"RNM"));
+            assert_eq!(token.owner, BOB);
+            assert_eq!(token.admin, DAVE);
+
+            // the old owner lost their rights
+            assert_noop!(
+                TemplateModule::snapshot(RuntimeOrigin::signed(ALICE), token_id),
+                Error::<Test>::NoPermission
+            );
+            assert_ok!(TemplateModule::snapshot(RuntimeOrigin::signed(BOB), token_id));
+        });
+    }
+
+    #[test]
+    fn can_force_cancel_approval() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(1000);
+
+            assert_noop!(
+                TemplateModule::force_cancel_approval(RuntimeOrigin::root(), token_id, ALICE, BOB),
+                Error::<Test>::NotApproved
+            );
+
+            assert_ok!(TemplateModule::approve(
+                RuntimeOrigin::signed(ALICE),
+                BOB,
+                token_id,
+                500
+            ));
+            assert_ok!(TemplateModule::force_cancel_approval(
+                RuntimeOrigin::root(),
+                token_id,
+                ALICE,
+                BOB
+            ));
+            System::assert_last_event(
+                Event::ApprovalForceCancelled {
+                    token_id,
+                    owner: ALICE,
+                    spender: BOB,
+                }
+                .into(),
+            );
+
+            assert_eq!(TemplateModule::allowance((token_id, ALICE, BOB)), 0);
+            assert_noop!(
+                TemplateModule::transfer_from(RuntimeOrigin::signed(BOB), token_id, ALICE, DAVE, 1),
+                Error::<Test>::NotApproved
+            );
+        });
+    }
+}
+
+mod create_origin {
+    use super::*;
+    use sp_runtime::{DispatchError::BadOrigin, DispatchResult};
+
+    fn mint_as(origin: RuntimeOrigin) -> DispatchResult {
+        let token = create_token(1000);
+
+        TemplateModule::mint(origin, token.name, token.symbol, token.supply, None)
+    }
+
+    #[test]
+    fn anyone_can_create() {
+        new_test_ext().execute_with(|| {
+            CreateOriginMode::set(CreateMode::Anyone);
+
+            assert_ok!(mint_as(RuntimeOrigin::signed(BOB)));
+            assert_eq!(TemplateModule::tokens(1).unwrap().owner, BOB);
+
+            assert_noop!(mint_as(RuntimeOrigin::root()), BadOrigin);
+            assert_noop!(mint_as(RuntimeOrigin::none()), BadOrigin);
+        });
+    }
+
+    #[test]
+    fn only_root_can_create() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            CreateOriginMode::set(CreateMode::RootOnly);
+
+            assert_noop!(mint_as(RuntimeOrigin::signed(ALICE)), BadOrigin);
+
+            assert_ok!(mint_as(RuntimeOrigin::root()));
+            System::assert_last_event(
+                Event::TokenMinted {
+                    token_id: 1,
+                    who: RootTokenOwner::get(),
+                }
+                .into(),
+            );
+
+            assert_eq!(TemplateModule::tokens(1).unwrap().owner, RootTokenOwner::get());
+            assert_eq!(TemplateModule::balance_of(1, RootTokenOwner::get()), 1000);
+        });
+    }
+
+    #[test]
+    fn only_allowlisted_accounts_can_create() {
+        new_test_ext().execute_with(|| {
+            CreateOriginMode::set(CreateMode::Allowlisted);
+            Creators::set(vec![DAVE]);
+
+            assert_noop!(mint_as(RuntimeOrigin::signed(ALICE)), BadOrigin);
+            assert_noop!(mint_as(RuntimeOrigin::root()), BadOrigin);
+
+            assert_ok!(mint_as(RuntimeOrigin::signed(DAVE)));
+            assert_eq!(TemplateModule::tokens(1).unwrap().owner, DAVE);
+        });
+    }
+}
+
+mod operators {
+    use super::*;
+
+    #[test]
+    fn can_set_and_revoke_operator() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+
+            assert_ok!(TemplateModule::set_operator(RuntimeOrigin::signed(ALICE), BOB, true));
+            System::assert_last_event(
+                Event::OperatorSet {
+                    owner: ALICE,
+                    operator: BOB,
+                    approved: true,
+                }
+                .into(),
+            );
+            assert!(TemplateModule::is_operator(&ALICE, &BOB));
+            assert!(!TemplateModule::is_operator(&BOB, &ALICE));
+
+            assert_ok!(TemplateModule::set_operator(RuntimeOrigin::signed(ALICE), BOB, false));
+            System::assert_last_event(
+                Event::OperatorSet {
+                    owner: ALICE,
+                    operator: BOB,
+                    approved: false,
+                }
+                .into(),
+            );
+            assert!(!TemplateModule::is_operator(&ALICE, &BOB));
+        });
+    }
+
+    #[test]
+    fn operator_can_transfer_every_token() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let first = mint_to_alice(1000);
+            let second = mint_to_alice(500);
+
+            assert_ok!(TemplateModule::set_operator(RuntimeOrigin::signed(ALICE), BOB, true));
+
+            assert_ok!(TemplateModule::transfer_from(
+                RuntimeOrigin::signed(BOB),
+                first,
+                ALICE,
+                DAVE,
+                1000
+            ));
+            assert_ok!(TemplateModule::transfer_from(
+                RuntimeOrigin::signed(BOB),
+                second,
+                ALICE,
+                BOB,
+                200
+            ));
+
+            assert_eq!(TemplateModule::balance_of(first, DAVE), 1000);
+            assert_eq!(TemplateModule::balance_of(second, BOB), 200);
+            // no allowance was consumed or created
+            assert_eq!(TemplateModule::allowance((second, ALICE, BOB)), 0);
+
+            assert_noop!(
+                TemplateModule::transfer_from(RuntimeOrigin::signed(BOB), second, ALICE, BOB, 301),
+                Error::<Test>::InsufficientBalance
+            );
+        });
+    }
+
+    #[test]
+    fn revoked_operator_falls_back_to_allowance() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(1000);
+
+            assert_ok!(TemplateModule::approve(
+                RuntimeOrigin::signed(ALICE),
+                BOB,
+                token_id,
+                100
+            ));
+            assert_ok!(TemplateModule::set_operator(RuntimeOrigin::signed(ALICE), BOB, true));
+            assert_ok!(TemplateModule::transfer_from(
+                RuntimeOrigin::signed(BOB),
+                token_id,
+                ALICE,
+                BOB,
+                500
+            ));
+            assert_eq!(TemplateModule::allowance((token_id, ALICE, BOB)), 100);
+
+            assert_ok!(TemplateModule::set_operator(RuntimeOrigin::signed(ALICE), BOB, false));
+            assert_noop!(
+                TemplateModule::transfer_from(RuntimeOrigin::signed(BOB), token_id, ALICE, BOB, 101),
+                Error::<Test>::InsufficientBalance
+            );
+            assert_ok!(TemplateModule::transfer_from(
+                RuntimeOrigin::signed(BOB),
+                token_id,
+                ALICE,
+                BOB,
+                100
+            ));
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 600);
+        });
+    }
+}
+
+mod batch_transfers {
+    use super::*;
+
+    fn batch(transfers: Vec<(u64, u64)>) -> BoundedVec<(u64, u64), ConstU32<3>> {
+        transfers.try_into().unwrap()
+    }
+
+    #[test]
+    fn owner_can_batch_transfer() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let first = mint_to_alice(1000);
+            let second = mint_to_alice(500);
+
+            assert_ok!(TemplateModule::safe_batch_transfer_from(
+                RuntimeOrigin::signed(ALICE),
+                ALICE,
+                BOB,
+                batch(vec![(first, 100), (second, 200)])
+            ));
+            System::assert_last_event(
+                Event::TransferredBatch {
+                    operator: ALICE,
+                    from: ALICE,
+                    to: BOB,
+                    transfers: batch(vec![(first, 100), (second, 200)]),
+                }
+                .into(),
+            );
+
+            assert_eq!(TemplateModule::balance_of(first, BOB), 100);
+            assert_eq!(TemplateModule::balance_of(second, BOB), 200);
+            assert_eq!(TemplateModule::balance_of(second, ALICE), 300);
+        });
+    }
+
+    #[test]
+    fn batch_transfer_is_atomic() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let first = mint_to_alice(1000);
+            let second = mint_to_alice(500);
+
+            assert_noop!(
+                TemplateModule::safe_batch_transfer_from(
+                    RuntimeOrigin::signed(ALICE),
+                    ALICE,
+                    BOB,
+                    batch(vec![(first, 100), (second, 501)])
+                ),
+                Error::<Test>::InsufficientBalance
+            );
+        });
+    }
+
+    #[test]
+    fn batch_transfer_checks_allowance_per_token() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let first = mint_to_alice(1000);
+            let second = mint_to_alice(500);
+
+            assert_ok!(TemplateModule::approve(
+                RuntimeOrigin::signed(ALICE),
+                BOB,
+                first,
+                100
+            ));
+
+            // no allowance for the second token
+            assert_noop!(
+                TemplateModule::safe_batch_transfer_from(
+                    RuntimeOrigin::signed(BOB),
+                    ALICE,
+                    DAVE,
+                    batch(vec![(first, 100), (second, 1)])
+                ),
+                Error::<Test>::NotApproved
+            );
+
+            assert_ok!(TemplateModule::approve(
+                RuntimeOrigin::signed(ALICE),
+                BOB,
+                second,
+                50
+            ));
+            assert_ok!(TemplateModule::safe_batch_transfer_from(
+                RuntimeOrigin::signed(BOB),
+                ALICE,
+                DAVE,
+                batch(vec![(first, 60), (second, 50)])
+            ));
+
+            assert_eq!(TemplateModule::allowance((first, ALICE, BOB)), 40);
+            assert_eq!(TemplateModule::allowance((second, ALICE, BOB)), 0);
+            assert_eq!(TemplateModule::balance_of(first, DAVE), 60);
+            assert_eq!(TemplateModule::balance_of(second, DAVE), 50);
+        });
+    }
+
+    #[test]
+    fn operator_can_batch_transfer() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let first = mint_to_alice(1000);
+            let second = mint_to_alice(500);
+
+            assert_ok!(TemplateModule::set_operator(RuntimeOrigin::signed(ALICE), BOB, true));
+            assert_ok!(TemplateModule::safe_batch_transfer_from(
+                RuntimeOrigin::signed(BOB),
+                ALICE,
+                DAVE,
+                batch(vec![(first, 1000), (second, 500)])
+            ));
+
+            assert_eq!(TemplateModule::balance_of(first, DAVE), 1000);
+            assert_eq!(TemplateModule::balance_of(second, DAVE), 500);
+        });
+    }
+}
+
+mod zero_rows {
+    use super::*;
+    use crate::{Allowance, BalanceOf};
+
+    #[test]
+    fn draining_balances_and_allowances_leaves_no_rows() {
+        new_test_ext().execute_with(|| {
+            let token_id = mint_to_alice(1000);
+
+            assert_ok!(TemplateModule::transfer(
+                RuntimeOrigin::signed(ALICE),
+                BOB,
+                token_id,
+                1000
+            ));
+            assert!(!BalanceOf::<Test>::contains_key(token_id, ALICE));
+
+            assert_ok!(TemplateModule::approve(
+                RuntimeOrigin::signed(BOB),
+                DAVE,
+                token_id,
+                1000
+            ));
+            assert_ok!(TemplateModule::transfer_from(
+                RuntimeOrigin::signed(DAVE),
+                token_id,
+                BOB,
+                ALICE,
+                1000
+            ));
+
+            // everything is back with ALICE, and only her row is left
+            assert_eq!(
+                BalanceOf::<Test>::iter_prefix(token_id).collect::<Vec<_>>(),
+                vec![(ALICE, 1000)]
+            );
+            assert_eq!(Allowance::<Test>::iter().count(), 0);
+        });
+    }
+
+    #[test]
+    fn approving_zero_removes_the_allowance() {
+        new_test_ext().execute_with(|| {
+            let token_id = mint_to_alice(1000);
+
+            assert_ok!(TemplateModule::approve(RuntimeOrigin::signed(ALICE), BOB, token_id, 10));
+            assert!(Allowance::<Test>::contains_key((token_id, ALICE, BOB)));
+
+            assert_ok!(TemplateModule::approve(RuntimeOrigin::signed(ALICE), BOB, token_id, 0));
+            assert!(!Allowance::<Test>::contains_key((token_id, ALICE, BOB)));
+        });
+    }
+
+    #[test]
+    fn force_setting_zero_removes_the_balance() {
+        new_test_ext().execute_with(|| {
+            let token_id = mint_to_alice(1000);
+
+            assert_ok!(TemplateModule::force_set_balance(
+                RuntimeOrigin::root(),
+                token_id,
+                ALICE,
+                0
+            ));
+            assert_eq!(BalanceOf::<Test>::iter_prefix(token_id).count(), 0);
+        });
+    }
+}
+
+mod rehash {
+    use super::*;
+    use crate::{
+        migrations::{v2::old, MIGRATION_BATCH},
+        AccountTokens, Allowance, BalanceOf, Rehash,
+    };
+    use frame_support::{
+        traits::{GetStorageVersion, StorageVersion},
+        weights::Weight,
+    };
+
+    const HOLDERS: u64 = 1000;
+
+    /// A v1 state with `HOLDERS` balances, every tenth of them zero, on each of two
+    /// tokens, and an allowance from each holder to `BOB`.
+    fn v1_state() {
+        for token_id in 1..=2 {
+            for who in 100..100 + HOLDERS {
+                let balance = if who % 10 == 0 { 0 } else { who * token_id };
+                old::BalanceOf::<Test>::insert(token_id, who, balance);
+                old::Allowance::<Test>::insert((token_id, who, BOB), balance / 2);
+            }
+        }
+        StorageVersion::new(1).put::<TemplateModule>();
+    }
+
+    fn migrate() -> u32 {
+        let mut blocks = 0;
+        while Rehash::<Test>::exists() {
+            blocks += 1;
+            TemplateModule::on_idle(blocks.into(), Weight::MAX);
+        }
+        blocks
+    }
+
+    #[test]
+    fn moves_every_row_over_several_blocks() {
+        new_test_ext().execute_with(|| {
+            v1_state();
+
+            TemplateModule::on_runtime_upgrade();
+            assert_eq!(TemplateModule::on_chain_storage_version(), 4);
+
+            // no block moves more than a batch
+            let rows = 4 * HOLDERS as u32;
+            assert!(migrate() >= rows / MIGRATION_BATCH);
+
+            for token_id in 1..=2 {
+                for who in 100..100 + HOLDERS {
+                    let balance = if who % 10 == 0 { 0 } else { who * token_id };
+
+                    assert_eq!(BalanceOf::<Test>::get(token_id, who), balance);
+                    assert_eq!(Allowance::<Test>::get((token_id, who, BOB)), balance / 2);
+                    // and the v3 index is built after
+                    assert_eq!(AccountTokens::<Test>::contains_key(who, token_id), balance > 0);
+                    assert!(!old::BalanceOf::<Test>::contains_key(token_id, who));
+                    assert!(!old::Allowance::<Test>::contains_key((token_id, who, BOB)));
+                }
+            }
+
+            // zero rows are dropped on the way
+            let non_zero = 2 * (HOLDERS - HOLDERS / 10) as usize;
+            assert_eq!(BalanceOf::<Test>::iter().count(), non_zero);
+            assert_eq!(Allowance::<Test>::iter().count(), non_zero);
+
+            // the migration only runs once
+            TemplateModule::on_runtime_upgrade();
+            assert!(!Rehash::<Test>::exists());
+        });
+    }
+
+    #[test]
+    fn balances_stay_usable_while_migrating() {
+        new_test_ext().execute_with(|| {
+            let token_id = mint_to_alice(1000);
+            old::BalanceOf::<Test>::insert(token_id, BOB, 300);
+            old::Allowance::<Test>::insert((token_id, BOB, DAVE), 100);
+            StorageVersion::new(1).put::<TemplateModule>();
+
+            TemplateModule::on_runtime_upgrade();
+
+            // nothing has been moved yet
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 300);
+            assert_eq!(TemplateModule::allowance((token_id, BOB, DAVE)), 100);
+
+            assert_ok!(TemplateModule::transfer_from(
+                RuntimeOrigin::signed(DAVE),
+                token_id,
+                BOB,
+                DAVE,
+                60
+            ));
+
+            // the rows written since live under the new key only
+            assert!(!old::BalanceOf::<Test>::contains_key(token_id, BOB));
+            assert!(!old::Allowance::<Test>::contains_key((token_id, BOB, DAVE)));
+            assert_eq!(BalanceOf::<Test>::get(token_id, BOB), 240);
+            assert_eq!(Allowance::<Test>::get((token_id, BOB, DAVE)), 40);
+
+            migrate();
+
+            assert_eq!(TemplateModule::balance_of(token_id, ALICE), 1000);
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 240);
+            assert_eq!(TemplateModule::balance_of(token_id, DAVE), 60);
+            assert_eq!(TemplateModule::allowance((token_id, BOB, DAVE)), 40);
+        });
+    }
+}
+
+mod portfolios {
+    use super::*;
+    use crate::{AccountTokens, BalanceOf, Rehash};
+    use frame_support::{
+        traits::{GetStorageVersion, StorageVersion},
+        weights::Weight,
+    };
+
+    fn ids(page: Vec<(u64, TokenDetails<Test>, u64)>) -> Vec<u64> {
+        page.into_iter().map(|(token_id, _, _)| token_id).collect()
+    }
+
+    #[test]
+    fn tracks_the_tokens_an_account_holds() {
+        new_test_ext().execute_with(|| {
+            let first = mint_to_alice(1000);
+            let second = mint_to_alice(500);
+
+            assert_ok!(TemplateModule::transfer(RuntimeOrigin::signed(ALICE), BOB, second, 200));
+
+            let mut alice = TemplateModule::portfolio(&ALICE, None, 10);
+            alice.sort_by_key(|(token_id, _, _)| *token_id);
+            let balances: Vec<_> =
+                alice.iter().map(|(token_id, _, balance)| (*token_id, *balance)).collect();
+            assert_eq!(balances, vec![(first, 1000), (second, 300)]);
+            assert_eq!(alice[1].1.supply, 500);
+
+            let bob = TemplateModule::portfolio(&BOB, None, 10);
+            assert_eq!(ids(bob), vec![second]);
+
+            // draining a balance takes the token out of the portfolio
+            assert_ok!(TemplateModule::transfer(RuntimeOrigin::signed(BOB), ALICE, second, 200));
+            assert!(TemplateModule::portfolio(&BOB, None, 10).is_empty());
+            assert_eq!(AccountTokens::<Test>::iter_prefix(BOB).count(), 0);
+        });
+    }
+
+    #[test]
+    fn pages_through_every_token_once() {
+        new_test_ext().execute_with(|| {
+            let minted: Vec<_> = (0..7).map(|_| mint_to_alice(100)).collect();
+
+            let mut tokens = Vec::new();
+            let mut holdings = Vec::new();
+            let mut start_after = None;
+            loop {
+                let page = ids(TemplateModule::token_page(start_after, 3));
+                assert!(page.len() <= 3);
+                start_after = match page.last() {
+                    Some(last) => Some(*last),
+                    None => break,
+                };
+                tokens.extend(page);
+            }
+            let mut start_after = None;
+            loop {
+                let page = ids(TemplateModule::portfolio(&ALICE, start_after, 2));
+                start_after = match page.last() {
+                    Some(last) => Some(*last),
+                    None => break,
+                };
+                holdings.extend(page);
+            }
+
+            tokens.sort();
+            holdings.sort();
+            assert_eq!(tokens, minted);
+            assert_eq!(holdings, minted);
+
+            // the supply comes with each token
+            let page = TemplateModule::token_page(None, 1);
+            assert_eq!(page[0].2, 100);
+            assert_eq!(page[0].1.supply, 100);
+        });
+    }
+
+    #[test]
+    fn migration_indexes_existing_balances() {
+        new_test_ext().execute_with(|| {
+            let token_id = mint_to_alice(1000);
+            for who in 100..400 {
+                BalanceOf::<Test>::insert(token_id, who, 1);
+            }
+            AccountTokens::<Test>::remove(ALICE, token_id);
+            StorageVersion::new(2).put::<TemplateModule>();
+
+            TemplateModule::on_runtime_upgrade();
+            assert_eq!(TemplateModule::on_chain_storage_version(), 4);
+
+            let mut blocks = 0;
+            while Rehash::<Test>::exists() {
+                blocks += 1;
+                TemplateModule::on_idle(blocks, Weight::MAX);
+            }
+            assert!(blocks > 1);
+
+            assert_eq!(ids(TemplateModule::portfolio(&ALICE, None, 10)), vec![token_id]);
+            for who in 100..400 {
+                assert_eq!(ids(TemplateModule::portfolio(&who, None, 10)), vec![token_id]);
+            }
+        });
+    }
+}
+
+mod memos {
+    use super::*;
+
+    fn memo(bytes: &[u8]) -> BoundedVec<u8, ConstU32<32>> {
+        BoundedVec::try_from(bytes.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn transfers_and_emits_the_memo() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(1000);
+
+            assert_ok!(TemplateModule::transfer_with_memo(
+                RuntimeOrigin::signed(ALICE),
+                BOB,
+                token_id,
+                300,
+                memo(b"INV-2023-0042")
+            ));
+
+            assert_eq!(TemplateModule::balance_of(token_id, ALICE), 700);
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 300);
+            System::assert_last_event(
+                Event::TransferredWithMemo {
+                    token_id,
+                    from: ALICE,
+                    to: BOB,
+                    amount: 300,
+                    memo: memo(b"INV-2023-0042"),
+                }
+                .into(),
+            );
+        });
+    }
+
+    #[test]
+    fn fails_like_transfer() {
+        new_test_ext().execute_with(|| {
+            let token_id = mint_to_alice(1000);
+
+            assert_noop!(
+                TemplateModule::transfer_with_memo(
+                    RuntimeOrigin::signed(BOB),
+                    ALICE,
+                    token_id,
+                    1,
+                    memo(b"INV-1")
+                ),
+                Error::<Test>::InsufficientBalance
+            );
+        });
+    }
+}
+
+mod rate_limits {
+    use super::*;
+    use crate::{Outflows, RateLimit};
+    use sp_runtime::DispatchResult;
+
+    fn limit(amount: u64, window: u64) -> Option<RateLimit<u64, u64>> {
+        Some(RateLimit { amount, window })
+    }
+
+    fn transfer(from: u64, to: u64, token_id: u64, amount: u64) -> DispatchResult {
+        TemplateModule::transfer(RuntimeOrigin::signed(from), to, token_id, amount)
+    }
+
+    #[test]
+    fn only_the_admin_sets_valid_limits() {
+        new_test_ext().execute_with(|| {
+            let token_id = mint_to_alice(1000);
+
+            assert_noop!(
+                TemplateModule::set_rate_limit(RuntimeOrigin::signed(BOB), token_id, limit(1, 1)),
+                Error::<Test>::NoPermission
+            );
+            assert_noop!(
+                TemplateModule::set_rate_limit(RuntimeOrigin::signed(ALICE), token_id, limit(0, 1)),
+                Error::<Test>::InvalidRateLimit
+            );
+            assert_noop!(
+                TemplateModule::set_rate_limit(RuntimeOrigin::signed(ALICE), token_id, limit(1, 0)),
+                Error::<Test>::InvalidRateLimit
+            );
+
+            System::set_block_number(1);
+            assert_ok!(TemplateModule::set_rate_limit(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                limit(100, 10)
+            ));
+            System::assert_last_event(
+                Event::RateLimitSet {
+                    token_id,
+                    limit: limit(100, 10),
+                }
+                .into(),
+            );
+        });
+    }
+
+    #[test]
+    fn usage_expires_once_it_is_a_window_old() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(1000);
+            assert_ok!(TemplateModule::set_rate_limit(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                limit(100, 10)
+            ));
+
+            assert_ok!(transfer(ALICE, BOB, token_id, 60));
+            assert_noop!(transfer(ALICE, BOB, token_id, 41), Error::<Test>::RateLimited);
+            assert_ok!(transfer(ALICE, BOB, token_id, 40));
+
+            // what was sent at block 1 counts until block 11
+            System::set_block_number(10);
+            assert_noop!(transfer(ALICE, BOB, token_id, 1), Error::<Test>::RateLimited);
+
+            System::set_block_number(11);
+            assert_ok!(transfer(ALICE, BOB, token_id, 100));
+            assert_noop!(transfer(ALICE, BOB, token_id, 1), Error::<Test>::RateLimited);
+
+            System::set_block_number(25);
+            assert_ok!(transfer(ALICE, BOB, token_id, 100));
+            System::set_block_number(34);
+            assert_noop!(transfer(ALICE, BOB, token_id, 1), Error::<Test>::RateLimited);
+            System::set_block_number(35);
+            assert_ok!(transfer(ALICE, BOB, token_id, 1));
+
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 301);
+        });
+    }
+
+    #[test]
+    fn the_limit_cannot_be_sent_twice_around_a_window_boundary() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(1000);
+            assert_ok!(TemplateModule::set_rate_limit(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                limit(100, 10)
+            ));
+
+            assert_ok!(transfer(ALICE, BOB, token_id, 1));
+            System::set_block_number(10);
+            assert_ok!(transfer(ALICE, BOB, token_id, 99));
+
+            // block 1 has left the window, block 10 hasn't
+            System::set_block_number(11);
+            assert_ok!(transfer(ALICE, BOB, token_id, 1));
+            assert_noop!(transfer(ALICE, BOB, token_id, 99), Error::<Test>::RateLimited);
+
+            System::set_block_number(19);
+            assert_noop!(transfer(ALICE, BOB, token_id, 1), Error::<Test>::RateLimited);
+            System::set_block_number(20);
+            assert_ok!(transfer(ALICE, BOB, token_id, 99));
+        });
+    }
+
+    #[test]
+    fn no_window_sends_more_than_the_limit() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(10_000);
+            assert_ok!(TemplateModule::set_rate_limit(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                limit(100, 10)
+            ));
+
+            let mut sent = Vec::new();
+            for block in 1..=60 {
+                System::set_block_number(block);
+                for amount in [30, 7, 1] {
+                    if transfer(ALICE, BOB, token_id, amount).is_ok() {
+                        sent.push((block, amount));
+                    }
+                }
+            }
+
+            // every run of 10 blocks, wherever it starts
+            for start in 1..=51 {
+                let in_window: u64 = sent
+                    .iter()
+                    .filter(|(block, _)| (start..start + 10).contains(block))
+                    .map(|(_, amount)| amount)
+                    .sum();
+                assert!(in_window <= 100);
+            }
+            assert_eq!(sent.iter().map(|(_, amount)| amount).sum::<u64>(), 600);
+        });
+    }
+
+    #[test]
+    fn buckets_stay_bounded() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(10_000);
+            assert_ok!(TemplateModule::set_rate_limit(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                limit(1000, 100)
+            ));
+
+            // with 10 buckets over 100 blocks each one covers 10 blocks
+            for block in 1..=30 {
+                System::set_block_number(block);
+                assert_ok!(transfer(ALICE, BOB, token_id, 10));
+            }
+            assert_eq!(Outflows::<Test>::get(token_id, ALICE).len(), 3);
+
+            // the first bucket counts until its last transfer, at block 10, is old
+            System::set_block_number(109);
+            assert_noop!(transfer(ALICE, BOB, token_id, 701), Error::<Test>::RateLimited);
+            System::set_block_number(110);
+            assert_ok!(transfer(ALICE, BOB, token_id, 800));
+            assert_eq!(Outflows::<Test>::get(token_id, ALICE).len(), 3);
+        });
+    }
+
+    #[test]
+    fn every_outgoing_transfer_counts() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(1000);
+            assert_ok!(TemplateModule::set_rate_limit(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                limit(100, 10)
+            ));
+            assert_ok!(TemplateModule::approve(RuntimeOrigin::signed(ALICE), DAVE, token_id, 500));
+
+            // the limit is on the owner of the funds, whoever moves them
+            assert_ok!(TemplateModule::transfer_from(
+                RuntimeOrigin::signed(DAVE),
+                token_id,
+                ALICE,
+                DAVE,
+                80
+            ));
+            assert_noop!(transfer(ALICE, BOB, token_id, 21), Error::<Test>::RateLimited);
+
+            // receiving doesn't use up BOB's limit
+            assert_ok!(transfer(ALICE, BOB, token_id, 20));
+            assert!(!Outflows::<Test>::contains_key(token_id, BOB));
+            assert_ok!(transfer(BOB, DAVE, token_id, 20));
+        });
+    }
+
+    #[test]
+    fn exempt_accounts_are_not_limited() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(1000);
+            assert_ok!(TemplateModule::set_rate_limit(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                limit(100, 10)
+            ));
+            let exempt: BoundedVec<_, _> = BoundedVec::try_from(vec![ALICE]).unwrap();
+
+            assert_noop!(
+                TemplateModule::add_rate_limit_exemptions(
+                    RuntimeOrigin::signed(BOB),
+                    token_id,
+                    exempt.clone()
+                ),
+                Error::<Test>::NoPermission
+            );
+            assert_ok!(TemplateModule::add_rate_limit_exemptions(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                exempt.clone()
+            ));
+            System::assert_last_event(
+                Event::RateLimitExemptionAdded {
+                    token_id,
+                    who: ALICE,
+                }
+                .into(),
+            );
+
+            assert_ok!(transfer(ALICE, BOB, token_id, 500));
+            assert!(!Outflows::<Test>::contains_key(token_id, ALICE));
+
+            assert_ok!(TemplateModule::remove_rate_limit_exemptions(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                exempt
+            ));
+            assert_noop!(transfer(ALICE, BOB, token_id, 101), Error::<Test>::RateLimited);
+        });
+    }
+
+    #[test]
+    fn lifting_the_limit_keeps_usage_until_the_window_ends() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(1000);
+            assert_ok!(TemplateModule::set_rate_limit(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                limit(100, 10)
+            ));
+            assert_ok!(transfer(ALICE, BOB, token_id, 100));
+
+            assert_ok!(TemplateModule::set_rate_limit(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                None
+            ));
+            // nothing is tracked without a limit, and the old row is left alone
+            assert_ok!(transfer(ALICE, BOB, token_id, 500));
+            assert_eq!(Outflows::<Test>::get(token_id, ALICE)[0].used, 100);
+
+            assert_ok!(TemplateModule::set_rate_limit(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                limit(100, 10)
+            ));
+            assert_noop!(transfer(ALICE, BOB, token_id, 1), Error::<Test>::RateLimited);
+
+            System::set_block_number(11);
+            assert_ok!(transfer(ALICE, BOB, token_id, 100));
+        });
+    }
+}
+
+mod transfer_fees {
+    use super::*;
+    use crate::{migrations::v4::old, Tokens};
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+    use sp_runtime::Permill;
+
+    const TREASURY: u64 = 99;
+
+    fn charge(token_id: u64, percent: u32) {
+        assert_ok!(TemplateModule::set_transfer_fee(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            Some((Permill::from_percent(percent), TREASURY))
+        ));
+    }
+
+    fn exempt(token_id: u64, who: u64) {
+        assert_ok!(TemplateModule::add_fee_exemptions(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            BoundedVec::try_from(vec![who]).unwrap()
+        ));
+    }
+
+    fn fee_charged(from: u64, to: u64, gross: u64, fee: u64, net: u64) -> RuntimeEvent {
+        Event::TransferFeeCharged {
+            token_id: 1,
+            from,
+            to,
+            beneficiary: TREASURY,
+            gross,
+            fee,
+            net,
+        }
+        .into()
+    }
+
+    #[test]
+    fn only_the_admin_sets_the_fee() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(10_000);
+
+            assert_noop!(
+                TemplateModule::set_transfer_fee(
+                    RuntimeOrigin::signed(BOB),
+                    token_id,
+                    Some((Permill::from_percent(1), BOB))
+                ),
+                Error::<Test>::NoPermission
+            );
+
+            charge(token_id, 1);
+            let fee = TransferFee {
+                rate: Permill::from_percent(1),
+                beneficiary: TREASURY,
+            };
+            assert_eq!(TemplateModule::tokens(token_id).unwrap().transfer_fee, Some(fee.clone()));
+            System::assert_last_event(
+                Event::TransferFeeSet {
+                    token_id,
+                    fee: Some(fee),
+                }
+                .into(),
+            );
+
+            assert_ok!(TemplateModule::set_transfer_fee(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                None
+            ));
+            assert_eq!(TemplateModule::tokens(token_id).unwrap().transfer_fee, None);
+        });
+    }
+
+    #[test]
+    fn transfers_pay_the_fee_to_the_beneficiary() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(10_000);
+            charge(token_id, 1);
+
+            assert_ok!(TemplateModule::transfer(RuntimeOrigin::signed(ALICE), BOB, token_id, 1000));
+
+            assert_eq!(TemplateModule::balance_of(token_id, ALICE), 9000);
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 990);
+            assert_eq!(TemplateModule::balance_of(token_id, TREASURY), 10);
+            System::assert_has_event(fee_charged(ALICE, BOB, 1000, 10, 990));
+        });
+    }
+
+    #[test]
+    fn transfer_from_spends_the_gross_allowance() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(10_000);
+            charge(token_id, 5);
+            assert_ok!(TemplateModule::approve(RuntimeOrigin::signed(ALICE), DAVE, token_id, 100));
+
+            assert_ok!(TemplateModule::transfer_from(
+                RuntimeOrigin::signed(DAVE),
+                token_id,
+                ALICE,
+                BOB,
+                100
+            ));
+
+            assert_eq!(TemplateModule::allowance((token_id, ALICE, DAVE)), 0);
+            assert_eq!(TemplateModule::balance_of(token_id, ALICE), 9900);
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 95);
+            assert_eq!(TemplateModule::balance_of(token_id, TREASURY), 5);
+            System::assert_has_event(fee_charged(ALICE, BOB, 100, 5, 95));
+        });
+    }
+
+    #[test]
+    fn an_escrow_pays_the_same_fee_as_a_transfer() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(10_000);
+            charge(token_id, 1);
+            let pot = TemplateModule::account_id();
+
+            // nothing is taken on the way into the pallet's account
+            assert_ok!(TemplateModule::escrow_transfer(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                BOB,
+                1000,
+                10
+            ));
+            assert_eq!(TemplateModule::balance_of(token_id, pot), 1000);
+            assert_eq!(TemplateModule::balance_of(token_id, TREASURY), 0);
+
+            // and on the way out it is charged once, like `transfer` would
+            System::set_block_number(10);
+            assert_ok!(TemplateModule::claim_escrow(RuntimeOrigin::signed(BOB), 0));
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 990);
+            assert_eq!(TemplateModule::balance_of(token_id, TREASURY), 10);
+            assert_eq!(TemplateModule::balance_of(token_id, pot), 0);
+            System::assert_has_event(fee_charged(pot, BOB, 1000, 10, 990));
+            System::assert_last_event(
+                Event::EscrowClaimed {
+                    id: 0,
+                    who: BOB,
+                    amount: 990,
+                }
+                .into(),
+            );
+
+            // a cancelled escrow goes back whole
+            assert_ok!(TemplateModule::escrow_transfer(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                BOB,
+                1000,
+                20
+            ));
+            assert_ok!(TemplateModule::cancel_escrow(RuntimeOrigin::signed(ALICE), 1));
+            assert_eq!(TemplateModule::balance_of(token_id, ALICE), 9000);
+            assert_eq!(TemplateModule::balance_of(token_id, TREASURY), 10);
+
+            // and the exemptions of the two ends still count
+            exempt(token_id, BOB);
+            assert_ok!(TemplateModule::escrow_transfer(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                BOB,
+                1000,
+                20
+            ));
+            System::set_block_number(20);
+            assert_ok!(TemplateModule::claim_escrow(RuntimeOrigin::signed(BOB), 2));
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 1990);
+            assert_eq!(TemplateModule::balance_of(token_id, TREASURY), 10);
+        });
+    }
+
+    #[test]
+    fn streams_pay_the_fee_on_what_the_recipient_gets() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(10_000);
+            charge(token_id, 10);
+
+            assert_ok!(TemplateModule::create_stream(
+                RuntimeOrigin::signed(ALICE),
+                BOB,
+                token_id,
+                100,
+                1,
+                11
+            ));
+
+            System::set_block_number(3);
+            assert_ok!(TemplateModule::withdraw_from_stream(RuntimeOrigin::signed(BOB), 0));
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 180);
+            assert_eq!(TemplateModule::balance_of(token_id, TREASURY), 20);
+
+            // on cancel the recipient's part pays the fee, the sender's refund doesn't
+            System::set_block_number(5);
+            assert_ok!(TemplateModule::cancel_stream(RuntimeOrigin::signed(ALICE), 0));
+            System::assert_last_event(
+                Event::StreamCancelled {
+                    id: 0,
+                    recipient_amount: 180,
+                    sender_amount: 600,
+                }
+                .into(),
+            );
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 360);
+            assert_eq!(TemplateModule::balance_of(token_id, TREASURY), 40);
+            assert_eq!(TemplateModule::balance_of(token_id, ALICE), 9600);
+        });
+    }
+
+    #[test]
+    fn the_beneficiary_must_be_able_to_receive_the_fee() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(10_000);
+            assert_ok!(TemplateModule::set_permissioned(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                true
+            ));
+            assert_ok!(TemplateModule::add_holders(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                BoundedVec::try_from(vec![BOB]).unwrap()
+            ));
+
+            assert_noop!(
+                TemplateModule::set_transfer_fee(
+                    RuntimeOrigin::signed(ALICE),
+                    token_id,
+                    Some((Permill::from_percent(10), TREASURY))
+                ),
+                Error::<Test>::NotAllowlisted
+            );
+
+            assert_ok!(TemplateModule::add_holders(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                BoundedVec::try_from(vec![TREASURY]).unwrap()
+            ));
+            charge(token_id, 10);
+            assert_ok!(TemplateModule::transfer(RuntimeOrigin::signed(ALICE), BOB, token_id, 100));
+            assert_eq!(TemplateModule::balance_of(token_id, TREASURY), 10);
+
+            // taken off the allowlist later, it can't be paid any more
+            assert_ok!(TemplateModule::remove_holders(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                BoundedVec::try_from(vec![TREASURY]).unwrap()
+            ));
+            assert_noop!(
+                TemplateModule::transfer(RuntimeOrigin::signed(ALICE), BOB, token_id, 100),
+                Error::<Test>::NotAllowlisted
+            );
+
+            // and `TransferFilter` sees the fee as a transfer to the beneficiary
+            assert_ok!(TemplateModule::set_permissioned(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                false
+            ));
+            BlockedRecipients::set(vec![TREASURY]);
+            assert_noop!(
+                TemplateModule::transfer(RuntimeOrigin::signed(ALICE), BOB, token_id, 100),
+                sp_runtime::DispatchError::Other("recipient blocked")
+            );
+
+            // unless there is no fee to pay
+            exempt(token_id, ALICE);
+            assert_ok!(TemplateModule::transfer(RuntimeOrigin::signed(ALICE), BOB, token_id, 100));
+        });
+    }
+
+    #[test]
+    fn vested_transfers_lock_what_arrives() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(10_000);
+            charge(token_id, 10);
+
+            assert_ok!(TemplateModule::vested_transfer(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                BOB,
+                VestingSchedule::new(100, 10, 1, 5)
+            ));
+            System::assert_last_event(
+                Event::VestedTransferred {
+                    token_id,
+                    from: ALICE,
+                    to: BOB,
+                    schedule: VestingSchedule::new(90, 10, 1, 5),
+                }
+                .into(),
+            );
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 90);
+            assert_eq!(TemplateModule::locked(token_id, BOB), 90);
+
+            // once it has all vested nothing is left locked
+            System::set_block_number(10);
+            assert_ok!(TemplateModule::vest(RuntimeOrigin::signed(BOB), token_id));
+            assert_eq!(TemplateModule::locked(token_id, BOB), 0);
+
+            // a fee that takes everything leaves nothing to lock
+            charge(token_id, 100);
+            assert_noop!(
+                TemplateModule::vested_transfer(
+                    RuntimeOrigin::signed(ALICE),
+                    token_id,
+                    BOB,
+                    VestingSchedule::new(100, 10, 10, 10)
+                ),
+                Error::<Test>::InvalidVestingSchedule
+            );
+        });
+    }
+
+    #[test]
+    fn fees_round_down() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(10_000);
+            charge(token_id, 1);
+
+            // 1% of 99 is 0.99, so nothing is taken
+            assert_ok!(TemplateModule::transfer(RuntimeOrigin::signed(ALICE), BOB, token_id, 99));
+            System::assert_has_event(fee_charged(ALICE, BOB, 99, 0, 99));
+            assert_eq!(TemplateModule::balance_of(token_id, TREASURY), 0);
+
+            // 1% of 199 is 1.99, so 1 is taken
+            assert_ok!(TemplateModule::transfer(RuntimeOrigin::signed(ALICE), BOB, token_id, 199));
+            System::assert_has_event(fee_charged(ALICE, BOB, 199, 1, 198));
+
+            // a third of 3 is 0.999999 at `Permill` precision, which is still 0
+            assert_ok!(TemplateModule::set_transfer_fee(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                Some((Permill::from_rational(1u32, 3u32), TREASURY))
+            ));
+            assert_ok!(TemplateModule::transfer(RuntimeOrigin::signed(ALICE), BOB, token_id, 3));
+            System::assert_has_event(fee_charged(ALICE, BOB, 3, 0, 3));
+
+            // splitting never creates or loses tokens
+            let held: u64 = [ALICE, BOB, TREASURY]
+                .iter()
+                .map(|who| TemplateModule::balance_of(token_id, who))
+                .sum();
+            assert_eq!(held, 10_000);
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 99 + 198 + 3);
+        });
+    }
+
+    #[test]
+    fn exempt_accounts_pay_no_fee() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let token_id = mint_to_alice(10_000);
+            charge(token_id, 10);
+
+            assert_noop!(
+                TemplateModule::add_fee_exemptions(
+                    RuntimeOrigin::signed(BOB),
+                    token_id,
+                    BoundedVec::try_from(vec![BOB]).unwrap()
+                ),
+                Error::<Test>::NoPermission
+            );
+
+            // an exempt sender
+            exempt(token_id, ALICE);
+            System::assert_last_event(
+                Event::FeeExemptionAdded {
+                    token_id,
+                    who: ALICE,
+                }
+                .into(),
+            );
+            assert_ok!(TemplateModule::transfer(RuntimeOrigin::signed(ALICE), BOB, token_id, 1000));
+            assert_eq!(TemplateModule::balance_of(token_id, BOB), 1000);
+
+            // an exempt recipient
+            exempt(token_id, DAVE);
+            assert_ok!(TemplateModule::transfer(RuntimeOrigin::signed(BOB), DAVE, token_id, 500));
+            assert_eq!(TemplateModule::balance_of(token_id, DAVE), 500);
+            assert_eq!(TemplateModule::balance_of(token_id, TREASURY), 0);
+
+            assert_ok!(TemplateModule::remove_fee_exemptions(
+                RuntimeOrigin::signed(ALICE),
+                token_id,
+                BoundedVec::try_from(vec![DAVE]).unwrap()
+            ));
+            assert_ok!(TemplateModule::transfer(RuntimeOrigin::signed(BOB), DAVE, token_id, 500));
+            assert_eq!(TemplateModule::balance_of(token_id, DAVE), 950);
+            assert_eq!(TemplateModule::balance_of(token_id, TREASURY), 50);
+        });
+    }
+
+    #[test]
+    fn migration_turns_fees_off() {
+        new_test_ext().execute_with(|| {
+            let token_id = mint_to_alice(1000);
+            let details = Tokens::<Test>::get(token_id).unwrap();
+            let v3 = old::TokenDetails::<Test> {
+                owner: details.owner,
+                admin: details.admin,
+                name: details.name.clone(),
+                symbol: details.symbol.clone(),
+                supply: details.supply,
+                permissioned: details.permissioned,
+                max_supply: details.max_supply,
+            };
+            frame_support::storage::unhashed::put(&Tokens::<Test>::hashed_key_for(token_id), &v3);
+            StorageVersion::new(3).put::<TemplateModule>();
+
+            TemplateModule::on_runtime_upgrade();
+
+            assert_eq!(TemplateModule::on_chain_storage_version(), 4);
+            let migrated = Tokens::<Test>::get(token_id).unwrap();
+            assert_eq!(migrated.name, details.name);
+            assert_eq!(migrated.supply, 1000);
+            assert_eq!(migrated.transfer_fee, None);
+        });
+    }
+}
+
+mod baseline_upgrade {
+    use super::*;
+    use crate::{migrations::v2::old, LastTokenId, Rehash, Tokens};
+    use codec::Encode;
+    use frame_support::{
+        storage::unhashed,
+        traits::{GetStorageVersion, StorageVersion},
+        weights::Weight,
+    };
+
+    #[test]
+    fn tokens_keep_their_details_and_go_to_the_pallet_account() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+
+            // how the baseline stored a token: `{ name, symbol, supply }` and nothing else
+            let baseline = (b"MY_TOKEN".to_vec(), b"MTKN".to_vec(), 1000u64).encode();
+            unhashed::put_raw(&Tokens::<Test>::hashed_key_for(1), &baseline);
+            LastTokenId::<Test>::put(1);
+            old::BalanceOf::<Test>::insert(1, ALICE, 1000);
+            StorageVersion::new(0).put::<TemplateModule>();
+
+            TemplateModule::on_runtime_upgrade();
+            while Rehash::<Test>::exists() {
+                TemplateModule::on_idle(1, Weight::MAX);
+            }
+
+            assert_eq!(TemplateModule::on_chain_storage_version(), 4);
+
+            let pallet = TemplateModule::account_id();
+            let token = Tokens::<Test>::get(1).unwrap();
+            assert_eq!(token.owner, pallet);
+            assert_eq!(token.admin, pallet);
+            assert_eq!(token.name.to_vec(), b"MY_TOKEN".to_vec());
+            assert_eq!(token.symbol.to_vec(), b"MTKN".to_vec());
+            assert_eq!(token.supply, 1000);
+            assert!(!token.permissioned);
+            assert_eq!(token.max_supply, None);
+            assert_eq!(token.transfer_fee, None);
+
+            // the token still works, and can be handed to its real owner
+            assert_ok!(TemplateModule::transfer(RuntimeOrigin::signed(ALICE), BOB, 1, 400));
+            assert_eq!(TemplateModule::balance_of(1, BOB), 400);
+
+            assert_ok!(TemplateModule::force_set_team(RuntimeOrigin::root(), 1, ALICE, ALICE));
+            assert_ok!(TemplateModule::snapshot(RuntimeOrigin::signed(ALICE), 1));
+        });
+    }
+}
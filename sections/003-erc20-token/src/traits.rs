@@ -0,0 +1,56 @@
+//! Extension points for other pallets that need to know when tokens move.
+use frame_support::pallet_prelude::DispatchResult;
+
+/// Called by `_transfer` before any balance changes. Returning an error vetoes
+/// the transfer, e.g. for a compliance pallet. A transfer fee is checked as its
+/// own transfer to the beneficiary.
+pub trait TransferFilter<TokenId, AccountId, Balance> {
+    fn filter(
+        token_id: &TokenId,
+        from: &AccountId,
+        to: &AccountId,
+        amount: &Balance,
+    ) -> DispatchResult;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<TokenId, AccountId, Balance> TransferFilter<TokenId, AccountId, Balance> for Tuple {
+    fn filter(
+        token_id: &TokenId,
+        from: &AccountId,
+        to: &AccountId,
+        amount: &Balance,
+    ) -> DispatchResult {
+        for_tuples!( #( Tuple::filter(token_id, from, to, amount)?; )* );
+
+        Ok(())
+    }
+}
+
+/// Called by `_transfer` once the balances have been updated, e.g. for a
+/// rewards pallet. Purely informational. A transfer fee is reported as its own
+/// transfer to the beneficiary.
+pub trait OnTransfer<TokenId, AccountId, Balance> {
+    fn on_transfer(token_id: &TokenId, from: &AccountId, to: &AccountId, amount: &Balance);
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<TokenId, AccountId, Balance> OnTransfer<TokenId, AccountId, Balance> for Tuple {
+    fn on_transfer(token_id: &TokenId, from: &AccountId, to: &AccountId, amount: &Balance) {
+        for_tuples!( #( Tuple::on_transfer(token_id, from, to, amount); )* );
+    }
+}
+
+/// Lets the benchmarks measure the runtime's own `TransferFilter` and `OnTransfer`.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper<TokenId, AccountId> {
+    /// Set up whatever state makes the hooks most expensive for a transfer of
+    /// `token_id` from `from` to `to`, e.g. a long list the filter has to search.
+    /// The transfer must still be let through.
+    fn setup_transfer_hooks(token_id: &TokenId, from: &AccountId, to: &AccountId);
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl<TokenId, AccountId> BenchmarkHelper<TokenId, AccountId> for () {
+    fn setup_transfer_hooks(_token_id: &TokenId, _from: &AccountId, _to: &AccountId) {}
+}
@@ -5,26 +5,394 @@ use frame_support::{
     traits::Get,
     RuntimeDebug,
 };
+use frame_system::pallet_prelude::BlockNumberFor;
 use scale_info::TypeInfo;
+use sp_std::vec::Vec;
+use sp_runtime::{
+    traits::{AtLeast32BitUnsigned, Convert, One, Saturating, Zero},
+    Permill,
+};
 
 #[derive(Encode, Decode, MaxEncodedLen, TypeInfo)]
 #[scale_info(skip_type_params(T))]
 pub struct TokenDetails<T: Config> {
+    pub owner: T::AccountId,
+    /// Manages the holder allowlist. Starts out as the owner.
+    pub admin: T::AccountId,
     pub name: BoundedVec<u8, T::MaxTokenNameLen>,
     pub symbol: BoundedVec<u8, T::MaxTokenSymbolLen>,
     pub supply: T::TokenBalance,
+    /// Only allowlisted accounts may receive a permissioned token.
+    pub permissioned: bool,
+    /// Hard cap on `supply`, enforced on every issuance. Can only be lowered.
+    pub max_supply: Option<T::TokenBalance>,
+    /// Taken out of every transfer that isn't exempt.
+    pub transfer_fee: Option<TransferFee<T::AccountId>>,
 }
 
 impl<T: Config> TokenDetails<T> {
     pub fn new(
+        owner: T::AccountId,
         name: BoundedVec<u8, T::MaxTokenNameLen>,
         symbol: BoundedVec<u8, T::MaxTokenSymbolLen>,
         supply: T::TokenBalance,
+        max_supply: Option<T::TokenBalance>,
     ) -> TokenDetails<T> {
         TokenDetails {
+            admin: owner.clone(),
+            owner,
             name,
             symbol,
             supply,
+            permissioned: false,
+            max_supply,
+            transfer_fee: None,
+        }
+    }
+}
+
+/// A cut of every transfer of a token, paid to `beneficiary`, e.g. a treasury.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct TransferFee<AccountId> {
+    pub rate: Permill,
+    pub beneficiary: AccountId,
+}
+
+impl<AccountId> TransferFee<AccountId> {
+    /// The fee on a transfer of `gross`, rounded down so the sender never pays more
+    /// than `rate`.
+    pub fn fee_on<Balance: AtLeast32BitUnsigned + Copy>(&self, gross: Balance) -> Balance {
+        self.rate.mul_floor(gross)
+    }
+}
+
+/// Snapshot ids start at 1 for each token; 0 means no snapshot was taken yet.
+pub type SnapshotId = u32;
+
+pub type VestingScheduleOf<T> = VestingSchedule<<T as Config>::TokenBalance, BlockNumberFor<T>>;
+
+/// Linear vesting with a cliff.
+///
+/// `locked` unlocks at `per_block` per block counted from `starting_block`,
+/// but nothing unlocks before `cliff_block`. At the cliff everything accrued
+/// since `starting_block` is released at once.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct VestingSchedule<Balance, BlockNumber> {
+    pub locked: Balance,
+    pub per_block: Balance,
+    pub starting_block: BlockNumber,
+    pub cliff_block: BlockNumber,
+}
+
+impl<Balance, BlockNumber> VestingSchedule<Balance, BlockNumber>
+where
+    Balance: AtLeast32BitUnsigned + Copy,
+    BlockNumber: AtLeast32BitUnsigned + Copy,
+{
+    pub fn new(
+        locked: Balance,
+        per_block: Balance,
+        starting_block: BlockNumber,
+        cliff_block: BlockNumber,
+    ) -> Self {
+        VestingSchedule {
+            locked,
+            per_block,
+            starting_block,
+            cliff_block,
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        !self.locked.is_zero()
+            && !self.per_block.is_zero()
+            && self.cliff_block >= self.starting_block
+    }
+
+    /// Amount still locked at block `n`.
+    pub fn locked_at<BlockNumberToBalance: Convert<BlockNumber, Balance>>(
+        &self,
+        n: BlockNumber,
+    ) -> Balance {
+        if n < self.cliff_block {
+            return self.locked;
+        }
+
+        let vested_blocks = BlockNumberToBalance::convert(n.saturating_sub(self.starting_block));
+
+        self.locked
+            .saturating_sub(vested_blocks.saturating_mul(self.per_block))
+    }
+}
+
+/// An amount held under a particular reason.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct IdAmount<Id, Balance> {
+    pub id: Id,
+    pub amount: Balance,
+}
+
+pub type RateLimitOf<T> = RateLimit<<T as Config>::TokenBalance, BlockNumberFor<T>>;
+
+/// At most `amount` of a token may leave an account in any `window` consecutive
+/// blocks.
+///
+/// What an account sends is kept in up to `MaxOutflowBuckets` buckets of about
+/// `window / MaxOutflowBuckets` blocks each. A bucket counts in full until the last
+/// transfer in it is `window` blocks old, so the limit is never exceeded, and an
+/// account waits at most one bucket longer than it strictly has to.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct RateLimit<Balance, BlockNumber> {
+    pub amount: Balance,
+    pub window: BlockNumber,
+}
+
+impl<Balance, BlockNumber> RateLimit<Balance, BlockNumber>
+where
+    Balance: AtLeast32BitUnsigned + Copy,
+    BlockNumber: AtLeast32BitUnsigned + Copy,
+{
+    pub fn is_valid(&self) -> bool {
+        !self.amount.is_zero() && !self.window.is_zero()
+    }
+
+    /// Add `amount` sent at block `n` to `outflows`, the buckets of one account, and
+    /// drop the buckets that have expired. `None` if that takes the last `window`
+    /// blocks over the limit.
+    pub fn track<S: Get<u32>>(
+        &self,
+        outflows: BoundedVec<Outflow<Balance, BlockNumber>, S>,
+        amount: Balance,
+        n: BlockNumber,
+    ) -> Option<BoundedVec<Outflow<Balance, BlockNumber>, S>> {
+        let mut outflows = outflows.into_inner();
+        outflows.retain(|outflow| n.saturating_sub(outflow.last) < self.window);
+
+        outflows
+            .iter()
+            .try_fold(amount, |acc, outflow| acc.checked_add(&outflow.used))
+            .filter(|used| *used <= self.amount)?;
+
+        let width = (self.window / BlockNumber::from(S::get().max(1))).max(One::one());
+        let full = outflows.len() >= S::get() as usize;
+
+        match outflows.last_mut() {
+            // once all buckets are in use the newest one grows, which only makes
+            // it count for longer
+            Some(outflow) if full || n.saturating_sub(outflow.started) < width => {
+                outflow.used = outflow.used.saturating_add(amount);
+                outflow.last = n;
+            },
+            _ => outflows.push(Outflow {
+                used: amount,
+                started: n,
+                last: n,
+            }),
+        }
+
+        BoundedVec::try_from(outflows).ok()
+    }
+}
+
+pub type OutflowOf<T> = Outflow<<T as Config>::TokenBalance, BlockNumberFor<T>>;
+
+/// What an account sent from block `started` to block `last`, one bucket of its
+/// rate limit.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct Outflow<Balance, BlockNumber> {
+    pub used: Balance,
+    pub started: BlockNumber,
+    pub last: BlockNumber,
+}
+
+pub type CheckpointOf<T> = Checkpoint<BlockNumberFor<T>, <T as Config>::TokenBalance>;
+
+/// Voting power of a delegatee from `from_block` until the next checkpoint.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct Checkpoint<BlockNumber, Balance> {
+    pub from_block: BlockNumber,
+    pub votes: Balance,
+}
+
+pub type EmissionId = u32;
+
+pub type EmissionCurveOf<T> = EmissionCurve<<T as Config>::TokenBalance, BlockNumberFor<T>>;
+
+/// How many tokens an emission schedule creates per block.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub enum EmissionCurve<Balance, BlockNumber> {
+    /// `per_block` for every block.
+    Linear { per_block: Balance },
+    /// Starts at `initial_per_block` and halves every `halving_period` blocks.
+    Halving {
+        initial_per_block: Balance,
+        halving_period: BlockNumber,
+    },
+}
+
+impl<Balance, BlockNumber> EmissionCurve<Balance, BlockNumber>
+where
+    Balance: AtLeast32BitUnsigned + Copy,
+    BlockNumber: AtLeast32BitUnsigned + Copy,
+{
+    pub fn is_valid(&self) -> bool {
+        match self {
+            EmissionCurve::Linear { per_block } => !per_block.is_zero(),
+            EmissionCurve::Halving {
+                initial_per_block,
+                halving_period,
+            } => !initial_per_block.is_zero() && !halving_period.is_zero(),
+        }
+    }
+
+    /// Total emitted for the blocks `from..to`, counted from the start of the schedule.
+    pub fn emitted_between<BlockNumberToBalance: Convert<BlockNumber, Balance>>(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> Balance {
+        match *self {
+            EmissionCurve::Linear { per_block } => {
+                BlockNumberToBalance::convert(to.saturating_sub(from)).saturating_mul(per_block)
+            }
+            EmissionCurve::Halving {
+                initial_per_block,
+                halving_period,
+            } => {
+                let mut total = Balance::zero();
+                let mut block = from;
+
+                // the rate reaches zero after at most one halving per bit of `Balance`,
+                // so this loop is bounded no matter how far apart `from` and `to` are
+                while block < to {
+                    let halvings = block / halving_period;
+                    let rate = Self::halve(initial_per_block, halvings);
+
+                    if rate.is_zero() {
+                        break;
+                    }
+
+                    let period_end = halvings
+                        .saturating_add(One::one())
+                        .saturating_mul(halving_period);
+                    let end = period_end.min(to);
+
+                    total = total.saturating_add(
+                        BlockNumberToBalance::convert(end - block).saturating_mul(rate),
+                    );
+                    block = end;
+                }
+
+                total
+            }
         }
     }
+
+    /// `true` once the curve can't emit anything after block `at`.
+    pub fn is_exhausted(&self, at: BlockNumber) -> bool {
+        match *self {
+            EmissionCurve::Linear { .. } => false,
+            EmissionCurve::Halving {
+                initial_per_block,
+                halving_period,
+            } => Self::halve(initial_per_block, at / halving_period).is_zero(),
+        }
+    }
+
+    fn halve(amount: Balance, times: BlockNumber) -> Balance {
+        let two = Balance::one().saturating_add(One::one());
+        let mut amount = amount;
+        let mut times = times;
+
+        while !times.is_zero() && !amount.is_zero() {
+            amount = amount / two;
+            times -= One::one();
+        }
+
+        amount
+    }
+}
+
+/// Creates tokens for `beneficiary` every block from `start`. Emission is lazy:
+/// whenever the schedule is processed it issues everything accrued since
+/// `last_emitted`.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct EmissionSchedule<T: Config> {
+    pub token_id: T::TokenId,
+    pub beneficiary: T::AccountId,
+    pub curve: EmissionCurveOf<T>,
+    pub start: BlockNumberFor<T>,
+    pub last_emitted: BlockNumberFor<T>,
+}
+
+pub type AirdropId = u32;
+
+/// Tokens escrowed in the pallet account, claimable by whoever can prove a
+/// `(index, account, amount)` leaf of `merkle_root` until `expiry`.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct Airdrop<T: Config> {
+    pub creator: T::AccountId,
+    pub token_id: T::TokenId,
+    pub merkle_root: T::Hash,
+    pub remaining: T::TokenBalance,
+    pub expiry: BlockNumberFor<T>,
+    /// Words of `ClaimedBitmap` in use, all of which `reclaim` removes.
+    pub claimed_words: u32,
+}
+
+pub type EscrowId = u32;
+
+/// Tokens held in the pallet account until `recipient` claims them at or after
+/// `unlock_at`. The sender can cancel before then.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct Escrow<T: Config> {
+    pub sender: T::AccountId,
+    pub recipient: T::AccountId,
+    pub token_id: T::TokenId,
+    pub amount: T::TokenBalance,
+    pub unlock_at: BlockNumberFor<T>,
+}
+
+pub type StreamId = u32;
+
+/// Pays `rate_per_block` to `recipient` for every block from `start` to `stop`.
+/// The whole `deposit` is escrowed up front and nothing happens per block: what
+/// accrued is worked out whenever the stream is withdrawn from or cancelled.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct Stream<T: Config> {
+    pub sender: T::AccountId,
+    pub recipient: T::AccountId,
+    pub token_id: T::TokenId,
+    pub rate_per_block: T::TokenBalance,
+    pub start: BlockNumberFor<T>,
+    pub stop: BlockNumberFor<T>,
+    pub deposit: T::TokenBalance,
+    pub withdrawn: T::TokenBalance,
+}
+
+impl<T: Config> Stream<T> {
+    /// Total paid out by block `now`, withdrawn or not.
+    pub fn accrued_at(&self, now: BlockNumberFor<T>) -> T::TokenBalance {
+        let elapsed = now.clamp(self.start, self.stop) - self.start;
+
+        T::BlockNumberToBalance::convert(elapsed).saturating_mul(self.rate_per_block)
+    }
+
+    /// What the recipient can withdraw at block `now`.
+    pub fn available_at(&self, now: BlockNumberFor<T>) -> T::TokenBalance {
+        self.accrued_at(now).saturating_sub(self.withdrawn)
+    }
+}
+
+/// Where an `on_idle` migration has got to: the step it is on and the raw
+/// storage key of the last row looked at, `None` before the first.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum SweepCursor {
+    Balances(Option<Vec<u8>>),
+    Allowances(Option<Vec<u8>>),
+    AccountTokens(Option<Vec<u8>>),
 }
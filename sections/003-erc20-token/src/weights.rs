@@ -1,12 +1,11 @@
-//! Autogenerated weights for pallet_erc20
+//! Weights for pallet_erc20
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2023-04-06, STEPS: `50`, REPEAT: `20`, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! WORST CASE MAP SIZE: `1000000`
-//! HOSTNAME: `Alexs-MacBook-Pro-2.local`, CPU: `<UNKNOWN>`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 1024
+//! NOT YET GENERATED: these are estimates that stand in until the command below
+//! is run against a runtime. The calls that move tokens are benchmarked with the
+//! runtime's `TransferFilter` and `OnTransfer` in the state `BenchmarkHelper`
+//! sets up, so regenerate this file whenever those change.
 
-// Executed Command:
+// Command:
 // ../../target/release/node-template
 // benchmark
 // pallet
@@ -33,57 +32,470 @@ use core::marker::PhantomData;
 
 /// Weight functions needed for pallet_erc20.
 pub trait WeightInfo {
-	fn do_something() -> Weight;
-	fn cause_error() -> Weight;
+	fn mint() -> Weight;
+	fn transfer() -> Weight;
+	fn approve() -> Weight;
+	fn transfer_from() -> Weight;
+	fn on_initialize_emissions(n: u32, ) -> Weight;
+	fn safe_batch_transfer_from(n: u32, ) -> Weight;
+	fn transfer_with_memo(m: u32, ) -> Weight;
+	fn vested_transfer() -> Weight;
+	fn vest() -> Weight;
+	fn snapshot() -> Weight;
+	fn delegate() -> Weight;
+	fn set_permissioned() -> Weight;
+	fn add_holders(n: u32, ) -> Weight;
+	fn remove_holders(n: u32, ) -> Weight;
+	fn issue() -> Weight;
+	fn set_max_supply() -> Weight;
+	fn create_emission() -> Weight;
+	fn cancel_emission() -> Weight;
+	fn create_airdrop() -> Weight;
+	fn claim(p: u32, ) -> Weight;
+	fn reclaim(w: u32, ) -> Weight;
+	fn escrow_transfer() -> Weight;
+	fn claim_escrow() -> Weight;
+	fn cancel_escrow() -> Weight;
+	fn create_stream() -> Weight;
+	fn withdraw_from_stream() -> Weight;
+	fn cancel_stream() -> Weight;
+	fn force_transfer() -> Weight;
+	fn force_set_balance() -> Weight;
+	fn force_set_metadata() -> Weight;
+	fn force_set_team() -> Weight;
+	fn force_cancel_approval() -> Weight;
+	fn set_operator() -> Weight;
+	fn set_rate_limit() -> Weight;
+	fn add_rate_limit_exemptions(n: u32, ) -> Weight;
+	fn remove_rate_limit_exemptions(n: u32, ) -> Weight;
+	fn set_transfer_fee() -> Weight;
+	fn add_fee_exemptions(n: u32, ) -> Weight;
+	fn remove_fee_exemptions(n: u32, ) -> Weight;
 }
 
 /// Weights for pallet_erc20 using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
-	/// Storage: TemplateModule Something (r:0 w:1)
-	/// Proof: TemplateModule Something (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn do_something() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `0`
-		//  Estimated: `0`
-		// Minimum execution time: 8_000_000 picoseconds.
-		Weight::from_parts(9_000_000, 0)
+	fn mint() -> Weight {
+		Weight::from_parts(18_000_000, 8_000)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	fn transfer() -> Weight {
+		Weight::from_parts(60_000_000, 120_000)
+			.saturating_add(T::DbWeight::get().reads(30_u64))
+			.saturating_add(T::DbWeight::get().writes(22_u64))
+	}
+	fn approve() -> Weight {
+		Weight::from_parts(14_000_000, 4_000)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	fn transfer_from() -> Weight {
+		Weight::from_parts(65_000_000, 128_000)
+			.saturating_add(T::DbWeight::get().reads(32_u64))
+			.saturating_add(T::DbWeight::get().writes(23_u64))
+	}
+	fn on_initialize_emissions(n: u32, ) -> Weight {
+		Weight::from_parts(6_000_000, 8_000)
+			.saturating_add(Weight::from_parts(45_000_000, 56_000).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().reads((14_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(T::DbWeight::get().writes((10_u64).saturating_mul(n.into())))
+	}
+	fn safe_batch_transfer_from(n: u32, ) -> Weight {
+		Weight::from_parts(12_000_000, 4_000)
+			.saturating_add(Weight::from_parts(65_000_000, 128_000).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().reads((32_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes((23_u64).saturating_mul(n.into())))
+	}
+	fn transfer_with_memo(m: u32, ) -> Weight {
+		Weight::from_parts(60_000_000, 120_000)
+			.saturating_add(Weight::from_parts(2_000, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(30_u64))
+			.saturating_add(T::DbWeight::get().writes(22_u64))
+	}
+	fn vested_transfer() -> Weight {
+		Weight::from_parts(70_000_000, 124_000)
+			.saturating_add(T::DbWeight::get().reads(31_u64))
+			.saturating_add(T::DbWeight::get().writes(24_u64))
+	}
+	fn vest() -> Weight {
+		Weight::from_parts(20_000_000, 8_000)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	fn snapshot() -> Weight {
+		Weight::from_parts(12_000_000, 8_000)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	fn delegate() -> Weight {
+		Weight::from_parts(35_000_000, 44_000)
+			.saturating_add(T::DbWeight::get().reads(11_u64))
+			.saturating_add(T::DbWeight::get().writes(11_u64))
+	}
+	fn set_permissioned() -> Weight {
+		Weight::from_parts(12_000_000, 4_000)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	fn add_holders(n: u32, ) -> Weight {
+		Weight::from_parts(10_000_000, 4_000)
+			.saturating_add(Weight::from_parts(3_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
+	fn remove_holders(n: u32, ) -> Weight {
+		Weight::from_parts(10_000_000, 4_000)
+			.saturating_add(Weight::from_parts(3_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
+	fn issue() -> Weight {
+		Weight::from_parts(40_000_000, 52_000)
+			.saturating_add(T::DbWeight::get().reads(13_u64))
+			.saturating_add(T::DbWeight::get().writes(9_u64))
+	}
+	fn set_max_supply() -> Weight {
+		Weight::from_parts(12_000_000, 4_000)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	fn create_emission() -> Weight {
+		Weight::from_parts(20_000_000, 12_000)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	fn cancel_emission() -> Weight {
+		Weight::from_parts(18_000_000, 12_000)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	fn create_airdrop() -> Weight {
+		Weight::from_parts(70_000_000, 124_000)
+			.saturating_add(T::DbWeight::get().reads(31_u64))
+			.saturating_add(T::DbWeight::get().writes(24_u64))
+	}
+	fn claim(p: u32, ) -> Weight {
+		Weight::from_parts(70_000_000, 128_000)
+			.saturating_add(Weight::from_parts(2_000_000, 0).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(32_u64))
+			.saturating_add(T::DbWeight::get().writes(24_u64))
+	}
+	fn reclaim(w: u32, ) -> Weight {
+		Weight::from_parts(70_000_000, 124_000)
+			.saturating_add(Weight::from_parts(1_500_000, 0).saturating_mul(w.into()))
+			.saturating_add(T::DbWeight::get().reads(31_u64))
+			.saturating_add(T::DbWeight::get().writes(24_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(w.into())))
+	}
+	fn escrow_transfer() -> Weight {
+		Weight::from_parts(70_000_000, 132_000)
+			.saturating_add(T::DbWeight::get().reads(33_u64))
+			.saturating_add(T::DbWeight::get().writes(24_u64))
+	}
+	fn claim_escrow() -> Weight {
+		Weight::from_parts(65_000_000, 124_000)
+			.saturating_add(T::DbWeight::get().reads(31_u64))
+			.saturating_add(T::DbWeight::get().writes(23_u64))
+	}
+	fn cancel_escrow() -> Weight {
+		Weight::from_parts(65_000_000, 124_000)
+			.saturating_add(T::DbWeight::get().reads(31_u64))
+			.saturating_add(T::DbWeight::get().writes(23_u64))
+	}
+	fn create_stream() -> Weight {
+		Weight::from_parts(70_000_000, 132_000)
+			.saturating_add(T::DbWeight::get().reads(33_u64))
+			.saturating_add(T::DbWeight::get().writes(24_u64))
+	}
+	fn withdraw_from_stream() -> Weight {
+		Weight::from_parts(65_000_000, 124_000)
+			.saturating_add(T::DbWeight::get().reads(31_u64))
+			.saturating_add(T::DbWeight::get().writes(23_u64))
+	}
+	fn cancel_stream() -> Weight {
+		Weight::from_parts(125_000_000, 244_000)
+			.saturating_add(T::DbWeight::get().reads(61_u64))
+			.saturating_add(T::DbWeight::get().writes(45_u64))
+	}
+	fn force_transfer() -> Weight {
+		Weight::from_parts(40_000_000, 64_000)
+			.saturating_add(T::DbWeight::get().reads(16_u64))
+			.saturating_add(T::DbWeight::get().writes(14_u64))
+	}
+	fn force_set_balance() -> Weight {
+		Weight::from_parts(35_000_000, 40_000)
+			.saturating_add(T::DbWeight::get().reads(10_u64))
+			.saturating_add(T::DbWeight::get().writes(9_u64))
+	}
+	fn force_set_metadata() -> Weight {
+		Weight::from_parts(15_000_000, 4_000)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
-	/// Storage: TemplateModule Something (r:1 w:1)
-	/// Proof: TemplateModule Something (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn cause_error() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `32`
-		//  Estimated: `1489`
-		// Minimum execution time: 6_000_000 picoseconds.
-		Weight::from_parts(6_000_000, 1489)
+	fn force_set_team() -> Weight {
+		Weight::from_parts(15_000_000, 4_000)
 			.saturating_add(T::DbWeight::get().reads(1_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	fn force_cancel_approval() -> Weight {
+		Weight::from_parts(15_000_000, 8_000)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	fn set_operator() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	fn set_rate_limit() -> Weight {
+		Weight::from_parts(12_000_000, 4_000)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	fn add_rate_limit_exemptions(n: u32, ) -> Weight {
+		Weight::from_parts(10_000_000, 4_000)
+			.saturating_add(Weight::from_parts(3_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
+	fn remove_rate_limit_exemptions(n: u32, ) -> Weight {
+		Weight::from_parts(10_000_000, 4_000)
+			.saturating_add(Weight::from_parts(3_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
+	fn set_transfer_fee() -> Weight {
+		Weight::from_parts(15_000_000, 8_000)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	fn add_fee_exemptions(n: u32, ) -> Weight {
+		Weight::from_parts(10_000_000, 4_000)
+			.saturating_add(Weight::from_parts(3_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
+	fn remove_fee_exemptions(n: u32, ) -> Weight {
+		Weight::from_parts(10_000_000, 4_000)
+			.saturating_add(Weight::from_parts(3_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
-	/// Storage: TemplateModule Something (r:0 w:1)
-	/// Proof: TemplateModule Something (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn do_something() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `0`
-		//  Estimated: `0`
-		// Minimum execution time: 8_000_000 picoseconds.
-		Weight::from_parts(9_000_000, 0)
+	fn mint() -> Weight {
+		Weight::from_parts(18_000_000, 8_000)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	fn transfer() -> Weight {
+		Weight::from_parts(60_000_000, 120_000)
+			.saturating_add(RocksDbWeight::get().reads(30_u64))
+			.saturating_add(RocksDbWeight::get().writes(22_u64))
+	}
+	fn approve() -> Weight {
+		Weight::from_parts(14_000_000, 4_000)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn transfer_from() -> Weight {
+		Weight::from_parts(65_000_000, 128_000)
+			.saturating_add(RocksDbWeight::get().reads(32_u64))
+			.saturating_add(RocksDbWeight::get().writes(23_u64))
+	}
+	fn on_initialize_emissions(n: u32, ) -> Weight {
+		Weight::from_parts(6_000_000, 8_000)
+			.saturating_add(Weight::from_parts(45_000_000, 56_000).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().reads((14_u64).saturating_mul(n.into())))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((10_u64).saturating_mul(n.into())))
 	}
-	/// Storage: TemplateModule Something (r:1 w:1)
-	/// Proof: TemplateModule Something (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn cause_error() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `32`
-		//  Estimated: `1489`
-		// Minimum execution time: 6_000_000 picoseconds.
-		Weight::from_parts(6_000_000, 1489)
+	fn safe_batch_transfer_from(n: u32, ) -> Weight {
+		Weight::from_parts(12_000_000, 4_000)
+			.saturating_add(Weight::from_parts(65_000_000, 128_000).saturating_mul(n.into()))
 			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().reads((32_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes((23_u64).saturating_mul(n.into())))
+	}
+	fn transfer_with_memo(m: u32, ) -> Weight {
+		Weight::from_parts(60_000_000, 120_000)
+			.saturating_add(Weight::from_parts(2_000, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(30_u64))
+			.saturating_add(RocksDbWeight::get().writes(22_u64))
+	}
+	fn vested_transfer() -> Weight {
+		Weight::from_parts(70_000_000, 124_000)
+			.saturating_add(RocksDbWeight::get().reads(31_u64))
+			.saturating_add(RocksDbWeight::get().writes(24_u64))
+	}
+	fn vest() -> Weight {
+		Weight::from_parts(20_000_000, 8_000)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	fn snapshot() -> Weight {
+		Weight::from_parts(12_000_000, 8_000)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
-}
\ No newline at end of file
+	fn delegate() -> Weight {
+		Weight::from_parts(35_000_000, 44_000)
+			.saturating_add(RocksDbWeight::get().reads(11_u64))
+			.saturating_add(RocksDbWeight::get().writes(11_u64))
+	}
+	fn set_permissioned() -> Weight {
+		Weight::from_parts(12_000_000, 4_000)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn add_holders(n: u32, ) -> Weight {
+		Weight::from_parts(10_000_000, 4_000)
+			.saturating_add(Weight::from_parts(3_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
+	fn remove_holders(n: u32, ) -> Weight {
+		Weight::from_parts(10_000_000, 4_000)
+			.saturating_add(Weight::from_parts(3_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
+	fn issue() -> Weight {
+		Weight::from_parts(40_000_000, 52_000)
+			.saturating_add(RocksDbWeight::get().reads(13_u64))
+			.saturating_add(RocksDbWeight::get().writes(9_u64))
+	}
+	fn set_max_supply() -> Weight {
+		Weight::from_parts(12_000_000, 4_000)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn create_emission() -> Weight {
+		Weight::from_parts(20_000_000, 12_000)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	fn cancel_emission() -> Weight {
+		Weight::from_parts(18_000_000, 12_000)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	fn create_airdrop() -> Weight {
+		Weight::from_parts(70_000_000, 124_000)
+			.saturating_add(RocksDbWeight::get().reads(31_u64))
+			.saturating_add(RocksDbWeight::get().writes(24_u64))
+	}
+	fn claim(p: u32, ) -> Weight {
+		Weight::from_parts(70_000_000, 128_000)
+			.saturating_add(Weight::from_parts(2_000_000, 0).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(32_u64))
+			.saturating_add(RocksDbWeight::get().writes(24_u64))
+	}
+	fn reclaim(w: u32, ) -> Weight {
+		Weight::from_parts(70_000_000, 124_000)
+			.saturating_add(Weight::from_parts(1_500_000, 0).saturating_mul(w.into()))
+			.saturating_add(RocksDbWeight::get().reads(31_u64))
+			.saturating_add(RocksDbWeight::get().writes(24_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(w.into())))
+	}
+	fn escrow_transfer() -> Weight {
+		Weight::from_parts(70_000_000, 132_000)
+			.saturating_add(RocksDbWeight::get().reads(33_u64))
+			.saturating_add(RocksDbWeight::get().writes(24_u64))
+	}
+	fn claim_escrow() -> Weight {
+		Weight::from_parts(65_000_000, 124_000)
+			.saturating_add(RocksDbWeight::get().reads(31_u64))
+			.saturating_add(RocksDbWeight::get().writes(23_u64))
+	}
+	fn cancel_escrow() -> Weight {
+		Weight::from_parts(65_000_000, 124_000)
+			.saturating_add(RocksDbWeight::get().reads(31_u64))
+			.saturating_add(RocksDbWeight::get().writes(23_u64))
+	}
+	fn create_stream() -> Weight {
+		Weight::from_parts(70_000_000, 132_000)
+			.saturating_add(RocksDbWeight::get().reads(33_u64))
+			.saturating_add(RocksDbWeight::get().writes(24_u64))
+	}
+	fn withdraw_from_stream() -> Weight {
+		Weight::from_parts(65_000_000, 124_000)
+			.saturating_add(RocksDbWeight::get().reads(31_u64))
+			.saturating_add(RocksDbWeight::get().writes(23_u64))
+	}
+	fn cancel_stream() -> Weight {
+		Weight::from_parts(125_000_000, 244_000)
+			.saturating_add(RocksDbWeight::get().reads(61_u64))
+			.saturating_add(RocksDbWeight::get().writes(45_u64))
+	}
+	fn force_transfer() -> Weight {
+		Weight::from_parts(40_000_000, 64_000)
+			.saturating_add(RocksDbWeight::get().reads(16_u64))
+			.saturating_add(RocksDbWeight::get().writes(14_u64))
+	}
+	fn force_set_balance() -> Weight {
+		Weight::from_parts(35_000_000, 40_000)
+			.saturating_add(RocksDbWeight::get().reads(10_u64))
+			.saturating_add(RocksDbWeight::get().writes(9_u64))
+	}
+	fn force_set_metadata() -> Weight {
+		Weight::from_parts(15_000_000, 4_000)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn force_set_team() -> Weight {
+		Weight::from_parts(15_000_000, 4_000)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn force_cancel_approval() -> Weight {
+		Weight::from_parts(15_000_000, 8_000)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	fn set_operator() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn set_rate_limit() -> Weight {
+		Weight::from_parts(12_000_000, 4_000)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn add_rate_limit_exemptions(n: u32, ) -> Weight {
+		Weight::from_parts(10_000_000, 4_000)
+			.saturating_add(Weight::from_parts(3_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
+	fn remove_rate_limit_exemptions(n: u32, ) -> Weight {
+		Weight::from_parts(10_000_000, 4_000)
+			.saturating_add(Weight::from_parts(3_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
+	fn set_transfer_fee() -> Weight {
+		Weight::from_parts(15_000_000, 8_000)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn add_fee_exemptions(n: u32, ) -> Weight {
+		Weight::from_parts(10_000_000, 4_000)
+			.saturating_add(Weight::from_parts(3_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
+	fn remove_fee_exemptions(n: u32, ) -> Weight {
+		Weight::from_parts(10_000_000, 4_000)
+			.saturating_add(Weight::from_parts(3_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
+}
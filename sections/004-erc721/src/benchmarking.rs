@@ -0,0 +1,193 @@
+//! Benchmarking setup for pallet-erc721
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Template;
+use frame_benchmarking::v2::*;
+use frame_support::{traits::Get, BoundedVec};
+use frame_system::RawOrigin;
+use sp_std::vec;
+
+const SEED: u32 = 0;
+
+fn metadata<T: Config>() -> BoundedVec<u8, T::MaxMetadataLen> {
+    BoundedVec::try_from(vec![b'M'; T::MaxMetadataLen::get() as usize]).unwrap()
+}
+
+fn new_collection<T: Config>(owner: &T::AccountId) -> T::CollectionId {
+    let name = BoundedVec::try_from(vec![b'N'; T::MaxCollectionNameLen::get() as usize]).unwrap();
+    let symbol =
+        BoundedVec::try_from(vec![b'S'; T::MaxCollectionSymbolLen::get() as usize]).unwrap();
+
+    assert!(
+        Template::<T>::create_collection(RawOrigin::Signed(owner.clone()).into(), name, symbol)
+            .is_ok()
+    );
+
+    Template::<T>::last_collection_id()
+}
+
+fn mint_item<T: Config>(owner: &T::AccountId) -> (T::CollectionId, T::ItemId) {
+    let collection_id = new_collection::<T>(owner);
+    let item_id = T::ItemId::default();
+
+    assert!(Template::<T>::mint(
+        RawOrigin::Signed(owner.clone()).into(),
+        collection_id,
+        item_id,
+        T::Lookup::unlookup(owner.clone()),
+        metadata::<T>(),
+    )
+    .is_ok());
+
+    (collection_id, item_id)
+}
+
+// The slowest way to reach an item: `caller` is neither its owner nor approved
+// for it, only an operator of the owner, and there is an approval to clear.
+fn mint_item_for_operator<T: Config>(
+    owner: &T::AccountId,
+    caller: &T::AccountId,
+) -> (T::CollectionId, T::ItemId) {
+    let (collection_id, item_id) = mint_item::<T>(owner);
+    let spender: T::AccountId = account("spender", 0, SEED);
+
+    Approvals::<T>::insert(collection_id, item_id, spender);
+    OperatorApprovals::<T>::insert((collection_id, owner, caller), ());
+
+    (collection_id, item_id)
+}
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    #[benchmark]
+    fn create_collection() {
+        let caller: T::AccountId = whitelisted_caller();
+        let name =
+            BoundedVec::try_from(vec![b'N'; T::MaxCollectionNameLen::get() as usize]).unwrap();
+        let symbol =
+            BoundedVec::try_from(vec![b'S'; T::MaxCollectionSymbolLen::get() as usize]).unwrap();
+
+        #[extrinsic_call]
+        create_collection(RawOrigin::Signed(caller.clone()), name, symbol);
+
+        assert_eq!(
+            Collections::<T>::get(T::CollectionId::one()).unwrap().owner,
+            caller
+        );
+    }
+
+    #[benchmark]
+    fn mint() {
+        let caller: T::AccountId = whitelisted_caller();
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let collection_id = new_collection::<T>(&caller);
+        let item_id = T::ItemId::default();
+
+        #[extrinsic_call]
+        mint(
+            RawOrigin::Signed(caller),
+            collection_id,
+            item_id,
+            T::Lookup::unlookup(owner.clone()),
+            metadata::<T>(),
+        );
+
+        assert_eq!(Template::<T>::owner_of(collection_id, item_id), Some(owner));
+    }
+
+    #[benchmark]
+    fn transfer() {
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let caller: T::AccountId = whitelisted_caller();
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let (collection_id, item_id) = mint_item_for_operator::<T>(&owner, &caller);
+
+        #[extrinsic_call]
+        transfer(
+            RawOrigin::Signed(caller),
+            collection_id,
+            item_id,
+            T::Lookup::unlookup(recipient.clone()),
+        );
+
+        assert_eq!(
+            Template::<T>::owner_of(collection_id, item_id),
+            Some(recipient)
+        );
+        assert!(Approvals::<T>::get(collection_id, item_id).is_none());
+    }
+
+    #[benchmark]
+    fn approve() {
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let caller: T::AccountId = whitelisted_caller();
+        let spender: T::AccountId = account("spender", 1, SEED);
+        let (collection_id, item_id) = mint_item_for_operator::<T>(&owner, &caller);
+
+        #[extrinsic_call]
+        approve(
+            RawOrigin::Signed(caller),
+            collection_id,
+            item_id,
+            Some(T::Lookup::unlookup(spender.clone())),
+        );
+
+        assert_eq!(Approvals::<T>::get(collection_id, item_id), Some(spender));
+    }
+
+    #[benchmark]
+    fn set_approval_for_all() {
+        let caller: T::AccountId = whitelisted_caller();
+        let operator: T::AccountId = account("operator", 0, SEED);
+        let collection_id = new_collection::<T>(&caller);
+
+        #[extrinsic_call]
+        set_approval_for_all(
+            RawOrigin::Signed(caller.clone()),
+            collection_id,
+            T::Lookup::unlookup(operator.clone()),
+            true,
+        );
+
+        assert!(Template::<T>::is_approved_for_all(
+            collection_id,
+            &caller,
+            &operator
+        ));
+    }
+
+    #[benchmark]
+    fn burn() {
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let caller: T::AccountId = whitelisted_caller();
+        let (collection_id, item_id) = mint_item_for_operator::<T>(&owner, &caller);
+
+        #[extrinsic_call]
+        burn(RawOrigin::Signed(caller), collection_id, item_id);
+
+        assert!(Template::<T>::owner_of(collection_id, item_id).is_none());
+        assert!(!BalanceOf::<T>::contains_key(collection_id, &owner));
+    }
+
+    #[benchmark]
+    fn set_metadata() {
+        let caller: T::AccountId = whitelisted_caller();
+        let (collection_id, item_id) = mint_item::<T>(&caller);
+
+        #[extrinsic_call]
+        set_metadata(
+            RawOrigin::Signed(caller),
+            collection_id,
+            item_id,
+            metadata::<T>(),
+        );
+
+        assert!(Items::<T>::contains_key(collection_id, item_id));
+    }
+
+    impl_benchmark_test_suite!(Template, crate::mock::new_test_ext(), crate::mock::Test);
+}
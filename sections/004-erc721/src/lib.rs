@@ -0,0 +1,481 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Edit this file to define custom logic or remove it if it is not needed.
+/// Learn more about FRAME and the core library of Substrate FRAME pallets:
+/// <https://docs.substrate.io/reference/frame-pallets/>
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use types::*;
+pub mod types;
+use sp_runtime::traits::{CheckedAdd, One, StaticLookup, Zero};
+pub use weights::*;
+
+type AccountIdLookupOf<T> = <<T as frame_system::Config>::Lookup as StaticLookup>::Source;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+    use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::*;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    /// Configure the pallet by specifying the parameters and types on which it depends.
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        /// Because this pallet emits events, it depends on the runtime's definition of an event.
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        /// Type representing the weight of this pallet
+        type WeightInfo: WeightInfo;
+
+        #[pallet::constant]
+        type MaxCollectionNameLen: Get<u32>;
+        #[pallet::constant]
+        type MaxCollectionSymbolLen: Get<u32>;
+        /// Maximum length of the metadata stored with each item.
+        #[pallet::constant]
+        type MaxMetadataLen: Get<u32>;
+        type CollectionId: Copy
+            + Default
+            + Member
+            + Parameter
+            + Encode
+            + Decode
+            + CheckedAdd
+            + MaxEncodedLen
+            + One
+            + Zero;
+        /// Item ids are picked by the collection owner when minting.
+        type ItemId: Copy + Default + Member + Parameter + Encode + Decode + MaxEncodedLen;
+    }
+
+    #[pallet::storage]
+    #[pallet::getter(fn last_collection_id)]
+    pub type LastCollectionId<T: Config> = StorageValue<_, T::CollectionId, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn collections)]
+    pub type Collections<T: Config> =
+        StorageMap<_, Twox64Concat, T::CollectionId, CollectionDetails<T>, OptionQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn items)]
+    pub type Items<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::CollectionId,
+        Twox64Concat,
+        T::ItemId,
+        ItemDetails<T>,
+        OptionQuery,
+    >;
+
+    /// Number of items of a collection owned by an account. Accounts holding none
+    /// have no entry.
+    #[pallet::storage]
+    #[pallet::getter(fn balance_of)]
+    pub type BalanceOf<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::CollectionId,
+        Blake2_128Concat,
+        T::AccountId,
+        u32,
+        ValueQuery,
+    >;
+
+    /// The one account allowed to move an item on its owner's behalf. Cleared on transfer.
+    #[pallet::storage]
+    #[pallet::getter(fn approved)]
+    pub type Approvals<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::CollectionId,
+        Twox64Concat,
+        T::ItemId,
+        T::AccountId,
+        OptionQuery,
+    >;
+
+    /// Operators may move and approve every item of the owner in a collection.
+    #[pallet::storage]
+    pub type OperatorApprovals<T: Config> = StorageNMap<
+        _,
+        (
+            NMapKey<Twox64Concat, T::CollectionId>,
+            NMapKey<Blake2_128Concat, T::AccountId>, // owner
+            NMapKey<Blake2_128Concat, T::AccountId>, // operator
+        ),
+        (),
+    >;
+
+    // Pallets use events to inform users when important changes are made.
+    // https://docs.substrate.io/main-docs/build/events-errors/
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        CollectionCreated {
+            collection_id: T::CollectionId,
+            owner: T::AccountId,
+        },
+        Minted {
+            collection_id: T::CollectionId,
+            item_id: T::ItemId,
+            owner: T::AccountId,
+        },
+        Transferred {
+            collection_id: T::CollectionId,
+            item_id: T::ItemId,
+            from: T::AccountId,
+            to: T::AccountId,
+        },
+        /// `approved` is `None` when the approval was cleared.
+        Approved {
+            collection_id: T::CollectionId,
+            item_id: T::ItemId,
+            owner: T::AccountId,
+            approved: Option<T::AccountId>,
+        },
+        ApprovalForAll {
+            collection_id: T::CollectionId,
+            owner: T::AccountId,
+            operator: T::AccountId,
+            approved: bool,
+        },
+        Burned {
+            collection_id: T::CollectionId,
+            item_id: T::ItemId,
+            owner: T::AccountId,
+        },
+        MetadataSet {
+            collection_id: T::CollectionId,
+            item_id: T::ItemId,
+            metadata: BoundedVec<u8, T::MaxMetadataLen>,
+        },
+    }
+
+    // Errors inform users that something went wrong.
+    #[pallet::error]
+    pub enum Error<T> {
+        NoneCollection,
+        CollectionIdOverflow,
+        NoneItem,
+        ItemExists,
+        NoPermission,
+        BalanceOverflow,
+    }
+
+    // Dispatchable functions allows users to interact with the pallet and invoke state changes.
+    // These functions materialize as "extrinsics", which are often compared to transactions.
+    // Dispatchable functions must be annotated with a weight and must return a DispatchResult.
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::WeightInfo::create_collection())]
+        pub fn create_collection(
+            _owner: OriginFor<T>,
+            name: BoundedVec<u8, T::MaxCollectionNameLen>,
+            symbol: BoundedVec<u8, T::MaxCollectionSymbolLen>,
+        ) -> DispatchResult {
+            let owner = ensure_signed(_owner)?;
+
+            let collection_id = LastCollectionId::<T>::get()
+                .checked_add(&One::one())
+                .ok_or(Error::<T>::CollectionIdOverflow)?;
+
+            LastCollectionId::<T>::put(collection_id);
+            Collections::<T>::insert(
+                collection_id,
+                CollectionDetails::new(owner.clone(), name, symbol),
+            );
+
+            Self::deposit_event(Event::CollectionCreated {
+                collection_id,
+                owner,
+            });
+
+            Ok(())
+        }
+
+        /// Create `item_id` in `collection_id` for `owner`. Collection owner only.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::WeightInfo::mint())]
+        pub fn mint(
+            _minter: OriginFor<T>,
+            collection_id: T::CollectionId,
+            item_id: T::ItemId,
+            _owner: AccountIdLookupOf<T>,
+            metadata: BoundedVec<u8, T::MaxMetadataLen>,
+        ) -> DispatchResult {
+            let minter = ensure_signed(_minter)?;
+            let owner = T::Lookup::lookup(_owner)?;
+
+            Collections::<T>::try_mutate(collection_id, |maybe_collection| -> DispatchResult {
+                let collection = maybe_collection.as_mut().ok_or(Error::<T>::NoneCollection)?;
+                ensure!(collection.owner == minter, Error::<T>::NoPermission);
+                ensure!(
+                    !Items::<T>::contains_key(collection_id, item_id),
+                    Error::<T>::ItemExists
+                );
+
+                collection.items = collection
+                    .items
+                    .checked_add(1)
+                    .ok_or(Error::<T>::BalanceOverflow)?;
+
+                Ok(())
+            })?;
+
+            BalanceOf::<T>::try_mutate(collection_id, &owner, |balance| -> DispatchResult {
+                *balance = balance.checked_add(1).ok_or(Error::<T>::BalanceOverflow)?;
+
+                Ok(())
+            })?;
+            Items::<T>::insert(collection_id, item_id, ItemDetails::new(owner.clone(), metadata));
+
+            Self::deposit_event(Event::Minted {
+                collection_id,
+                item_id,
+                owner,
+            });
+
+            Ok(())
+        }
+
+        /// Move `item_id` to `to`. The caller must own the item, be approved for it,
+        /// or be an operator of its owner.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::transfer())]
+        pub fn transfer(
+            _spender: OriginFor<T>,
+            collection_id: T::CollectionId,
+            item_id: T::ItemId,
+            _to: AccountIdLookupOf<T>,
+        ) -> DispatchResult {
+            let spender = ensure_signed(_spender)?;
+            let to = T::Lookup::lookup(_to)?;
+
+            let from = Self::_transfer(collection_id, item_id, &spender, &to)?;
+
+            Self::deposit_event(Event::Transferred {
+                collection_id,
+                item_id,
+                from,
+                to,
+            });
+
+            Ok(())
+        }
+
+        /// Let `approved` move `item_id`, or clear the approval with `None`. The
+        /// caller must own the item or be an operator of its owner.
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::approve())]
+        pub fn approve(
+            _caller: OriginFor<T>,
+            collection_id: T::CollectionId,
+            item_id: T::ItemId,
+            _approved: Option<AccountIdLookupOf<T>>,
+        ) -> DispatchResult {
+            let caller = ensure_signed(_caller)?;
+            let approved = _approved.map(T::Lookup::lookup).transpose()?;
+
+            let item = Items::<T>::get(collection_id, item_id).ok_or(Error::<T>::NoneItem)?;
+            ensure!(
+                item.owner == caller || Self::is_approved_for_all(collection_id, &item.owner, &caller),
+                Error::<T>::NoPermission
+            );
+
+            match approved {
+                Some(ref approved) => Approvals::<T>::insert(collection_id, item_id, approved),
+                None => Approvals::<T>::remove(collection_id, item_id),
+            }
+
+            Self::deposit_event(Event::Approved {
+                collection_id,
+                item_id,
+                owner: item.owner,
+                approved,
+            });
+
+            Ok(())
+        }
+
+        /// Let `operator` move and approve every item the caller owns in `collection_id`,
+        /// including ones received later, or revoke that with `approved: false`.
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::set_approval_for_all())]
+        pub fn set_approval_for_all(
+            _owner: OriginFor<T>,
+            collection_id: T::CollectionId,
+            _operator: AccountIdLookupOf<T>,
+            approved: bool,
+        ) -> DispatchResult {
+            let owner = ensure_signed(_owner)?;
+            let operator = T::Lookup::lookup(_operator)?;
+
+            ensure!(
+                Collections::<T>::contains_key(collection_id),
+                Error::<T>::NoneCollection
+            );
+
+            if approved {
+                OperatorApprovals::<T>::insert((collection_id, &owner, &operator), ());
+            } else {
+                OperatorApprovals::<T>::remove((collection_id, &owner, &operator));
+            }
+
+            Self::deposit_event(Event::ApprovalForAll {
+                collection_id,
+                owner,
+                operator,
+                approved,
+            });
+
+            Ok(())
+        }
+
+        /// Destroy `item_id`. Allowed to the same accounts as `transfer`.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::burn())]
+        pub fn burn(
+            _caller: OriginFor<T>,
+            collection_id: T::CollectionId,
+            item_id: T::ItemId,
+        ) -> DispatchResult {
+            let caller = ensure_signed(_caller)?;
+
+            let item = Items::<T>::get(collection_id, item_id).ok_or(Error::<T>::NoneItem)?;
+            Self::ensure_can_move(collection_id, item_id, &item.owner, &caller)?;
+
+            Items::<T>::remove(collection_id, item_id);
+            Approvals::<T>::remove(collection_id, item_id);
+            Self::decrease_balance(collection_id, &item.owner);
+            Collections::<T>::mutate(collection_id, |maybe_collection| {
+                if let Some(collection) = maybe_collection {
+                    collection.items = collection.items.saturating_sub(1);
+                }
+            });
+
+            Self::deposit_event(Event::Burned {
+                collection_id,
+                item_id,
+                owner: item.owner,
+            });
+
+            Ok(())
+        }
+
+        /// Replace the metadata of `item_id`. Collection owner only.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::set_metadata())]
+        pub fn set_metadata(
+            _owner: OriginFor<T>,
+            collection_id: T::CollectionId,
+            item_id: T::ItemId,
+            metadata: BoundedVec<u8, T::MaxMetadataLen>,
+        ) -> DispatchResult {
+            let owner = ensure_signed(_owner)?;
+
+            let collection =
+                Collections::<T>::get(collection_id).ok_or(Error::<T>::NoneCollection)?;
+            ensure!(collection.owner == owner, Error::<T>::NoPermission);
+
+            Items::<T>::try_mutate(collection_id, item_id, |maybe_item| -> DispatchResult {
+                let item = maybe_item.as_mut().ok_or(Error::<T>::NoneItem)?;
+
+                item.metadata = metadata.clone();
+
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::MetadataSet {
+                collection_id,
+                item_id,
+                metadata,
+            });
+
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Move `item_id` to `to` on behalf of `spender`. Returns the previous owner.
+        pub fn _transfer(
+            collection_id: T::CollectionId,
+            item_id: T::ItemId,
+            spender: &T::AccountId,
+            to: &T::AccountId,
+        ) -> Result<T::AccountId, DispatchError> {
+            Items::<T>::try_mutate(
+                collection_id,
+                item_id,
+                |maybe_item| -> Result<T::AccountId, DispatchError> {
+                    let item = maybe_item.as_mut().ok_or(Error::<T>::NoneItem)?;
+                    let from = item.owner.clone();
+
+                    Self::ensure_can_move(collection_id, item_id, &from, spender)?;
+
+                    if &from != to {
+                        Self::decrease_balance(collection_id, &from);
+                        BalanceOf::<T>::try_mutate(collection_id, to, |balance| -> DispatchResult {
+                            *balance = balance.checked_add(1).ok_or(Error::<T>::BalanceOverflow)?;
+
+                            Ok(())
+                        })?;
+                    }
+
+                    item.owner = to.clone();
+                    Approvals::<T>::remove(collection_id, item_id);
+
+                    Ok(from)
+                },
+            )
+        }
+
+        pub fn owner_of(collection_id: T::CollectionId, item_id: T::ItemId) -> Option<T::AccountId> {
+            Items::<T>::get(collection_id, item_id).map(|item| item.owner)
+        }
+
+        pub fn is_approved_for_all(
+            collection_id: T::CollectionId,
+            owner: &T::AccountId,
+            operator: &T::AccountId,
+        ) -> bool {
+            OperatorApprovals::<T>::contains_key((collection_id, owner, operator))
+        }
+
+        /// Take one item off `owner`'s count, dropping the row once they hold none.
+        fn decrease_balance(collection_id: T::CollectionId, owner: &T::AccountId) {
+            BalanceOf::<T>::mutate_exists(collection_id, owner, |balance| {
+                *balance = balance.map(|b| b.saturating_sub(1)).filter(|b| *b > 0)
+            });
+        }
+
+        fn ensure_can_move(
+            collection_id: T::CollectionId,
+            item_id: T::ItemId,
+            owner: &T::AccountId,
+            spender: &T::AccountId,
+        ) -> DispatchResult {
+            ensure!(
+                owner == spender
+                    || Approvals::<T>::get(collection_id, item_id).as_ref() == Some(spender)
+                    || Self::is_approved_for_all(collection_id, owner, spender),
+                Error::<T>::NoPermission
+            );
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,413 @@
+use crate::{mock::*, BalanceOf, Error, Event};
+use frame_support::traits::ConstU32;
+use frame_support::{assert_noop, assert_ok, BoundedVec};
+
+type MaxNameLen<T> = <T as pallet_erc721::Config>::MaxCollectionNameLen;
+type MaxSymbolLen<T> = <T as pallet_erc721::Config>::MaxCollectionSymbolLen;
+
+const ALICE: u64 = 1;
+const BOB: u64 = 2;
+const DAVE: u64 = 3;
+
+fn metadata(data: &[u8]) -> BoundedVec<u8, ConstU32<32>> {
+    data.to_vec().try_into().unwrap()
+}
+
+fn create_collection() -> u64 {
+    let name =
+        BoundedVec::<u8, MaxNameLen<Test>>::try_from("MY_ITEMS".as_bytes().to_vec()).unwrap();
+    let symbol =
+        BoundedVec::<u8, MaxSymbolLen<Test>>::try_from("MITM".as_bytes().to_vec()).unwrap();
+
+    assert_ok!(TemplateModule::create_collection(
+        RuntimeOrigin::signed(ALICE),
+        name,
+        symbol
+    ));
+
+    TemplateModule::last_collection_id()
+}
+
+// ALICE owns the collection and item 7 in it.
+fn mint_to_alice() -> (u64, u32) {
+    let collection_id = create_collection();
+
+    assert_ok!(TemplateModule::mint(
+        RuntimeOrigin::signed(ALICE),
+        collection_id,
+        7,
+        ALICE,
+        metadata(b"ipfs://item-7")
+    ));
+
+    (collection_id, 7)
+}
+
+#[test]
+fn can_create_collection() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+
+        assert_eq!(create_collection(), 1);
+        System::assert_last_event(
+            Event::CollectionCreated {
+                collection_id: 1,
+                owner: ALICE,
+            }
+            .into(),
+        );
+
+        assert_eq!(create_collection(), 2);
+
+        let collection = TemplateModule::collections(1).unwrap();
+        assert_eq!(collection.owner, ALICE);
+        assert_eq!(collection.symbol.to_vec(), b"MITM".to_vec());
+        assert_eq!(collection.items, 0);
+    });
+}
+
+#[test]
+fn can_mint_item() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let collection_id = create_collection();
+
+        assert_ok!(TemplateModule::mint(
+            RuntimeOrigin::signed(ALICE),
+            collection_id,
+            1,
+            BOB,
+            metadata(b"ipfs://item-1")
+        ));
+        System::assert_last_event(
+            Event::Minted {
+                collection_id,
+                item_id: 1,
+                owner: BOB,
+            }
+            .into(),
+        );
+
+        assert_eq!(TemplateModule::owner_of(collection_id, 1), Some(BOB));
+        assert_eq!(TemplateModule::balance_of(collection_id, BOB), 1);
+        assert_eq!(TemplateModule::collections(collection_id).unwrap().items, 1);
+        assert_eq!(
+            TemplateModule::items(collection_id, 1).unwrap().metadata,
+            metadata(b"ipfs://item-1")
+        );
+    });
+}
+
+#[test]
+fn cannot_mint_without_permission_or_twice() {
+    new_test_ext().execute_with(|| {
+        let (collection_id, item_id) = mint_to_alice();
+
+        assert_noop!(
+            TemplateModule::mint(
+                RuntimeOrigin::signed(ALICE),
+                collection_id,
+                item_id,
+                BOB,
+                metadata(b"")
+            ),
+            Error::<Test>::ItemExists
+        );
+        assert_noop!(
+            TemplateModule::mint(RuntimeOrigin::signed(BOB), collection_id, 8, BOB, metadata(b"")),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            TemplateModule::mint(RuntimeOrigin::signed(ALICE), 99, 8, BOB, metadata(b"")),
+            Error::<Test>::NoneCollection
+        );
+    });
+}
+
+#[test]
+fn owner_can_transfer() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let (collection_id, item_id) = mint_to_alice();
+
+        assert_ok!(TemplateModule::transfer(
+            RuntimeOrigin::signed(ALICE),
+            collection_id,
+            item_id,
+            BOB
+        ));
+        System::assert_last_event(
+            Event::Transferred {
+                collection_id,
+                item_id,
+                from: ALICE,
+                to: BOB,
+            }
+            .into(),
+        );
+
+        assert_eq!(TemplateModule::owner_of(collection_id, item_id), Some(BOB));
+        assert_eq!(TemplateModule::balance_of(collection_id, ALICE), 0);
+        assert!(!BalanceOf::<Test>::contains_key(collection_id, ALICE));
+        assert_eq!(TemplateModule::balance_of(collection_id, BOB), 1);
+
+        // ALICE no longer owns it
+        assert_noop!(
+            TemplateModule::transfer(RuntimeOrigin::signed(ALICE), collection_id, item_id, ALICE),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            TemplateModule::transfer(RuntimeOrigin::signed(BOB), collection_id, 8, ALICE),
+            Error::<Test>::NoneItem
+        );
+    });
+}
+
+#[test]
+fn approved_account_can_transfer_once() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let (collection_id, item_id) = mint_to_alice();
+
+        assert_ok!(TemplateModule::approve(
+            RuntimeOrigin::signed(ALICE),
+            collection_id,
+            item_id,
+            Some(BOB)
+        ));
+        System::assert_last_event(
+            Event::Approved {
+                collection_id,
+                item_id,
+                owner: ALICE,
+                approved: Some(BOB),
+            }
+            .into(),
+        );
+        assert_eq!(TemplateModule::approved(collection_id, item_id), Some(BOB));
+
+        assert_ok!(TemplateModule::transfer(
+            RuntimeOrigin::signed(BOB),
+            collection_id,
+            item_id,
+            DAVE
+        ));
+        assert_eq!(TemplateModule::owner_of(collection_id, item_id), Some(DAVE));
+
+        // the approval went away with the transfer
+        assert_eq!(TemplateModule::approved(collection_id, item_id), None);
+        assert_noop!(
+            TemplateModule::transfer(RuntimeOrigin::signed(BOB), collection_id, item_id, BOB),
+            Error::<Test>::NoPermission
+        );
+    });
+}
+
+#[test]
+fn can_clear_approval() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let (collection_id, item_id) = mint_to_alice();
+
+        assert_noop!(
+            TemplateModule::approve(RuntimeOrigin::signed(BOB), collection_id, item_id, Some(BOB)),
+            Error::<Test>::NoPermission
+        );
+
+        assert_ok!(TemplateModule::approve(
+            RuntimeOrigin::signed(ALICE),
+            collection_id,
+            item_id,
+            Some(BOB)
+        ));
+        assert_ok!(TemplateModule::approve(
+            RuntimeOrigin::signed(ALICE),
+            collection_id,
+            item_id,
+            None
+        ));
+        System::assert_last_event(
+            Event::Approved {
+                collection_id,
+                item_id,
+                owner: ALICE,
+                approved: None,
+            }
+            .into(),
+        );
+
+        assert_noop!(
+            TemplateModule::transfer(RuntimeOrigin::signed(BOB), collection_id, item_id, BOB),
+            Error::<Test>::NoPermission
+        );
+    });
+}
+
+#[test]
+fn operator_can_transfer_and_approve_every_item() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let (collection_id, item_id) = mint_to_alice();
+        assert_ok!(TemplateModule::mint(
+            RuntimeOrigin::signed(ALICE),
+            collection_id,
+            8,
+            ALICE,
+            metadata(b"")
+        ));
+
+        assert_ok!(TemplateModule::set_approval_for_all(
+            RuntimeOrigin::signed(ALICE),
+            collection_id,
+            BOB,
+            true
+        ));
+        System::assert_last_event(
+            Event::ApprovalForAll {
+                collection_id,
+                owner: ALICE,
+                operator: BOB,
+                approved: true,
+            }
+            .into(),
+        );
+        assert!(TemplateModule::is_approved_for_all(collection_id, &ALICE, &BOB));
+
+        assert_ok!(TemplateModule::transfer(
+            RuntimeOrigin::signed(BOB),
+            collection_id,
+            item_id,
+            DAVE
+        ));
+        assert_ok!(TemplateModule::approve(
+            RuntimeOrigin::signed(BOB),
+            collection_id,
+            8,
+            Some(DAVE)
+        ));
+        assert_ok!(TemplateModule::transfer(
+            RuntimeOrigin::signed(DAVE),
+            collection_id,
+            8,
+            DAVE
+        ));
+        assert_eq!(TemplateModule::balance_of(collection_id, DAVE), 2);
+
+        // operators are per owner: DAVE's items are out of reach
+        assert_noop!(
+            TemplateModule::transfer(RuntimeOrigin::signed(BOB), collection_id, item_id, BOB),
+            Error::<Test>::NoPermission
+        );
+
+        assert_ok!(TemplateModule::set_approval_for_all(
+            RuntimeOrigin::signed(ALICE),
+            collection_id,
+            BOB,
+            false
+        ));
+        assert!(!TemplateModule::is_approved_for_all(collection_id, &ALICE, &BOB));
+    });
+}
+
+#[test]
+fn can_burn_item() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let (collection_id, item_id) = mint_to_alice();
+
+        assert_noop!(
+            TemplateModule::burn(RuntimeOrigin::signed(BOB), collection_id, item_id),
+            Error::<Test>::NoPermission
+        );
+
+        assert_ok!(TemplateModule::approve(
+            RuntimeOrigin::signed(ALICE),
+            collection_id,
+            item_id,
+            Some(BOB)
+        ));
+        assert_ok!(TemplateModule::burn(
+            RuntimeOrigin::signed(BOB),
+            collection_id,
+            item_id
+        ));
+        System::assert_last_event(
+            Event::Burned {
+                collection_id,
+                item_id,
+                owner: ALICE,
+            }
+            .into(),
+        );
+
+        assert_eq!(TemplateModule::owner_of(collection_id, item_id), None);
+        assert_eq!(TemplateModule::approved(collection_id, item_id), None);
+        assert_eq!(TemplateModule::balance_of(collection_id, ALICE), 0);
+        assert!(!BalanceOf::<Test>::contains_key(collection_id, ALICE));
+        assert_eq!(TemplateModule::collections(collection_id).unwrap().items, 0);
+
+        // the id can be minted again
+        assert_ok!(TemplateModule::mint(
+            RuntimeOrigin::signed(ALICE),
+            collection_id,
+            item_id,
+            BOB,
+            metadata(b"")
+        ));
+    });
+}
+
+#[test]
+fn collection_owner_sets_metadata() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let (collection_id, item_id) = mint_to_alice();
+
+        assert_ok!(TemplateModule::transfer(
+            RuntimeOrigin::signed(ALICE),
+            collection_id,
+            item_id,
+            BOB
+        ));
+
+        // owning the item is not enough
+        assert_noop!(
+            TemplateModule::set_metadata(
+                RuntimeOrigin::signed(BOB),
+                collection_id,
+                item_id,
+                metadata(b"ipfs://mine")
+            ),
+            Error::<Test>::NoPermission
+        );
+
+        assert_ok!(TemplateModule::set_metadata(
+            RuntimeOrigin::signed(ALICE),
+            collection_id,
+            item_id,
+            metadata(b"ipfs://updated")
+        ));
+        System::assert_last_event(
+            Event::MetadataSet {
+                collection_id,
+                item_id,
+                metadata: metadata(b"ipfs://updated"),
+            }
+            .into(),
+        );
+        assert_eq!(
+            TemplateModule::items(collection_id, item_id).unwrap().metadata,
+            metadata(b"ipfs://updated")
+        );
+
+        assert_noop!(
+            TemplateModule::set_metadata(
+                RuntimeOrigin::signed(ALICE),
+                collection_id,
+                8,
+                metadata(b"")
+            ),
+            Error::<Test>::NoneItem
+        );
+    });
+}
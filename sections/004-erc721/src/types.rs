@@ -0,0 +1,47 @@
+use crate::Config;
+use codec::{Decode, Encode};
+use frame_support::{
+    pallet_prelude::{BoundedVec, MaxEncodedLen},
+    traits::Get,
+};
+use scale_info::TypeInfo;
+
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct CollectionDetails<T: Config> {
+    /// Mints new items and manages their metadata.
+    pub owner: T::AccountId,
+    pub name: BoundedVec<u8, T::MaxCollectionNameLen>,
+    pub symbol: BoundedVec<u8, T::MaxCollectionSymbolLen>,
+    /// Number of items currently in existence.
+    pub items: u32,
+}
+
+impl<T: Config> CollectionDetails<T> {
+    pub fn new(
+        owner: T::AccountId,
+        name: BoundedVec<u8, T::MaxCollectionNameLen>,
+        symbol: BoundedVec<u8, T::MaxCollectionSymbolLen>,
+    ) -> CollectionDetails<T> {
+        CollectionDetails {
+            owner,
+            name,
+            symbol,
+            items: 0,
+        }
+    }
+}
+
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct ItemDetails<T: Config> {
+    pub owner: T::AccountId,
+    /// Usually a URI or content hash pointing at the item's off-chain data.
+    pub metadata: BoundedVec<u8, T::MaxMetadataLen>,
+}
+
+impl<T: Config> ItemDetails<T> {
+    pub fn new(owner: T::AccountId, metadata: BoundedVec<u8, T::MaxMetadataLen>) -> ItemDetails<T> {
+        ItemDetails { owner, metadata }
+    }
+}
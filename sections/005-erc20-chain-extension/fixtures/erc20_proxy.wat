@@ -0,0 +1,47 @@
+;; Forwards its input to the erc20 chain extension and returns the result.
+;;
+;; Input: the chain extension id as a little endian u32, then the SCALE
+;; encoded arguments. Output: the status code as a little endian u32, then
+;; whatever the chain extension wrote.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_call_chain_extension"
+		(func $seal_call_chain_extension (param i32 i32 i32 i32 i32) (result i32))
+	)
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) capacity, then length, of the input buffer
+	(data (i32.const 0) "\00\04")
+
+	;; [4, 1028) input buffer: [4, 8) id, [8, 1028) arguments
+
+	;; [1100, 1104) capacity, then length, of the output buffer
+	(data (i32.const 1100) "\00\01")
+
+	;; [1104, 1108) status code
+	;; [1108, 1364) output buffer
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 4) (i32.const 0))
+
+		(i32.store
+			(i32.const 1104)
+			(call $seal_call_chain_extension
+				(i32.load (i32.const 4))
+				(i32.const 8)
+				(i32.sub (i32.load (i32.const 0)) (i32.const 4))
+				(i32.const 1108)
+				(i32.const 1100)
+			)
+		)
+
+		(call $seal_return
+			(i32.const 0)
+			(i32.const 1104)
+			(i32.add (i32.load (i32.const 1100)) (i32.const 4))
+		)
+	)
+)
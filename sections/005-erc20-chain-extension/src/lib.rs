@@ -0,0 +1,159 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! A `pallet-contracts` chain extension that lets contracts hold and move
+//! `pallet-erc20` tokens. Transfers and approvals act on behalf of the calling
+//! contract, exactly as if its account had signed the extrinsic.
+//!
+//! Inputs and outputs are SCALE encoded. Every function returns a status code
+//! (see [`Erc20Status`]) so contracts can handle failures without trapping.
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use codec::Encode;
+use frame_support::traits::Get;
+use frame_system::RawOrigin;
+use pallet_contracts::chain_extension::{ChainExtension, Environment, Ext, InitState, Result, RetVal};
+use pallet_erc20::WeightInfo;
+use sp_runtime::{traits::StaticLookup, DispatchError};
+
+/// Function ids, passed in the lower 16 bits of the chain extension id.
+pub mod func_id {
+    /// `(token_id, owner) -> balance`
+    pub const BALANCE_OF: u16 = 1;
+    /// `token_id -> supply`, zero for unknown tokens
+    pub const TOTAL_SUPPLY: u16 = 2;
+    /// `(token_id, owner, spender) -> allowance`
+    pub const ALLOWANCE: u16 = 3;
+    /// `(token_id, to, amount)`
+    pub const TRANSFER: u16 = 4;
+    /// `(token_id, spender, amount)`
+    pub const APPROVE: u16 = 5;
+    /// `(token_id, from, to, amount)`
+    pub const TRANSFER_FROM: u16 = 6;
+}
+
+/// Returned to the contract by every function.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u32)]
+pub enum Erc20Status {
+    Success = 0,
+    NoneToken = 1,
+    InsufficientBalance = 2,
+    NotApproved = 3,
+    /// Any other error, e.g. a locked balance or a vetoed transfer.
+    Failed = 4,
+}
+
+impl Erc20Status {
+    fn from_result<T: pallet_erc20::Config>(result: core::result::Result<(), DispatchError>) -> Self {
+        match result {
+            Ok(()) => Erc20Status::Success,
+            Err(e) if e == pallet_erc20::Error::<T>::NoneToken.into() => Erc20Status::NoneToken,
+            Err(e) if e == pallet_erc20::Error::<T>::InsufficientBalance.into() => {
+                Erc20Status::InsufficientBalance
+            },
+            Err(e) if e == pallet_erc20::Error::<T>::NotApproved.into() => Erc20Status::NotApproved,
+            Err(_) => Erc20Status::Failed,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Erc20Extension;
+
+impl<T> ChainExtension<T> for Erc20Extension
+where
+    T: pallet_contracts::Config + pallet_erc20::Config,
+{
+    fn call<E: Ext<T = T>>(&mut self, env: Environment<E, InitState>) -> Result<RetVal> {
+        let id = env.func_id();
+        let mut env = env.buf_in_buf_out();
+        let read = <T as frame_system::Config>::DbWeight::get().reads(1);
+
+        let status = match id {
+            func_id::BALANCE_OF => {
+                env.charge_weight(read)?;
+                let (token_id, owner) = env.read_as::<(T::TokenId, T::AccountId)>()?;
+
+                let balance = pallet_erc20::Pallet::<T>::balance_of(token_id, owner);
+                env.write(&balance.encode(), false, None)?;
+
+                Erc20Status::Success
+            },
+            func_id::TOTAL_SUPPLY => {
+                env.charge_weight(read)?;
+                let token_id = env.read_as::<T::TokenId>()?;
+
+                let supply = pallet_erc20::Pallet::<T>::tokens(token_id)
+                    .map(|token| token.supply)
+                    .unwrap_or_default();
+                env.write(&supply.encode(), false, None)?;
+
+                Erc20Status::Success
+            },
+            func_id::ALLOWANCE => {
+                env.charge_weight(read)?;
+                let (token_id, owner, spender) =
+                    env.read_as::<(T::TokenId, T::AccountId, T::AccountId)>()?;
+
+                let allowance = pallet_erc20::Pallet::<T>::allowance((token_id, owner, spender));
+                env.write(&allowance.encode(), false, None)?;
+
+                Erc20Status::Success
+            },
+            func_id::TRANSFER => {
+                env.charge_weight(<T as pallet_erc20::Config>::WeightInfo::transfer())?;
+                let (token_id, to, amount) =
+                    env.read_as::<(T::TokenId, T::AccountId, T::TokenBalance)>()?;
+                let contract = env.ext().address().clone();
+
+                Erc20Status::from_result::<T>(pallet_erc20::Pallet::<T>::transfer(
+                    RawOrigin::Signed(contract).into(),
+                    T::Lookup::unlookup(to),
+                    token_id,
+                    amount,
+                ))
+            },
+            func_id::APPROVE => {
+                env.charge_weight(<T as pallet_erc20::Config>::WeightInfo::approve())?;
+                let (token_id, spender, amount) =
+                    env.read_as::<(T::TokenId, T::AccountId, T::TokenBalance)>()?;
+                let contract = env.ext().address().clone();
+
+                Erc20Status::from_result::<T>(pallet_erc20::Pallet::<T>::approve(
+                    RawOrigin::Signed(contract).into(),
+                    T::Lookup::unlookup(spender),
+                    token_id,
+                    amount,
+                ))
+            },
+            func_id::TRANSFER_FROM => {
+                env.charge_weight(<T as pallet_erc20::Config>::WeightInfo::transfer_from())?;
+                let (token_id, from, to, amount) = env
+                    .read_as::<(T::TokenId, T::AccountId, T::AccountId, T::TokenBalance)>()?;
+                let contract = env.ext().address().clone();
+
+                Erc20Status::from_result::<T>(pallet_erc20::Pallet::<T>::transfer_from(
+                    RawOrigin::Signed(contract).into(),
+                    token_id,
+                    T::Lookup::unlookup(from),
+                    T::Lookup::unlookup(to),
+                    amount,
+                ))
+            },
+            _ => return Err(DispatchError::Other("unknown erc20 chain extension function")),
+        };
+
+        // only the queries have output; clear the buffer for everything else so
+        // the contract never reads stale bytes as a result
+        if !matches!(id, func_id::BALANCE_OF | func_id::TOTAL_SUPPLY | func_id::ALLOWANCE) {
+            env.write(&[], false, None)?;
+        }
+
+        Ok(RetVal::Converging(status as u32))
+    }
+}
@@ -0,0 +1,168 @@
+use crate::Erc20Extension;
+use frame_support::{
+    parameter_types,
+    traits::{AsEnsureOriginWithArg, ConstBool, ConstU16, ConstU32, ConstU64, Nothing},
+    weights::Weight,
+    PalletId,
+};
+use frame_system::{EnsureRoot, EnsureSigned};
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, Convert, ConvertInto, IdentityLookup},
+    AccountId32, BuildStorage,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// A runtime with both pallets, wired together through the chain extension.
+frame_support::construct_runtime!(
+    pub enum Test
+    {
+        System: frame_system,
+        Balances: pallet_balances,
+        Timestamp: pallet_timestamp,
+        Randomness: pallet_insecure_randomness_collective_flip,
+        Contracts: pallet_contracts,
+        Erc20: pallet_erc20,
+    }
+);
+
+// pallet-contracts derives contract addresses from hashes, so accounts can't be plain integers.
+pub type AccountId = AccountId32;
+
+pub const ALICE: AccountId = AccountId32::new([1u8; 32]);
+pub const BOB: AccountId = AccountId32::new([2u8; 32]);
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Nonce = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Block = Block;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ConstU16<42>;
+    type OnSetCode = ();
+    type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+    type Balance = u64;
+    type DustRemoval = ();
+    type RuntimeEvent = RuntimeEvent;
+    type ExistentialDeposit = ConstU64<1>;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type MaxLocks = ();
+    type MaxReserves = ConstU32<50>;
+    type ReserveIdentifier = [u8; 8];
+    type FreezeIdentifier = ();
+    type MaxFreezes = ();
+    type RuntimeHoldReason = ();
+    type MaxHolds = ();
+}
+
+impl pallet_timestamp::Config for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = ConstU64<1>;
+    type WeightInfo = ();
+}
+
+impl pallet_insecure_randomness_collective_flip::Config for Test {}
+
+parameter_types! {
+    pub ContractsSchedule: pallet_contracts::Schedule<Test> = Default::default();
+    pub const Erc20PalletId: PalletId = PalletId(*b"py/erc20");
+}
+
+// Fees are not under test; one unit per unit of ref time keeps things simple.
+impl Convert<Weight, u64> for Test {
+    fn convert(weight: Weight) -> u64 {
+        weight.ref_time()
+    }
+}
+
+impl pallet_contracts::Config for Test {
+    type Time = Timestamp;
+    type Randomness = Randomness;
+    type Currency = Balances;
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeCall = RuntimeCall;
+    type CallFilter = Nothing;
+    type CallStack = [pallet_contracts::Frame<Self>; 5];
+    type WeightPrice = Self;
+    type WeightInfo = ();
+    type ChainExtension = Erc20Extension;
+    type Schedule = ContractsSchedule;
+    type DepositPerByte = ConstU64<1>;
+    type DepositPerItem = ConstU64<2>;
+    type DefaultDepositLimit = ConstU64<10_000_000>;
+    type AddressGenerator = pallet_contracts::DefaultAddressGenerator;
+    type MaxCodeLen = ConstU32<{ 123 * 1024 }>;
+    type MaxStorageKeyLen = ConstU32<128>;
+    type UnsafeUnstableInterface = ConstBool<false>;
+    type MaxDebugBufferLen = ConstU32<{ 2 * 1024 * 1024 }>;
+    type Migrations = ();
+}
+
+impl pallet_erc20::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type WeightInfo = ();
+    type TokenId = u64;
+    type TokenBalance = u64;
+    type MaxTokenNameLen = ConstU32<20>;
+    type MaxTokenSymbolLen = ConstU32<5>;
+    type MaxLength = ConstU32<20>;
+    type MaxVestingSchedules = ConstU32<3>;
+    type MinVestedTransfer = ConstU64<10>;
+    type BlockNumberToBalance = ConvertInto;
+    type RuntimeHoldReason = ();
+    type MaxHolds = ConstU32<2>;
+    type MaxSnapshots = ConstU32<10>;
+    type CheckpointRetention = ConstU64<100>;
+    type TransferFilter = ();
+    type OnTransfer = ();
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = ();
+    type MaxAllowlistBatch = ConstU32<2>;
+    type MaxEmissionSchedules = ConstU32<4>;
+    type MaxEmissionsPerBlock = ConstU32<2>;
+    type PalletId = Erc20PalletId;
+    type MaxProofLength = ConstU32<8>;
+    type ForceOrigin = EnsureRoot<AccountId>;
+    type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+    type MaxBatchTransfers = ConstU32<3>;
+    type MaxMemoLen = ConstU32<32>;
+    type MaxOutflowBuckets = ConstU32<10>;
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![(ALICE, 1_000_000_000_000), (BOB, 1_000_000_000_000)],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
@@ -0,0 +1,200 @@
+use crate::{func_id, mock::*, Erc20Status};
+use codec::{Decode, Encode};
+use frame_support::{assert_ok, weights::Weight, BoundedVec};
+use pallet_contracts::{CollectEvents, DebugInfo, Determinism};
+use pallet_contracts_primitives::Code;
+
+const GAS_LIMIT: Weight = Weight::from_parts(100_000_000_000, 3 * 1024 * 1024);
+
+// Deploys the proxy fixture, which hands its input straight to the chain extension.
+fn deploy() -> AccountId {
+    let wasm = wat::parse_str(include_str!("../fixtures/erc20_proxy.wat")).unwrap();
+
+    Contracts::bare_instantiate(
+        ALICE,
+        0,
+        GAS_LIMIT,
+        None,
+        Code::Upload(wasm),
+        vec![],
+        vec![],
+        DebugInfo::Skip,
+        CollectEvents::Skip,
+    )
+    .result
+    .unwrap()
+    .account_id
+}
+
+fn call(contract: &AccountId, id: u16, input: impl Encode) -> (Erc20Status, Vec<u8>) {
+    let mut data = (id as u32).to_le_bytes().to_vec();
+    input.encode_to(&mut data);
+
+    let result = Contracts::bare_call(
+        ALICE,
+        contract.clone(),
+        0,
+        GAS_LIMIT,
+        None,
+        data,
+        DebugInfo::Skip,
+        CollectEvents::Skip,
+        Determinism::Enforced,
+    )
+    .result
+    .unwrap();
+
+    assert!(!result.did_revert());
+
+    let status = match u32::from_le_bytes(result.data[..4].try_into().unwrap()) {
+        0 => Erc20Status::Success,
+        1 => Erc20Status::NoneToken,
+        2 => Erc20Status::InsufficientBalance,
+        3 => Erc20Status::NotApproved,
+        _ => Erc20Status::Failed,
+    };
+
+    (status, result.data[4..].to_vec())
+}
+
+fn query(contract: &AccountId, id: u16, input: impl Encode) -> u64 {
+    let (status, output) = call(contract, id, input);
+    assert_eq!(status, Erc20Status::Success);
+
+    u64::decode(&mut &output[..]).unwrap()
+}
+
+// ALICE mints a token with 1000 supply and gives 300 of it to the contract.
+fn fund_contract(contract: &AccountId) -> u64 {
+    let name = BoundedVec::try_from(b"MY_TOKEN".to_vec()).unwrap();
+    let symbol = BoundedVec::try_from(b"MTKN".to_vec()).unwrap();
+
+    assert_ok!(Erc20::mint(RuntimeOrigin::signed(ALICE), name, symbol, 1000, None));
+    let token_id = Erc20::last_token_id();
+
+    assert_ok!(Erc20::transfer(
+        RuntimeOrigin::signed(ALICE),
+        contract.clone(),
+        token_id,
+        300
+    ));
+
+    token_id
+}
+
+#[test]
+fn contract_can_read_balances_and_supply() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let token_id = fund_contract(&contract);
+
+        assert_eq!(query(&contract, func_id::BALANCE_OF, (token_id, &contract)), 300);
+        assert_eq!(query(&contract, func_id::BALANCE_OF, (token_id, ALICE)), 700);
+        assert_eq!(query(&contract, func_id::TOTAL_SUPPLY, token_id), 1000);
+        assert_eq!(query(&contract, func_id::TOTAL_SUPPLY, 99u64), 0);
+    });
+}
+
+#[test]
+fn contract_can_transfer_its_tokens() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let token_id = fund_contract(&contract);
+
+        assert_eq!(
+            call(&contract, func_id::TRANSFER, (token_id, BOB, 100u64)),
+            (Erc20Status::Success, vec![])
+        );
+        assert_eq!(Erc20::balance_of(token_id, &contract), 200);
+        assert_eq!(Erc20::balance_of(token_id, BOB), 100);
+
+        assert_eq!(
+            call(&contract, func_id::TRANSFER, (token_id, BOB, 201u64)).0,
+            Erc20Status::InsufficientBalance
+        );
+        assert_eq!(
+            call(&contract, func_id::TRANSFER, (99u64, BOB, 1u64)).0,
+            Erc20Status::NoneToken
+        );
+        assert_eq!(Erc20::balance_of(token_id, &contract), 200);
+    });
+}
+
+#[test]
+fn contract_can_approve_spenders() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let token_id = fund_contract(&contract);
+
+        assert_eq!(
+            call(&contract, func_id::APPROVE, (token_id, BOB, 50u64)).0,
+            Erc20Status::Success
+        );
+        assert_eq!(query(&contract, func_id::ALLOWANCE, (token_id, &contract, BOB)), 50);
+
+        assert_ok!(Erc20::transfer_from(
+            RuntimeOrigin::signed(BOB),
+            token_id,
+            contract.clone(),
+            BOB,
+            50
+        ));
+        assert_eq!(Erc20::balance_of(token_id, BOB), 50);
+        assert_eq!(query(&contract, func_id::ALLOWANCE, (token_id, &contract, BOB)), 0);
+    });
+}
+
+#[test]
+fn contract_can_spend_allowances() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let token_id = fund_contract(&contract);
+
+        assert_eq!(
+            call(&contract, func_id::TRANSFER_FROM, (token_id, ALICE, BOB, 100u64)).0,
+            Erc20Status::NotApproved
+        );
+
+        assert_ok!(Erc20::approve(
+            RuntimeOrigin::signed(ALICE),
+            contract.clone(),
+            token_id,
+            200
+        ));
+        assert_eq!(
+            call(&contract, func_id::TRANSFER_FROM, (token_id, ALICE, BOB, 150u64)),
+            (Erc20Status::Success, vec![])
+        );
+
+        assert_eq!(Erc20::balance_of(token_id, ALICE), 550);
+        assert_eq!(Erc20::balance_of(token_id, BOB), 150);
+        assert_eq!(query(&contract, func_id::ALLOWANCE, (token_id, ALICE, &contract)), 50);
+
+        assert_eq!(
+            call(&contract, func_id::TRANSFER_FROM, (token_id, ALICE, BOB, 51u64)).0,
+            Erc20Status::InsufficientBalance
+        );
+    });
+}
+
+#[test]
+fn unknown_functions_trap() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let result = Contracts::bare_call(
+            ALICE,
+            contract,
+            0,
+            GAS_LIMIT,
+            None,
+            100u32.to_le_bytes().to_vec(),
+            DebugInfo::Skip,
+            CollectEvents::Skip,
+            Determinism::Enforced,
+        )
+        .result;
+
+        assert!(result.is_err());
+    });
+}
@@ -0,0 +1,407 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! An EVM precompile set that makes every `pallet-erc20` token look like an
+//! ordinary ERC-20 contract to Ethereum tooling.
+//!
+//! Token `n` lives at `0xffffffff` followed by `n` as a big endian `u128`, see
+//! [`Erc20Precompiles::token_address`]. Calls are decoded from the Solidity ABI,
+//! run against the pallet as the mapped caller, and emit the usual `Transfer`
+//! and `Approval` logs. A transfer that pays a fee logs a second `Transfer` to
+//! the fee's beneficiary.
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use core::marker::PhantomData;
+use fp_evm::{
+    ExitRevert, ExitSucceed, IsPrecompileResult, PrecompileFailure, PrecompileHandle,
+    PrecompileOutput, PrecompileResult, PrecompileSet,
+};
+use frame_support::{traits::Get, weights::Weight};
+use frame_system::RawOrigin;
+use pallet_erc20::WeightInfo;
+use pallet_evm::{AddressMapping, GasWeightMapping};
+use sp_core::{hashing::keccak_256, H160, H256, U256};
+use sp_runtime::{
+    traits::{Convert, StaticLookup, Zero},
+    DispatchError,
+};
+use sp_std::vec::Vec;
+
+/// The first four bytes of every token address.
+pub const ADDRESS_PREFIX: [u8; 4] = [0xff; 4];
+
+/// Function selectors of the ERC-20 interface.
+pub mod selector {
+    pub const TOTAL_SUPPLY: u32 = 0x18160ddd;
+    pub const BALANCE_OF: u32 = 0x70a08231;
+    pub const ALLOWANCE: u32 = 0xdd62ed3e;
+    pub const TRANSFER: u32 = 0xa9059cbb;
+    pub const APPROVE: u32 = 0x095ea7b3;
+    pub const TRANSFER_FROM: u32 = 0x23b872dd;
+    pub const NAME: u32 = 0x06fdde03;
+    pub const SYMBOL: u32 = 0x95d89b41;
+    pub const DECIMALS: u32 = 0x313ce567;
+    /// `Error(string)`, the standard revert reason.
+    pub const ERROR: u32 = 0x08c379a0;
+}
+
+pub const TRANSFER_SIGNATURE: &[u8] = b"Transfer(address,address,uint256)";
+pub const APPROVAL_SIGNATURE: &[u8] = b"Approval(address,address,uint256)";
+
+// Gas charged per log, per topic and per byte of log data (EIP-150 values).
+const LOG_GAS: u64 = 375;
+const LOG_TOPIC_GAS: u64 = 375;
+const LOG_DATA_GAS: u64 = 8;
+
+/// `Decimals` is reported by `decimals()`; the pallet itself has no notion of it.
+/// `AccountToAddress` is the inverse of the runtime's `AddressMapping`, used to
+/// name the beneficiary of a transfer fee in its `Transfer` log.
+pub struct Erc20Precompiles<R, Decimals, AccountToAddress>(
+    PhantomData<(R, Decimals, AccountToAddress)>,
+);
+
+impl<R, Decimals, AccountToAddress> Erc20Precompiles<R, Decimals, AccountToAddress> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<R, Decimals, AccountToAddress> Default for Erc20Precompiles<R, Decimals, AccountToAddress> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R, Decimals, AccountToAddress> Erc20Precompiles<R, Decimals, AccountToAddress>
+where
+    R: pallet_evm::Config + pallet_erc20::Config,
+    R::TokenId: TryFrom<u128> + Into<u128>,
+    R::TokenBalance: Into<U256> + TryFrom<U256>,
+    Decimals: Get<u8>,
+    AccountToAddress: Convert<R::AccountId, H160>,
+{
+    pub fn token_address(token_id: R::TokenId) -> H160 {
+        let mut address = [0u8; 20];
+        address[..4].copy_from_slice(&ADDRESS_PREFIX);
+        address[4..].copy_from_slice(&token_id.into().to_be_bytes());
+
+        H160(address)
+    }
+
+    pub fn token_id(address: H160) -> Option<R::TokenId> {
+        if address.0[..4] != ADDRESS_PREFIX {
+            return None;
+        }
+
+        let mut id = [0u8; 16];
+        id.copy_from_slice(&address.0[4..]);
+
+        R::TokenId::try_from(u128::from_be_bytes(id)).ok()
+    }
+
+    /// The fee the pallet will take from a transfer of `amount`, with the address
+    /// of its beneficiary. Read before the transfer, which doesn't change it.
+    fn fee(
+        handle: &mut impl PrecompileHandle,
+        token_id: R::TokenId,
+        from: &R::AccountId,
+        to: &R::AccountId,
+        amount: R::TokenBalance,
+    ) -> Result<Option<(H160, R::TokenBalance)>, PrecompileFailure> {
+        // the token and both fee exemptions
+        record_reads::<R>(handle, 3)?;
+
+        Ok(pallet_erc20::Pallet::<R>::transfer_fee(token_id, from, to)
+            .map(|fee| (AccountToAddress::convert(fee.beneficiary), fee.fee_on(amount)))
+            .filter(|(_, fee)| !fee.is_zero()))
+    }
+
+    fn call(handle: &mut impl PrecompileHandle, token_id: R::TokenId) -> PrecompileResult {
+        let input = handle.input().to_vec();
+        let context = handle.context().clone();
+        let address = handle.code_address();
+
+        if input.len() < 4 {
+            return Err(revert("missing selector"));
+        }
+        if !context.apparent_value.is_zero() {
+            return Err(revert("function is not payable"));
+        }
+
+        let function = u32::from_be_bytes([input[0], input[1], input[2], input[3]]);
+        let args = &input[4..];
+        let caller = R::AddressMapping::into_account_id(context.caller);
+
+        match function {
+            selector::TOTAL_SUPPLY => {
+                record_reads::<R>(handle, 1)?;
+                let token = pallet_erc20::Tokens::<R>::get(token_id)
+                    .ok_or_else(|| revert("unknown token"))?;
+
+                Ok(succeed(encode_u256(token.supply.into())))
+            },
+            selector::BALANCE_OF => {
+                record_reads::<R>(handle, 1)?;
+                let owner = R::AddressMapping::into_account_id(read_address(args, 0)?);
+
+                let balance = pallet_erc20::Pallet::<R>::balance_of(token_id, owner);
+
+                Ok(succeed(encode_u256(balance.into())))
+            },
+            selector::ALLOWANCE => {
+                record_reads::<R>(handle, 1)?;
+                let owner = R::AddressMapping::into_account_id(read_address(args, 0)?);
+                let spender = R::AddressMapping::into_account_id(read_address(args, 1)?);
+
+                let allowance = pallet_erc20::Pallet::<R>::allowance((token_id, owner, spender));
+
+                Ok(succeed(encode_u256(allowance.into())))
+            },
+            selector::NAME | selector::SYMBOL => {
+                record_reads::<R>(handle, 1)?;
+                let token = pallet_erc20::Tokens::<R>::get(token_id)
+                    .ok_or_else(|| revert("unknown token"))?;
+                let value = if function == selector::NAME {
+                    token.name.to_vec()
+                } else {
+                    token.symbol.to_vec()
+                };
+
+                Ok(succeed(encode_bytes(&value)))
+            },
+            selector::DECIMALS => Ok(succeed(encode_u256(Decimals::get().into()))),
+            selector::TRANSFER => {
+                ensure_mutable(handle)?;
+                record_weight::<R>(handle, <R as pallet_erc20::Config>::WeightInfo::transfer())?;
+                let to = read_address(args, 0)?;
+                let amount = to_balance::<R>(read_u256(args, 1)?)?;
+                let dest = R::AddressMapping::into_account_id(to);
+                let fee = Self::fee(handle, token_id, &caller, &dest, amount)?;
+
+                pallet_erc20::Pallet::<R>::transfer(
+                    RawOrigin::Signed(caller).into(),
+                    R::Lookup::unlookup(dest),
+                    token_id,
+                    amount,
+                )
+                .map_err(revert_dispatch)?;
+
+                log_transfer::<R>(handle, address, context.caller, to, amount, fee)?;
+
+                Ok(succeed(encode_bool(true)))
+            },
+            selector::APPROVE => {
+                ensure_mutable(handle)?;
+                record_weight::<R>(handle, <R as pallet_erc20::Config>::WeightInfo::approve())?;
+                let spender = read_address(args, 0)?;
+                let amount = read_u256(args, 1)?;
+
+                pallet_erc20::Pallet::<R>::approve(
+                    RawOrigin::Signed(caller).into(),
+                    R::Lookup::unlookup(R::AddressMapping::into_account_id(spender)),
+                    token_id,
+                    to_balance::<R>(amount)?,
+                )
+                .map_err(revert_dispatch)?;
+
+                log(handle, address, APPROVAL_SIGNATURE, context.caller, spender, amount)?;
+
+                Ok(succeed(encode_bool(true)))
+            },
+            selector::TRANSFER_FROM => {
+                ensure_mutable(handle)?;
+                record_weight::<R>(
+                    handle,
+                    <R as pallet_erc20::Config>::WeightInfo::transfer_from(),
+                )?;
+                let from = read_address(args, 0)?;
+                let to = read_address(args, 1)?;
+                let amount = to_balance::<R>(read_u256(args, 2)?)?;
+                let source = R::AddressMapping::into_account_id(from);
+                let dest = R::AddressMapping::into_account_id(to);
+                let fee = Self::fee(handle, token_id, &source, &dest, amount)?;
+
+                pallet_erc20::Pallet::<R>::transfer_from(
+                    RawOrigin::Signed(caller).into(),
+                    token_id,
+                    R::Lookup::unlookup(source),
+                    R::Lookup::unlookup(dest),
+                    amount,
+                )
+                .map_err(revert_dispatch)?;
+
+                log_transfer::<R>(handle, address, from, to, amount, fee)?;
+
+                Ok(succeed(encode_bool(true)))
+            },
+            _ => Err(revert("unknown selector")),
+        }
+    }
+}
+
+impl<R, Decimals, AccountToAddress> PrecompileSet
+    for Erc20Precompiles<R, Decimals, AccountToAddress>
+where
+    R: pallet_evm::Config + pallet_erc20::Config,
+    R::TokenId: TryFrom<u128> + Into<u128>,
+    R::TokenBalance: Into<U256> + TryFrom<U256>,
+    Decimals: Get<u8>,
+    AccountToAddress: Convert<R::AccountId, H160>,
+{
+    fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
+        let token_id = Self::token_id(handle.code_address())?;
+
+        if !pallet_erc20::Tokens::<R>::contains_key(token_id) {
+            return None;
+        }
+
+        Some(Self::call(handle, token_id))
+    }
+
+    fn is_precompile(&self, address: H160, _remaining_gas: u64) -> IsPrecompileResult {
+        IsPrecompileResult::Answer {
+            is_precompile: Self::token_id(address)
+                .map_or(false, |token_id| pallet_erc20::Tokens::<R>::contains_key(token_id)),
+            extra_cost: 0,
+        }
+    }
+}
+
+fn succeed(output: Vec<u8>) -> PrecompileOutput {
+    PrecompileOutput {
+        exit_status: ExitSucceed::Returned,
+        output,
+    }
+}
+
+/// Reverts with a Solidity `Error(string)` so tools can show the reason.
+fn revert(reason: &str) -> PrecompileFailure {
+    let mut output = selector::ERROR.to_be_bytes().to_vec();
+    output.extend(encode_bytes(reason.as_bytes()));
+
+    PrecompileFailure::Revert {
+        exit_status: ExitRevert::Reverted,
+        output,
+    }
+}
+
+fn revert_dispatch(error: DispatchError) -> PrecompileFailure {
+    revert(error.into())
+}
+
+fn ensure_mutable(handle: &impl PrecompileHandle) -> Result<(), PrecompileFailure> {
+    if handle.is_static() {
+        return Err(revert("cannot modify state in a static call"));
+    }
+
+    Ok(())
+}
+
+fn record_weight<R: pallet_evm::Config>(
+    handle: &mut impl PrecompileHandle,
+    weight: Weight,
+) -> Result<(), PrecompileFailure> {
+    handle.record_cost(R::GasWeightMapping::weight_to_gas(weight))?;
+
+    Ok(())
+}
+
+fn record_reads<R: pallet_evm::Config>(
+    handle: &mut impl PrecompileHandle,
+    reads: u64,
+) -> Result<(), PrecompileFailure> {
+    record_weight::<R>(handle, <R as frame_system::Config>::DbWeight::get().reads(reads))
+}
+
+fn log(
+    handle: &mut impl PrecompileHandle,
+    address: H160,
+    signature: &[u8],
+    from: H160,
+    to: H160,
+    amount: U256,
+) -> Result<(), PrecompileFailure> {
+    handle.record_cost(LOG_GAS + 3 * LOG_TOPIC_GAS + 32 * LOG_DATA_GAS)?;
+    handle.log(
+        address,
+        sp_std::vec![H256(keccak_256(signature)), from.into(), to.into()],
+        encode_u256(amount),
+    )?;
+
+    Ok(())
+}
+
+/// `Transfer` logs for what `to` received and, if there was a fee, for what its
+/// beneficiary got.
+fn log_transfer<R: pallet_erc20::Config>(
+    handle: &mut impl PrecompileHandle,
+    address: H160,
+    from: H160,
+    to: H160,
+    amount: R::TokenBalance,
+    fee: Option<(H160, R::TokenBalance)>,
+) -> Result<(), PrecompileFailure>
+where
+    R::TokenBalance: Into<U256>,
+{
+    let net = fee.map_or(amount, |(_, fee)| amount - fee);
+    log(handle, address, TRANSFER_SIGNATURE, from, to, net.into())?;
+
+    if let Some((beneficiary, fee)) = fee {
+        log(handle, address, TRANSFER_SIGNATURE, from, beneficiary, fee.into())?;
+    }
+
+    Ok(())
+}
+
+fn read_word(args: &[u8], index: usize) -> Result<&[u8], PrecompileFailure> {
+    args.get(index * 32..(index + 1) * 32)
+        .ok_or_else(|| revert("input too short"))
+}
+
+fn read_address(args: &[u8], index: usize) -> Result<H160, PrecompileFailure> {
+    let word = read_word(args, index)?;
+
+    if word[..12].iter().any(|byte| *byte != 0) {
+        return Err(revert("invalid address"));
+    }
+
+    Ok(H160::from_slice(&word[12..]))
+}
+
+fn read_u256(args: &[u8], index: usize) -> Result<U256, PrecompileFailure> {
+    Ok(U256::from_big_endian(read_word(args, index)?))
+}
+
+fn to_balance<R: pallet_erc20::Config>(amount: U256) -> Result<R::TokenBalance, PrecompileFailure>
+where
+    R::TokenBalance: TryFrom<U256>,
+{
+    R::TokenBalance::try_from(amount).map_err(|_| revert("amount out of bounds"))
+}
+
+fn encode_u256(value: U256) -> Vec<u8> {
+    let mut word = [0u8; 32];
+    value.to_big_endian(&mut word);
+
+    word.to_vec()
+}
+
+fn encode_bool(value: bool) -> Vec<u8> {
+    encode_u256(if value { U256::one() } else { U256::zero() })
+}
+
+/// ABI encodes `bytes` as a lone dynamic `string`: offset, length, then the
+/// data padded to a whole number of words.
+fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    let mut output = encode_u256(32u64.into());
+    output.extend(encode_u256((bytes.len() as u64).into()));
+    output.extend_from_slice(bytes);
+    output.resize(64 + (bytes.len() + 31) / 32 * 32, 0);
+
+    output
+}
@@ -0,0 +1,188 @@
+use crate::Erc20Precompiles;
+use frame_support::{
+    parameter_types,
+    traits::{AsEnsureOriginWithArg, ConstU128, ConstU16, ConstU32, ConstU64},
+    weights::Weight,
+    PalletId,
+};
+use frame_system::{EnsureRoot, EnsureSigned};
+use pallet_evm::{AddressMapping, EnsureAddressNever, EnsureAddressRoot, FeeCalculator};
+use sp_core::{H160, H256, U256};
+use sp_runtime::{
+    traits::{BlakeTwo256, Convert, ConvertInto, IdentityLookup},
+    BuildStorage,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// A runtime with both pallets; the precompile set is plugged into pallet-evm.
+frame_support::construct_runtime!(
+    pub enum Test
+    {
+        System: frame_system,
+        Balances: pallet_balances,
+        Timestamp: pallet_timestamp,
+        EVM: pallet_evm,
+        Erc20: pallet_erc20,
+    }
+);
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Nonce = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Block = Block;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ConstU16<42>;
+    type OnSetCode = ();
+    type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+    type Balance = u64;
+    type DustRemoval = ();
+    type RuntimeEvent = RuntimeEvent;
+    type ExistentialDeposit = ConstU64<1>;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type MaxLocks = ();
+    type MaxReserves = ConstU32<50>;
+    type ReserveIdentifier = [u8; 8];
+    type FreezeIdentifier = ();
+    type MaxFreezes = ();
+    type RuntimeHoldReason = ();
+    type MaxHolds = ();
+}
+
+impl pallet_timestamp::Config for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = ConstU64<1>;
+    type WeightInfo = ();
+}
+
+// Gas is free in the tests; only the precompile's behaviour is under test.
+pub struct FreeGas;
+impl FeeCalculator for FreeGas {
+    fn min_gas_price() -> (U256, Weight) {
+        (U256::zero(), Weight::zero())
+    }
+}
+
+// Account `n` is the address with `n` in its last eight bytes.
+pub struct U64AddressMapping;
+impl AddressMapping<u64> for U64AddressMapping {
+    fn into_account_id(address: H160) -> u64 {
+        let mut account = [0u8; 8];
+        account.copy_from_slice(&address.0[12..]);
+
+        u64::from_be_bytes(account)
+    }
+}
+
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const DAVE: u64 = 3;
+pub const TREASURY: u64 = 4;
+
+pub fn address_of(who: u64) -> H160 {
+    H160::from_low_u64_be(who)
+}
+
+// The inverse of `U64AddressMapping`.
+pub struct U64AccountToAddress;
+
+impl Convert<u64, H160> for U64AccountToAddress {
+    fn convert(who: u64) -> H160 {
+        address_of(who)
+    }
+}
+
+parameter_types! {
+    pub BlockGasLimit: U256 = U256::from(u64::MAX);
+    pub WeightPerGas: Weight = Weight::from_parts(20_000, 0);
+    pub const GasLimitPovSizeRatio: u64 = 4;
+    pub const Decimals: u8 = 12;
+    pub PrecompilesValue: Erc20Precompiles<Test, Decimals, U64AccountToAddress> = Erc20Precompiles::new();
+    pub const Erc20PalletId: PalletId = PalletId(*b"py/erc20");
+}
+
+impl pallet_evm::Config for Test {
+    type FeeCalculator = FreeGas;
+    type GasWeightMapping = pallet_evm::FixedGasWeightMapping<Self>;
+    type WeightPerGas = WeightPerGas;
+    type BlockHashMapping = pallet_evm::SubstrateBlockHashMapping<Self>;
+    type CallOrigin = EnsureAddressRoot<u64>;
+    type WithdrawOrigin = EnsureAddressNever<u64>;
+    type AddressMapping = U64AddressMapping;
+    type Currency = Balances;
+    type RuntimeEvent = RuntimeEvent;
+    type PrecompilesType = Erc20Precompiles<Test, Decimals, U64AccountToAddress>;
+    type PrecompilesValue = PrecompilesValue;
+    type ChainId = ();
+    type BlockGasLimit = BlockGasLimit;
+    type Runner = pallet_evm::runner::stack::Runner<Self>;
+    type OnChargeTransaction = ();
+    type OnCreate = ();
+    type FindAuthor = ();
+    type GasLimitPovSizeRatio = GasLimitPovSizeRatio;
+    type Timestamp = Timestamp;
+    type WeightInfo = ();
+}
+
+impl pallet_erc20::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type WeightInfo = ();
+    type TokenId = u64;
+    type TokenBalance = u128;
+    type MaxTokenNameLen = ConstU32<20>;
+    type MaxTokenSymbolLen = ConstU32<5>;
+    type MaxLength = ConstU32<20>;
+    type MaxVestingSchedules = ConstU32<3>;
+    type MinVestedTransfer = ConstU128<10>;
+    type BlockNumberToBalance = ConvertInto;
+    type RuntimeHoldReason = ();
+    type MaxHolds = ConstU32<2>;
+    type MaxSnapshots = ConstU32<10>;
+    type CheckpointRetention = ConstU64<100>;
+    type TransferFilter = ();
+    type OnTransfer = ();
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = ();
+    type MaxAllowlistBatch = ConstU32<2>;
+    type MaxEmissionSchedules = ConstU32<4>;
+    type MaxEmissionsPerBlock = ConstU32<2>;
+    type PalletId = Erc20PalletId;
+    type MaxProofLength = ConstU32<8>;
+    type ForceOrigin = EnsureRoot<u64>;
+    type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<u64>>;
+    type MaxBatchTransfers = ConstU32<3>;
+    type MaxMemoLen = ConstU32<32>;
+    type MaxOutflowBuckets = ConstU32<10>;
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut ext: sp_io::TestExternalities = frame_system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap()
+        .into();
+
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
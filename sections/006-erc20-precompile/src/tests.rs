@@ -0,0 +1,277 @@
+use crate::{mock::*, selector, APPROVAL_SIGNATURE, TRANSFER_SIGNATURE};
+use fp_evm::{CallInfo, ExitReason, ExitRevert, ExitSucceed, PrecompileSet};
+use frame_support::{assert_ok, traits::Get, BoundedVec};
+use pallet_evm::Runner;
+use sp_core::{hashing::keccak_256, H256, U256};
+use sp_runtime::Permill;
+
+type Precompiles = crate::Erc20Precompiles<Test, Decimals, U64AccountToAddress>;
+
+const GAS_LIMIT: u64 = 10_000_000;
+
+fn word(value: impl Into<U256>) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    value.into().to_big_endian(&mut word);
+    word
+}
+
+fn address_word(who: u64) -> [u8; 32] {
+    H256::from(address_of(who)).0
+}
+
+fn call(from: u64, token_id: u64, function: u32, args: &[[u8; 32]]) -> CallInfo {
+    let mut input = function.to_be_bytes().to_vec();
+    args.iter().for_each(|arg| input.extend_from_slice(arg));
+
+    <Test as pallet_evm::Config>::Runner::call(
+        address_of(from),
+        Precompiles::token_address(token_id),
+        input,
+        U256::zero(),
+        GAS_LIMIT,
+        None,
+        None,
+        None,
+        vec![],
+        false,
+        false,
+        None,
+        None,
+        <Test as pallet_evm::Config>::config(),
+    )
+    .unwrap()
+}
+
+fn query(token_id: u64, function: u32, args: &[[u8; 32]]) -> U256 {
+    let info = call(ALICE, token_id, function, args);
+    assert_eq!(info.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+
+    U256::from_big_endian(&info.value)
+}
+
+fn assert_reverted(info: CallInfo, reason: &str) {
+    assert_eq!(info.exit_reason, ExitReason::Revert(ExitRevert::Reverted));
+    assert_eq!(info.value[..4], selector::ERROR.to_be_bytes());
+
+    let length = U256::from_big_endian(&info.value[36..68]).as_usize();
+    assert_eq!(&info.value[68..68 + length], reason.as_bytes());
+}
+
+// ALICE mints a token with 1000 supply.
+fn create_token() -> u64 {
+    let name = BoundedVec::try_from(b"MY_TOKEN".to_vec()).unwrap();
+    let symbol = BoundedVec::try_from(b"MTKN".to_vec()).unwrap();
+
+    assert_ok!(Erc20::mint(RuntimeOrigin::signed(ALICE), name, symbol, 1000, None));
+    Erc20::last_token_id()
+}
+
+#[test]
+fn selectors_match_signatures() {
+    let selectors = [
+        (selector::TOTAL_SUPPLY, "totalSupply()"),
+        (selector::BALANCE_OF, "balanceOf(address)"),
+        (selector::ALLOWANCE, "allowance(address,address)"),
+        (selector::TRANSFER, "transfer(address,uint256)"),
+        (selector::APPROVE, "approve(address,uint256)"),
+        (selector::TRANSFER_FROM, "transferFrom(address,address,uint256)"),
+        (selector::NAME, "name()"),
+        (selector::SYMBOL, "symbol()"),
+        (selector::DECIMALS, "decimals()"),
+        (selector::ERROR, "Error(string)"),
+    ];
+
+    for (function, signature) in selectors {
+        assert_eq!(function.to_be_bytes(), keccak_256(signature.as_bytes())[..4]);
+    }
+}
+
+#[test]
+fn only_existing_tokens_are_precompiles() {
+    new_test_ext().execute_with(|| {
+        let token_id = create_token();
+        let address = Precompiles::token_address(token_id);
+
+        assert_eq!(Precompiles::token_id(address), Some(token_id));
+        assert_eq!(Precompiles::token_id(address_of(ALICE)), None);
+
+        let precompiles = PrecompilesValue::get();
+        assert!(precompiles.is_precompile(address, GAS_LIMIT).is_precompile());
+        assert!(!precompiles
+            .is_precompile(Precompiles::token_address(token_id + 1), GAS_LIMIT)
+            .is_precompile());
+    });
+}
+
+#[test]
+fn reads_token_details() {
+    new_test_ext().execute_with(|| {
+        let token_id = create_token();
+
+        assert_eq!(query(token_id, selector::TOTAL_SUPPLY, &[]), U256::from(1000u64));
+        assert_eq!(
+            query(token_id, selector::BALANCE_OF, &[address_word(ALICE)]),
+            U256::from(1000u64)
+        );
+        assert_eq!(query(token_id, selector::BALANCE_OF, &[address_word(BOB)]), U256::from(0u64));
+        assert_eq!(query(token_id, selector::DECIMALS, &[]), U256::from(12u64));
+
+        let name = call(ALICE, token_id, selector::NAME, &[]).value;
+        assert_eq!(name.len(), 96);
+        assert_eq!(U256::from_big_endian(&name[32..64]), U256::from(8u64));
+        assert_eq!(&name[64..72], b"MY_TOKEN");
+
+        let symbol = call(ALICE, token_id, selector::SYMBOL, &[]).value;
+        assert_eq!(&symbol[64..68], b"MTKN");
+    });
+}
+
+#[test]
+fn transfer_moves_tokens_and_logs() {
+    new_test_ext().execute_with(|| {
+        let token_id = create_token();
+
+        let info = call(ALICE, token_id, selector::TRANSFER, &[address_word(BOB), word(300u64)]);
+        assert_eq!(info.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+        assert_eq!(U256::from_big_endian(&info.value), U256::one());
+
+        assert_eq!(Erc20::balance_of(token_id, ALICE), 700);
+        assert_eq!(Erc20::balance_of(token_id, BOB), 300);
+
+        assert_eq!(info.logs.len(), 1);
+        assert_eq!(info.logs[0].address, Precompiles::token_address(token_id));
+        assert_eq!(
+            info.logs[0].topics,
+            vec![
+                H256(keccak_256(TRANSFER_SIGNATURE)),
+                address_of(ALICE).into(),
+                address_of(BOB).into()
+            ]
+        );
+        assert_eq!(info.logs[0].data, word(300u64).to_vec());
+    });
+}
+
+#[test]
+fn transfer_fees_get_their_own_log() {
+    new_test_ext().execute_with(|| {
+        let token_id = create_token();
+        assert_ok!(Erc20::set_transfer_fee(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            Some((Permill::from_percent(10), TREASURY))
+        ));
+
+        let info = call(ALICE, token_id, selector::TRANSFER, &[address_word(BOB), word(300u64)]);
+        assert_eq!(info.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+
+        assert_eq!(Erc20::balance_of(token_id, BOB), 270);
+        assert_eq!(Erc20::balance_of(token_id, TREASURY), 30);
+
+        assert_eq!(info.logs.len(), 2);
+        assert_eq!(info.logs[0].topics[2], address_of(BOB).into());
+        assert_eq!(info.logs[0].data, word(270u64).to_vec());
+        assert_eq!(
+            info.logs[1].topics,
+            vec![
+                H256(keccak_256(TRANSFER_SIGNATURE)),
+                address_of(ALICE).into(),
+                address_of(TREASURY).into()
+            ]
+        );
+        assert_eq!(info.logs[1].data, word(30u64).to_vec());
+
+        let exempt = BoundedVec::try_from(vec![BOB]).unwrap();
+        assert_ok!(Erc20::add_fee_exemptions(RuntimeOrigin::signed(ALICE), token_id, exempt));
+
+        let info = call(BOB, token_id, selector::TRANSFER, &[address_word(DAVE), word(100u64)]);
+        assert_eq!(info.logs.len(), 1);
+        assert_eq!(info.logs[0].data, word(100u64).to_vec());
+    });
+}
+
+#[test]
+fn transfer_reverts_with_the_pallet_error() {
+    new_test_ext().execute_with(|| {
+        let token_id = create_token();
+
+        assert_reverted(
+            call(BOB, token_id, selector::TRANSFER, &[address_word(ALICE), word(1u64)]),
+            "InsufficientBalance",
+        );
+        assert_reverted(
+            call(ALICE, token_id, selector::TRANSFER, &[address_word(BOB), word(U256::MAX)]),
+            "amount out of bounds",
+        );
+        assert_reverted(
+            call(ALICE, token_id, selector::TRANSFER, &[address_word(BOB)]),
+            "input too short",
+        );
+        assert_reverted(call(ALICE, token_id, 0xdeadbeef, &[]), "unknown selector");
+
+        assert_eq!(Erc20::balance_of(token_id, ALICE), 1000);
+    });
+}
+
+#[test]
+fn approve_and_transfer_from() {
+    new_test_ext().execute_with(|| {
+        let token_id = create_token();
+
+        let info = call(ALICE, token_id, selector::APPROVE, &[address_word(BOB), word(200u64)]);
+        assert_eq!(info.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+        assert_eq!(info.logs[0].topics[0], H256(keccak_256(APPROVAL_SIGNATURE)));
+        assert_eq!(
+            query(token_id, selector::ALLOWANCE, &[address_word(ALICE), address_word(BOB)]),
+            U256::from(200u64)
+        );
+
+        let info = call(
+            BOB,
+            token_id,
+            selector::TRANSFER_FROM,
+            &[address_word(ALICE), address_word(DAVE), word(150u64)],
+        );
+        assert_eq!(info.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+        assert_eq!(
+            info.logs[0].topics,
+            vec![
+                H256(keccak_256(TRANSFER_SIGNATURE)),
+                address_of(ALICE).into(),
+                address_of(DAVE).into()
+            ]
+        );
+
+        assert_eq!(Erc20::balance_of(token_id, ALICE), 850);
+        assert_eq!(Erc20::balance_of(token_id, DAVE), 150);
+        assert_eq!(
+            query(token_id, selector::ALLOWANCE, &[address_word(ALICE), address_word(BOB)]),
+            U256::from(50u64)
+        );
+
+        assert_reverted(
+            call(
+                DAVE,
+                token_id,
+                selector::TRANSFER_FROM,
+                &[address_word(ALICE), address_word(DAVE), word(1u64)],
+            ),
+            "NotApproved",
+        );
+    });
+}
+
+#[test]
+fn rejects_malformed_addresses() {
+    new_test_ext().execute_with(|| {
+        let token_id = create_token();
+        let mut to = address_word(BOB);
+        to[0] = 1;
+
+        assert_reverted(
+            call(ALICE, token_id, selector::TRANSFER, &[to, word(1u64)]),
+            "invalid address",
+        );
+        assert_eq!(Erc20::balance_of(token_id, BOB), 0);
+    });
+}
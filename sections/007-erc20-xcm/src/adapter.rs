@@ -0,0 +1,74 @@
+//! The receiving side of a transfer: the XCM executor moves assets in and out of
+//! its holding register through these.
+use crate::{Config, ForeignTokens, Pallet};
+use core::marker::PhantomData;
+use frame_support::traits::ContainsPair;
+use xcm::latest::prelude::*;
+use xcm_executor::{
+    traits::{Convert, TransactAsset},
+    Assets,
+};
+
+/// Derivatives are burned when withdrawn and issued when deposited. Tokens minted on
+/// this chain are real balances instead: withdrawing one, usually out of the
+/// sender's sovereign account, transfers it into `pallet_erc20`'s account while it
+/// is in the holding register, and depositing transfers it back out. Both legs are
+/// `pallet_erc20` transfers, so the transfer hooks see them and the rate limit of
+/// the account withdrawn from applies. Like an escrow they pay no fee; the tokens
+/// paid one on their way out.
+pub struct Erc20Transactor<T>(PhantomData<T>);
+
+impl<T: Config> TransactAsset for Erc20Transactor<T> {
+    fn deposit_asset(what: &MultiAsset, who: &MultiLocation, _context: &XcmContext) -> XcmResult {
+        let (token_id, amount) = Pallet::<T>::match_asset(what).ok_or(XcmError::AssetNotFound)?;
+        let who = T::LocationToAccountId::convert_ref(who)
+            .map_err(|()| XcmError::FailedToTransactAsset("AccountIdConversionFailed"))?;
+
+        let deposited = if Pallet::<T>::is_derivative(token_id) {
+            pallet_erc20::Pallet::<T>::do_issue(token_id, &who, amount)
+        } else {
+            let holding = pallet_erc20::Pallet::<T>::account_id();
+
+            pallet_erc20::Pallet::<T>::_transfer(token_id, &holding, &who, amount).map(|_| ())
+        };
+
+        deposited.map_err(|e| XcmError::FailedToTransactAsset(e.into()))
+    }
+
+    fn withdraw_asset(
+        what: &MultiAsset,
+        who: &MultiLocation,
+        _maybe_context: Option<&XcmContext>,
+    ) -> Result<Assets, XcmError> {
+        let (token_id, amount) = Pallet::<T>::match_asset(what).ok_or(XcmError::AssetNotFound)?;
+        let who = T::LocationToAccountId::convert_ref(who)
+            .map_err(|()| XcmError::FailedToTransactAsset("AccountIdConversionFailed"))?;
+
+        let withdrawn = if Pallet::<T>::is_derivative(token_id) {
+            pallet_erc20::Pallet::<T>::do_burn(token_id, &who, amount)
+        } else {
+            let holding = pallet_erc20::Pallet::<T>::account_id();
+
+            pallet_erc20::Pallet::<T>::_transfer(token_id, &who, &holding, amount).map(|_| ())
+        };
+
+        withdrawn.map_err(|e| XcmError::FailedToTransactAsset(e.into()))?;
+
+        Ok(what.clone().into())
+    }
+}
+
+/// Trusts a chain as the reserve of the registered foreign tokens under its location.
+/// Use as the executor's `IsReserve`.
+pub struct RegisteredReserves<T>(PhantomData<T>);
+
+impl<T: Config> ContainsPair<MultiAsset, MultiLocation> for RegisteredReserves<T> {
+    fn contains(asset: &MultiAsset, origin: &MultiLocation) -> bool {
+        match &asset.id {
+            Concrete(location) => {
+                location.starts_with(origin) && ForeignTokens::<T>::contains_key(location)
+            },
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! Benchmarking setup for pallet-erc20-xcm
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Erc20Xcm;
+use frame_benchmarking::{v2::*, BenchmarkResult};
+use frame_support::{
+    traits::{EnsureOrigin, Get},
+    weights::Weight,
+    BoundedVec,
+};
+use frame_system::RawOrigin;
+use pallet_erc20::BenchmarkHelper;
+use sp_std::{boxed::Box, vec};
+use xcm::latest::prelude::*;
+use xcm_executor::traits::Convert;
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    #[benchmark]
+    fn register_foreign_token() -> Result<(), BenchmarkError> {
+        let origin = T::RegisterOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let location = MultiLocation::new(1, X3(Parachain(2), PalletInstance(1), GeneralIndex(1)));
+        let name = BoundedVec::try_from(vec![b'N'; T::MaxTokenNameLen::get() as usize]).unwrap();
+        let symbol =
+            BoundedVec::try_from(vec![b'S'; T::MaxTokenSymbolLen::get() as usize]).unwrap();
+
+        #[extrinsic_call]
+        register_foreign_token(origin as T::RuntimeOrigin, Box::new(location), name, symbol);
+
+        assert!(ForeignTokens::<T>::contains_key(location));
+
+        Ok(())
+    }
+
+    // Worst case: a derivative sent back to its reserve, which is a full
+    // `pallet_erc20` transfer with its hooks into the sovereign account, and a burn.
+    #[benchmark]
+    fn transfer_to_parachain() -> Result<(), BenchmarkError> {
+        let para_id = T::ReachableParaId::get().ok_or(BenchmarkError::Override(
+            BenchmarkResult::from_weight(Weight::MAX),
+        ))?;
+        let caller: T::AccountId = whitelisted_caller();
+        let name = BoundedVec::try_from(vec![b'N'; T::MaxTokenNameLen::get() as usize]).unwrap();
+        let symbol =
+            BoundedVec::try_from(vec![b'S'; T::MaxTokenSymbolLen::get() as usize]).unwrap();
+        let location =
+            MultiLocation::new(1, X3(Parachain(para_id), PalletInstance(1), GeneralIndex(1)));
+        let token_id =
+            pallet_erc20::Pallet::<T>::do_create(Erc20Xcm::<T>::account_id(), name, symbol)
+                .unwrap();
+        ForeignTokens::<T>::insert(location, token_id);
+        TokenLocations::<T>::insert(token_id, location);
+        pallet_erc20::Pallet::<T>::do_issue(token_id, &caller, 1_000_000u32.into()).unwrap();
+
+        let amount: T::TokenBalance = 100u32.into();
+        let beneficiary = MultiLocation::new(0, X1(AccountId32 { network: None, id: [0; 32] }));
+        let sovereign =
+            T::LocationToAccountId::convert_ref(MultiLocation::new(1, X1(Parachain(para_id))))
+                .unwrap();
+        <T as pallet_erc20::Config>::BenchmarkHelper::setup_transfer_hooks(
+            &token_id,
+            &caller,
+            &sovereign,
+        );
+
+        #[extrinsic_call]
+        transfer_to_parachain(
+            RawOrigin::Signed(caller.clone()),
+            token_id,
+            para_id,
+            Box::new(beneficiary),
+            amount,
+        );
+
+        assert_eq!(
+            pallet_erc20::BalanceOf::<T>::get(token_id, &caller),
+            (1_000_000u32 - 100u32).into()
+        );
+        assert_eq!(
+            pallet_erc20::Tokens::<T>::get(token_id).unwrap().supply,
+            (1_000_000u32 - 100u32).into()
+        );
+
+        Ok(())
+    }
+
+    impl_benchmark_test_suite!(
+        Erc20Xcm,
+        crate::mock::para_ext(1),
+        crate::mock::parachain::Runtime
+    );
+}
@@ -0,0 +1,269 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Moves `pallet-erc20` tokens between parachains with reserve backed XCM transfers.
+//!
+//! A token minted on this chain never leaves it: sending it parks the amount in the
+//! sovereign account of the destination and tells the destination to mint a
+//! derivative. Derivatives of tokens from other chains are burned when sent back,
+//! and their reserve pays out of the parked amount. Both are moved to the sovereign
+//! account with a `pallet_erc20` transfer first, so they pass the same checks as
+//! any other transfer. [`Erc20Transactor`] is the `TransactAsset` adapter that lets
+//! the XCM executor do the receiving side.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+pub use adapter::*;
+pub mod adapter;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub use weights::*;
+pub mod weights;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use crate::weights::WeightInfo;
+    use frame_support::{pallet_prelude::*, traits::PalletInfoAccess, PalletId};
+    use frame_system::pallet_prelude::*;
+    use sp_runtime::traits::AccountIdConversion;
+    use sp_std::{boxed::Box, vec};
+    use xcm::latest::prelude::*;
+    use xcm_executor::traits::Convert;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config + pallet_erc20::Config {
+        /// Because this pallet emits events, it depends on the runtime's definition of an event.
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        /// Delivers the transfer messages to other chains.
+        type XcmRouter: SendXcm;
+        /// Where this chain sits in the consensus universe, used to reanchor assets.
+        type UniversalLocation: Get<InteriorMultiLocation>;
+        /// Turns beneficiaries and sibling chains into local accounts.
+        type LocationToAccountId: Convert<MultiLocation, Self::AccountId>;
+        /// Maps token ids to the `GeneralIndex` of their location and back.
+        type TokenIdConvert: Convert<u128, Self::TokenId>;
+        /// May register derivatives of foreign tokens. Usually root.
+        type RegisterOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+        /// Owns every derivative, so nobody can issue them outside of XCM.
+        #[pallet::constant]
+        type PalletId: Get<PalletId>;
+        /// Type representing the weight of this pallet
+        type WeightInfo: WeightInfo;
+        /// A sibling the benchmarks can send to, or `None` to skip
+        /// `transfer_to_parachain`.
+        #[cfg(feature = "runtime-benchmarks")]
+        type ReachableParaId: Get<Option<u32>>;
+    }
+
+    /// The local derivative of each foreign token, by the location of the original.
+    #[pallet::storage]
+    #[pallet::getter(fn foreign_tokens)]
+    pub type ForeignTokens<T: Config> =
+        StorageMap<_, Blake2_128Concat, MultiLocation, T::TokenId, OptionQuery>;
+
+    /// The location of the original of each derivative, which is also its reserve.
+    #[pallet::storage]
+    #[pallet::getter(fn token_locations)]
+    pub type TokenLocations<T: Config> =
+        StorageMap<_, Twox64Concat, T::TokenId, MultiLocation, OptionQuery>;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        ForeignTokenRegistered {
+            token_id: T::TokenId,
+            location: MultiLocation,
+        },
+        /// `amount` is what was sent to the sibling, after any transfer fee.
+        TransferredToParachain {
+            token_id: T::TokenId,
+            from: T::AccountId,
+            para_id: u32,
+            beneficiary: MultiLocation,
+            amount: T::TokenBalance,
+        },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// A derivative is already registered for this location.
+        LocationTaken,
+        /// The location can't be used, e.g. it points into this chain.
+        BadLocation,
+        /// Derivatives can only be sent back to the chain of the original.
+        NotReserve,
+        /// The amount doesn't fit in an XCM asset.
+        AmountOverflow,
+        /// The message could not be sent.
+        SendFailure,
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Create the local derivative of the token at `location`, e.g.
+        /// `(1, X3(Parachain(id), PalletInstance(i), GeneralIndex(token_id)))`.
+        #[pallet::call_index(0)]
+        #[pallet::weight(<T as Config>::WeightInfo::register_foreign_token())]
+        pub fn register_foreign_token(
+            origin: OriginFor<T>,
+            location: Box<MultiLocation>,
+            name: BoundedVec<u8, T::MaxTokenNameLen>,
+            symbol: BoundedVec<u8, T::MaxTokenSymbolLen>,
+        ) -> DispatchResult {
+            T::RegisterOrigin::ensure_origin(origin)?;
+
+            let location = *location;
+
+            ensure!(location.parents > 0, Error::<T>::BadLocation);
+            ensure!(
+                !ForeignTokens::<T>::contains_key(location),
+                Error::<T>::LocationTaken
+            );
+
+            let token_id = pallet_erc20::Pallet::<T>::do_create(Self::account_id(), name, symbol)?;
+
+            ForeignTokens::<T>::insert(location, token_id);
+            TokenLocations::<T>::insert(token_id, location);
+
+            Self::deposit_event(Event::ForeignTokenRegistered { token_id, location });
+
+            Ok(())
+        }
+
+        /// Send `amount` to `beneficiary`, given relative to the sibling `para_id`.
+        #[pallet::call_index(1)]
+        #[pallet::weight(<T as Config>::WeightInfo::transfer_to_parachain())]
+        pub fn transfer_to_parachain(
+            _from: OriginFor<T>,
+            token_id: T::TokenId,
+            para_id: u32,
+            beneficiary: Box<MultiLocation>,
+            amount: T::TokenBalance,
+        ) -> DispatchResult {
+            let from = ensure_signed(_from)?;
+            let dest = MultiLocation::new(1, X1(Parachain(para_id)));
+
+            let maybe_reserve = TokenLocations::<T>::get(token_id);
+            match maybe_reserve {
+                Some(location) => ensure!(location.starts_with(&dest), Error::<T>::NotReserve),
+                None => ensure!(
+                    pallet_erc20::Tokens::<T>::contains_key(token_id),
+                    pallet_erc20::Error::<T>::NoneToken
+                ),
+            }
+
+            // Either way the tokens leave through an ordinary transfer to the sovereign
+            // account of `dest`, so hooks, fees and rate limits apply, and only what
+            // arrives is sent.
+            let sovereign =
+                T::LocationToAccountId::convert_ref(dest).map_err(|()| Error::<T>::BadLocation)?;
+            let sent = pallet_erc20::Pallet::<T>::_transfer(token_id, &from, &sovereign, amount)?;
+
+            let (location, into_holding): (_, fn(MultiAssets) -> Instruction<()>) =
+                match maybe_reserve {
+                    // burn the derivative, the reserve releases the original
+                    Some(location) => {
+                        pallet_erc20::Pallet::<T>::do_burn(token_id, &sovereign, sent)?;
+
+                        (location, WithdrawAsset)
+                    },
+                    // park the original here, backing the derivative minted there
+                    None => (Self::local_location(token_id)?, ReserveAssetDeposited),
+                };
+
+            let fungible: u128 = sent.try_into().map_err(|_| Error::<T>::AmountOverflow)?;
+            let asset = MultiAsset::from((location, fungible))
+                .reanchored(&dest, T::UniversalLocation::get())
+                .map_err(|()| Error::<T>::BadLocation)?;
+
+            let message = Xcm(vec![
+                into_holding(asset.clone().into()),
+                ClearOrigin,
+                BuyExecution {
+                    fees: asset,
+                    weight_limit: Unlimited,
+                },
+                DepositAsset {
+                    assets: Wild(AllCounted(1)),
+                    beneficiary: *beneficiary,
+                },
+            ]);
+            send_xcm::<T::XcmRouter>(dest, message).map_err(|_| Error::<T>::SendFailure)?;
+
+            Self::deposit_event(Event::TransferredToParachain {
+                token_id,
+                from,
+                para_id,
+                beneficiary: *beneficiary,
+                amount: sent,
+            });
+
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// The owner of every derivative.
+        pub fn account_id() -> T::AccountId {
+            <T as Config>::PalletId::get().into_account_truncating()
+        }
+
+        /// The location of a token minted on this chain, relative to this chain.
+        pub fn local_location(token_id: T::TokenId) -> Result<MultiLocation, DispatchError> {
+            let index =
+                T::TokenIdConvert::reverse_ref(token_id).map_err(|()| Error::<T>::BadLocation)?;
+
+            Ok(MultiLocation::new(
+                0,
+                X2(PalletInstance(Self::erc20_index()), GeneralIndex(index)),
+            ))
+        }
+
+        /// Whether `token_id` is the derivative of a token on another chain.
+        pub fn is_derivative(token_id: T::TokenId) -> bool {
+            TokenLocations::<T>::contains_key(token_id)
+        }
+
+        /// The token and amount `asset` stands for, if it is a token minted on this
+        /// chain or a registered derivative.
+        pub fn match_asset(asset: &MultiAsset) -> Option<(T::TokenId, T::TokenBalance)> {
+            let (location, amount) = match asset {
+                MultiAsset {
+                    id: Concrete(location),
+                    fun: Fungible(amount),
+                } => (location, *amount),
+                _ => return None,
+            };
+
+            let token_id =
+                ForeignTokens::<T>::get(location).or_else(|| Self::local_token(location))?;
+
+            Some((token_id, amount.try_into().ok()?))
+        }
+
+        fn local_token(location: &MultiLocation) -> Option<T::TokenId> {
+            match location {
+                MultiLocation {
+                    parents: 0,
+                    interior: X2(PalletInstance(instance), GeneralIndex(index)),
+                } if *instance == Self::erc20_index() => T::TokenIdConvert::convert_ref(index)
+                    .ok()
+                    // derivatives are only known by the location of their original
+                    .filter(|token_id| !Self::is_derivative(*token_id)),
+                _ => None,
+            }
+        }
+
+        fn erc20_index() -> u8 {
+            <pallet_erc20::Pallet<T> as PalletInfoAccess>::index() as u8
+        }
+    }
+}
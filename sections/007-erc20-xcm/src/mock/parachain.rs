@@ -0,0 +1,332 @@
+//! A parachain with pallet-erc20 as its only asset pallet.
+use crate as pallet_erc20_xcm;
+use codec::{Decode, Encode};
+use frame_support::{
+    construct_runtime, parameter_types,
+    traits::{ConstU128, ConstU16, ConstU32, ConstU64, Everything, Nothing},
+    weights::Weight,
+    PalletId,
+};
+use frame_system::{EnsureRoot, EnsureSigned};
+use polkadot_core_primitives::BlockNumber as RelayBlockNumber;
+use polkadot_parachain::primitives::{
+    DmpMessageHandler, Id as ParaId, Sibling, XcmpMessageFormat, XcmpMessageHandler,
+};
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, ConvertInto, Hash, IdentityLookup},
+    AccountId32, DispatchError, DispatchResult,
+};
+use xcm::{latest::prelude::*, VersionedXcm};
+use xcm_builder::{
+    AccountId32Aliases, AllowTopLevelPaidExecutionFrom, FixedWeightBounds, ParentIsPreset,
+    SiblingParachainConvertsVia, SignedAccountId32AsNative, SovereignSignedViaLocation,
+};
+use xcm_executor::{
+    traits::{JustTry, WeightTrader},
+    Assets, XcmExecutor,
+};
+
+pub type AccountId = AccountId32;
+
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+construct_runtime!(
+    pub enum Runtime
+    {
+        System: frame_system,
+        MsgQueue: mock_msg_queue,
+        Erc20: pallet_erc20,
+        Erc20Xcm: pallet_erc20_xcm,
+    }
+);
+
+impl frame_system::Config for Runtime {
+    type BaseCallFilter = Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Nonce = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Block = Block;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ConstU16<42>;
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+    pub const RelayNetwork: NetworkId = NetworkId::Kusama;
+    pub UniversalLocation: InteriorMultiLocation =
+        X2(GlobalConsensus(RelayNetwork::get()), Parachain(MsgQueue::parachain_id().into()));
+    pub UnitWeightCost: Weight = Weight::from_parts(10, 10);
+    pub const MaxInstructions: u32 = 100;
+    pub const MaxAssetsIntoHolding: u32 = 64;
+    pub const Erc20PalletId: PalletId = PalletId(*b"py/erc20");
+    pub const Erc20XcmPalletId: PalletId = PalletId(*b"py/e20xc");
+    pub static BlockedSenders: Vec<AccountId> = vec![];
+}
+
+// Stands in for a compliance pallet, to check XCM transfers go through it.
+pub struct BlockSenders;
+impl pallet_erc20::TransferFilter<u64, AccountId, u128> for BlockSenders {
+    fn filter(
+        _token_id: &u64,
+        from: &AccountId,
+        _to: &AccountId,
+        _amount: &u128,
+    ) -> DispatchResult {
+        if BlockedSenders::get().contains(from) {
+            return Err(DispatchError::Other("sender blocked"));
+        }
+
+        Ok(())
+    }
+}
+
+pub type LocationToAccountId = (
+    ParentIsPreset<AccountId>,
+    SiblingParachainConvertsVia<Sibling, AccountId>,
+    AccountId32Aliases<RelayNetwork, AccountId>,
+);
+
+pub type XcmOriginToCallOrigin = (
+    SovereignSignedViaLocation<LocationToAccountId, RuntimeOrigin>,
+    SignedAccountId32AsNative<RelayNetwork, RuntimeOrigin>,
+);
+
+pub type XcmRouter = super::ParachainXcmRouter<MsgQueue>;
+
+// Execution is free; fees are not what these tests are about.
+pub struct FreeExecution;
+impl WeightTrader for FreeExecution {
+    fn new() -> Self {
+        FreeExecution
+    }
+
+    fn buy_weight(
+        &mut self,
+        _weight: Weight,
+        payment: Assets,
+        _context: &XcmContext,
+    ) -> Result<Assets, XcmError> {
+        Ok(payment)
+    }
+}
+
+pub struct XcmConfig;
+impl xcm_executor::Config for XcmConfig {
+    type RuntimeCall = RuntimeCall;
+    type XcmSender = XcmRouter;
+    type AssetTransactor = pallet_erc20_xcm::Erc20Transactor<Runtime>;
+    type OriginConverter = XcmOriginToCallOrigin;
+    type IsReserve = pallet_erc20_xcm::RegisteredReserves<Runtime>;
+    type IsTeleporter = ();
+    type UniversalLocation = UniversalLocation;
+    type Barrier = AllowTopLevelPaidExecutionFrom<Everything>;
+    type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
+    type Trader = FreeExecution;
+    type ResponseHandler = ();
+    type AssetTrap = ();
+    type AssetLocker = ();
+    type AssetExchanger = ();
+    type AssetClaims = ();
+    type SubscriptionService = ();
+    type PalletInstancesInfo = AllPalletsWithSystem;
+    type MaxAssetsIntoHolding = MaxAssetsIntoHolding;
+    type FeeManager = ();
+    type MessageExporter = ();
+    type UniversalAliases = Nothing;
+    type CallDispatcher = RuntimeCall;
+    type SafeCallFilter = Everything;
+}
+
+impl mock_msg_queue::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type XcmExecutor = XcmExecutor<XcmConfig>;
+}
+
+impl pallet_erc20::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type WeightInfo = ();
+    type TokenId = u64;
+    type TokenBalance = u128;
+    type MaxTokenNameLen = ConstU32<20>;
+    type MaxTokenSymbolLen = ConstU32<5>;
+    type MaxLength = ConstU32<20>;
+    type MaxVestingSchedules = ConstU32<3>;
+    type MinVestedTransfer = ConstU128<10>;
+    type BlockNumberToBalance = ConvertInto;
+    type RuntimeHoldReason = ();
+    type MaxHolds = ConstU32<2>;
+    type MaxSnapshots = ConstU32<10>;
+    type CheckpointRetention = ConstU64<100>;
+    type TransferFilter = BlockSenders;
+    type OnTransfer = ();
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = ();
+    type MaxAllowlistBatch = ConstU32<2>;
+    type MaxEmissionSchedules = ConstU32<4>;
+    type MaxEmissionsPerBlock = ConstU32<2>;
+    type PalletId = Erc20PalletId;
+    type MaxProofLength = ConstU32<8>;
+    type ForceOrigin = EnsureRoot<AccountId>;
+    type CreateOrigin = frame_support::traits::AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+    type MaxBatchTransfers = ConstU32<3>;
+    type MaxMemoLen = ConstU32<32>;
+    type MaxOutflowBuckets = ConstU32<10>;
+}
+
+// ParaB in the mock network.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct ReachableParaId;
+#[cfg(feature = "runtime-benchmarks")]
+impl frame_support::traits::Get<Option<u32>> for ReachableParaId {
+    fn get() -> Option<u32> {
+        Some(2)
+    }
+}
+
+impl pallet_erc20_xcm::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type XcmRouter = XcmRouter;
+    type UniversalLocation = UniversalLocation;
+    type LocationToAccountId = LocationToAccountId;
+    type TokenIdConvert = JustTry;
+    type RegisterOrigin = EnsureRoot<AccountId>;
+    type PalletId = Erc20XcmPalletId;
+    type WeightInfo = ();
+    #[cfg(feature = "runtime-benchmarks")]
+    type ReachableParaId = ReachableParaId;
+}
+
+/// Hands incoming XCMP and DMP messages straight to the executor, standing in for
+/// the message queues of a real parachain.
+#[frame_support::pallet]
+pub mod mock_msg_queue {
+    use super::*;
+    use frame_support::pallet_prelude::*;
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        type XcmExecutor: ExecuteXcm<Self::RuntimeCall>;
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {}
+
+    #[pallet::pallet]
+    #[pallet::without_storage_info]
+    pub struct Pallet<T>(_);
+
+    #[pallet::storage]
+    #[pallet::getter(fn parachain_id)]
+    pub(super) type ParachainId<T: Config> = StorageValue<_, ParaId, ValueQuery>;
+
+    impl<T: Config> Get<ParaId> for Pallet<T> {
+        fn get() -> ParaId {
+            Self::parachain_id()
+        }
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// Some XCM was executed ok.
+        Success(Option<T::Hash>),
+        /// Some XCM failed.
+        Fail(Option<T::Hash>, XcmError),
+        /// Bad XCM version used.
+        BadVersion(Option<T::Hash>),
+        /// Downward message executed with the given outcome.
+        ExecutedDownward([u8; 32], Outcome),
+    }
+
+    impl<T: Config> Pallet<T> {
+        pub fn set_para_id(para_id: ParaId) {
+            ParachainId::<T>::put(para_id);
+        }
+
+        fn handle_xcmp_message(
+            sender: ParaId,
+            xcm: VersionedXcm<T::RuntimeCall>,
+            max_weight: Weight,
+        ) -> Result<Weight, XcmError> {
+            let hash = Encode::using_encoded(&xcm, T::Hashing::hash);
+            let message_hash = Encode::using_encoded(&xcm, sp_io::hashing::blake2_256);
+
+            let (result, event) = match Xcm::<T::RuntimeCall>::try_from(xcm) {
+                Ok(xcm) => {
+                    let location = (Parent, Parachain(sender.into()));
+                    match T::XcmExecutor::execute_xcm(location, xcm, message_hash, max_weight) {
+                        Outcome::Error(e) => (Err(e.clone()), Event::Fail(Some(hash), e)),
+                        Outcome::Complete(w) => (Ok(w), Event::Success(Some(hash))),
+                        Outcome::Incomplete(w, e) => (Ok(w), Event::Fail(Some(hash), e)),
+                    }
+                },
+                Err(()) => (Err(XcmError::UnhandledXcmVersion), Event::BadVersion(Some(hash))),
+            };
+
+            Self::deposit_event(event);
+            result
+        }
+    }
+
+    impl<T: Config> XcmpMessageHandler for Pallet<T> {
+        fn handle_xcmp_messages<'a, I: Iterator<Item = (ParaId, RelayBlockNumber, &'a [u8])>>(
+            iter: I,
+            max_weight: Weight,
+        ) -> Weight {
+            for (sender, _sent_at, data) in iter {
+                let mut data_ref = data;
+                let _ = XcmpMessageFormat::decode(&mut data_ref)
+                    .expect("Simulator encodes with versioned xcm format; qed");
+
+                let mut remaining_fragments = data_ref;
+                while !remaining_fragments.is_empty() {
+                    if let Ok(xcm) =
+                        VersionedXcm::<T::RuntimeCall>::decode(&mut remaining_fragments)
+                    {
+                        let _ = Self::handle_xcmp_message(sender, xcm, max_weight);
+                    } else {
+                        debug_assert!(false, "Invalid incoming XCMP message data");
+                    }
+                }
+            }
+            max_weight
+        }
+    }
+
+    impl<T: Config> DmpMessageHandler for Pallet<T> {
+        fn handle_dmp_messages(
+            iter: impl Iterator<Item = (RelayBlockNumber, Vec<u8>)>,
+            limit: Weight,
+        ) -> Weight {
+            for (_sent_at, data) in iter {
+                let id = sp_io::hashing::blake2_256(&data[..]);
+
+                if let Ok(xcm) = VersionedXcm::<T::RuntimeCall>::decode(&mut &data[..])
+                    .map_err(|_| ())
+                    .and_then(Xcm::try_from)
+                {
+                    let outcome = T::XcmExecutor::execute_xcm(Parent, xcm, id, limit);
+                    Self::deposit_event(Event::ExecutedDownward(id, outcome));
+                }
+            }
+            limit
+        }
+    }
+}
@@ -0,0 +1,357 @@
+use crate::{mock::*, Error, Event};
+use frame_support::{assert_noop, assert_ok, traits::PalletInfoAccess, BoundedVec};
+use pallet_erc20::RateLimit;
+use parachain::{Erc20, Erc20Xcm, RuntimeOrigin};
+use sp_runtime::DispatchError;
+use xcm::latest::prelude::*;
+use xcm_simulator::TestExt;
+
+fn mint(owner: parachain::AccountId, supply: u128) -> u64 {
+    let name = BoundedVec::try_from(b"MY_TOKEN".to_vec()).unwrap();
+    let symbol = BoundedVec::try_from(b"MTKN".to_vec()).unwrap();
+
+    assert_ok!(Erc20::mint(RuntimeOrigin::signed(owner), name, symbol, supply, None));
+    Erc20::last_token_id()
+}
+
+fn register(location: MultiLocation) -> u64 {
+    let name = BoundedVec::try_from(b"xMY_TOKEN".to_vec()).unwrap();
+    let symbol = BoundedVec::try_from(b"xMTKN".to_vec()).unwrap();
+
+    assert_ok!(Erc20Xcm::register_foreign_token(
+        RuntimeOrigin::root(),
+        Box::new(location),
+        name,
+        symbol
+    ));
+    Erc20::last_token_id()
+}
+
+/// Where token `token_id` of parachain `para_id` is, seen from a sibling.
+fn sibling_token(para_id: u32, token_id: u64) -> MultiLocation {
+    MultiLocation::new(
+        1,
+        X3(
+            Parachain(para_id),
+            PalletInstance(<Erc20 as PalletInfoAccess>::index() as u8),
+            GeneralIndex(token_id.into()),
+        ),
+    )
+}
+
+fn account(who: parachain::AccountId) -> Box<MultiLocation> {
+    Box::new(
+        AccountId32 {
+            network: None,
+            id: who.into(),
+        }
+        .into(),
+    )
+}
+
+fn supply(token_id: u64) -> u128 {
+    Erc20::tokens(token_id).unwrap().supply
+}
+
+#[test]
+fn tokens_round_trip_to_a_sibling() {
+    MockNet::reset();
+
+    let token_id = ParaA::execute_with(|| mint(ALICE, 1000));
+    let derivative = ParaB::execute_with(|| register(sibling_token(1, token_id)));
+
+    ParaA::execute_with(|| {
+        assert_ok!(Erc20Xcm::transfer_to_parachain(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            2,
+            account(BOB),
+            300
+        ));
+        parachain::System::assert_last_event(
+            Event::TransferredToParachain {
+                token_id,
+                from: ALICE,
+                para_id: 2,
+                beneficiary: *account(BOB),
+                amount: 300,
+            }
+            .into(),
+        );
+
+        // the original is parked, not burned
+        assert_eq!(Erc20::balance_of(token_id, ALICE), 700);
+        assert_eq!(Erc20::balance_of(token_id, sibling_account(2)), 300);
+        assert_eq!(supply(token_id), 1000);
+    });
+
+    ParaB::execute_with(|| {
+        assert_eq!(Erc20::balance_of(derivative, BOB), 300);
+        assert_eq!(supply(derivative), 300);
+
+        assert_ok!(Erc20Xcm::transfer_to_parachain(
+            RuntimeOrigin::signed(BOB),
+            derivative,
+            1,
+            account(ALICE),
+            100
+        ));
+
+        assert_eq!(Erc20::balance_of(derivative, BOB), 200);
+        assert_eq!(supply(derivative), 200);
+    });
+
+    ParaA::execute_with(|| {
+        assert_eq!(Erc20::balance_of(token_id, ALICE), 800);
+        assert_eq!(Erc20::balance_of(token_id, sibling_account(2)), 200);
+        // only passed through while in the holding register
+        assert_eq!(Erc20::balance_of(token_id, Erc20::account_id()), 0);
+        assert_eq!(supply(token_id), 1000);
+    });
+}
+
+#[test]
+fn derivatives_only_go_back_to_their_reserve() {
+    MockNet::reset();
+
+    let token_id = ParaA::execute_with(|| mint(ALICE, 1000));
+    let derivative = ParaB::execute_with(|| register(sibling_token(1, token_id)));
+
+    ParaA::execute_with(|| {
+        assert_ok!(Erc20Xcm::transfer_to_parachain(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            2,
+            account(BOB),
+            300
+        ));
+    });
+
+    ParaB::execute_with(|| {
+        assert_noop!(
+            Erc20Xcm::transfer_to_parachain(
+                RuntimeOrigin::signed(BOB),
+                derivative,
+                3,
+                account(ALICE),
+                100
+            ),
+            Error::<parachain::Runtime>::NotReserve
+        );
+        assert_noop!(
+            Erc20Xcm::transfer_to_parachain(
+                RuntimeOrigin::signed(BOB),
+                derivative,
+                1,
+                account(ALICE),
+                301
+            ),
+            pallet_erc20::Error::<parachain::Runtime>::InsufficientBalance
+        );
+    });
+}
+
+#[test]
+fn sending_derivatives_back_is_checked_like_a_transfer() {
+    MockNet::reset();
+
+    let token_id = ParaA::execute_with(|| mint(ALICE, 1000));
+    let derivative = ParaB::execute_with(|| register(sibling_token(1, token_id)));
+
+    ParaA::execute_with(|| {
+        assert_ok!(Erc20Xcm::transfer_to_parachain(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            2,
+            account(BOB),
+            300
+        ));
+    });
+
+    ParaB::execute_with(|| {
+        parachain::BlockedSenders::set(vec![BOB]);
+        assert_noop!(
+            Erc20Xcm::transfer_to_parachain(
+                RuntimeOrigin::signed(BOB),
+                derivative,
+                1,
+                account(ALICE),
+                100
+            ),
+            DispatchError::Other("sender blocked")
+        );
+        parachain::BlockedSenders::set(vec![]);
+
+        assert_ok!(Erc20::set_rate_limit(
+            RuntimeOrigin::signed(Erc20Xcm::account_id()),
+            derivative,
+            Some(RateLimit {
+                amount: 150,
+                window: 10
+            })
+        ));
+        assert_ok!(Erc20Xcm::transfer_to_parachain(
+            RuntimeOrigin::signed(BOB),
+            derivative,
+            1,
+            account(ALICE),
+            100
+        ));
+        assert_noop!(
+            Erc20Xcm::transfer_to_parachain(
+                RuntimeOrigin::signed(BOB),
+                derivative,
+                1,
+                account(ALICE),
+                100
+            ),
+            pallet_erc20::Error::<parachain::Runtime>::RateLimited
+        );
+
+        // burned, not left in the sovereign account
+        assert_eq!(Erc20::balance_of(derivative, sibling_account(1)), 0);
+        assert_eq!(supply(derivative), 200);
+    });
+}
+
+#[test]
+fn returning_tokens_leave_the_sovereign_account_by_transfer() {
+    MockNet::reset();
+
+    let token_id = ParaA::execute_with(|| mint(ALICE, 1000));
+    let derivative = ParaB::execute_with(|| register(sibling_token(1, token_id)));
+
+    ParaA::execute_with(|| {
+        assert_ok!(Erc20Xcm::transfer_to_parachain(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            2,
+            account(BOB),
+            300
+        ));
+
+        parachain::BlockedSenders::set(vec![sibling_account(2)]);
+    });
+
+    ParaB::execute_with(|| {
+        assert_ok!(Erc20Xcm::transfer_to_parachain(
+            RuntimeOrigin::signed(BOB),
+            derivative,
+            1,
+            account(ALICE),
+            100
+        ));
+    });
+
+    ParaA::execute_with(|| {
+        // the filter stopped the sovereign account from paying out
+        assert!(parachain::System::events().iter().any(|record| matches!(
+            record.event,
+            parachain::RuntimeEvent::MsgQueue(parachain::mock_msg_queue::Event::Fail(
+                _,
+                XcmError::FailedToTransactAsset(_)
+            ))
+        )));
+        assert_eq!(Erc20::balance_of(token_id, ALICE), 700);
+        assert_eq!(Erc20::balance_of(token_id, sibling_account(2)), 300);
+        assert_eq!(supply(token_id), 1000);
+    });
+}
+
+#[test]
+fn unregistered_tokens_are_rejected() {
+    MockNet::reset();
+
+    let token_id = ParaA::execute_with(|| mint(ALICE, 1000));
+
+    ParaA::execute_with(|| {
+        assert_ok!(Erc20Xcm::transfer_to_parachain(
+            RuntimeOrigin::signed(ALICE),
+            token_id,
+            2,
+            account(BOB),
+            300
+        ));
+    });
+
+    ParaB::execute_with(|| {
+        // ParaA is not a trusted reserve for a token ParaB never registered
+        assert!(parachain::System::events().iter().any(|record| matches!(
+            record.event,
+            parachain::RuntimeEvent::MsgQueue(parachain::mock_msg_queue::Event::Fail(
+                _,
+                XcmError::UntrustedReserveLocation
+            ))
+        )));
+        assert_eq!(Erc20::last_token_id(), 0);
+    });
+}
+
+#[test]
+fn only_existing_local_tokens_can_be_sent() {
+    MockNet::reset();
+
+    ParaA::execute_with(|| {
+        assert_noop!(
+            Erc20Xcm::transfer_to_parachain(
+                RuntimeOrigin::signed(ALICE),
+                1,
+                2,
+                account(BOB),
+                1
+            ),
+            pallet_erc20::Error::<parachain::Runtime>::NoneToken
+        );
+    });
+}
+
+#[test]
+fn registering_checks_origin_and_location() {
+    MockNet::reset();
+
+    ParaB::execute_with(|| {
+        let name: BoundedVec<_, _> = BoundedVec::try_from(b"xMY_TOKEN".to_vec()).unwrap();
+        let symbol: BoundedVec<_, _> = BoundedVec::try_from(b"xMTKN".to_vec()).unwrap();
+
+        assert_noop!(
+            Erc20Xcm::register_foreign_token(
+                RuntimeOrigin::signed(ALICE),
+                Box::new(sibling_token(1, 1)),
+                name.clone(),
+                symbol.clone()
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Erc20Xcm::register_foreign_token(
+                RuntimeOrigin::root(),
+                Box::new(MultiLocation::new(0, X1(GeneralIndex(1)))),
+                name.clone(),
+                symbol.clone()
+            ),
+            Error::<parachain::Runtime>::BadLocation
+        );
+
+        let derivative = register(sibling_token(1, 1));
+        parachain::System::assert_last_event(
+            Event::ForeignTokenRegistered {
+                token_id: derivative,
+                location: sibling_token(1, 1),
+            }
+            .into(),
+        );
+        // nobody but the pallet can issue derivatives
+        assert_eq!(Erc20::tokens(derivative).unwrap().owner, Erc20Xcm::account_id());
+        assert_eq!(supply(derivative), 0);
+
+        assert_noop!(
+            Erc20Xcm::register_foreign_token(
+                RuntimeOrigin::root(),
+                Box::new(sibling_token(1, 1)),
+                name,
+                symbol
+            ),
+            Error::<parachain::Runtime>::LocationTaken
+        );
+    });
+}
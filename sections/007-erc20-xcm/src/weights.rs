@@ -0,0 +1,66 @@
+//! Weights for pallet_erc20_xcm
+//!
+//! NOT YET GENERATED: these are estimates that stand in until the command below
+//! is run against a runtime. `transfer_to_parachain` is benchmarked sending a
+//! derivative back, a full `pallet_erc20` transfer into the sovereign account with
+//! the runtime's hooks followed by a burn.
+
+// Command:
+// ../../target/release/node-template
+// benchmark
+// pallet
+// --chain
+// dev
+// --pallet
+// pallet_erc20_xcm
+// --extrinsic
+// *
+// --steps=50
+// --repeat=20
+// --wasm-execution=compiled
+// --output
+// pallets/erc20-xcm/src/weights.rs
+// --template
+// ../../.maintain/frame-weight-template.hbs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_erc20_xcm.
+pub trait WeightInfo {
+	fn register_foreign_token() -> Weight;
+	fn transfer_to_parachain() -> Weight;
+}
+
+/// Weights for pallet_erc20_xcm using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn register_foreign_token() -> Weight {
+		Weight::from_parts(25_000_000, 16_000)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	fn transfer_to_parachain() -> Weight {
+		Weight::from_parts(110_000_000, 156_000)
+			.saturating_add(T::DbWeight::get().reads(39_u64))
+			.saturating_add(T::DbWeight::get().writes(29_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn register_foreign_token() -> Weight {
+		Weight::from_parts(25_000_000, 16_000)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	fn transfer_to_parachain() -> Weight {
+		Weight::from_parts(110_000_000, 156_000)
+			.saturating_add(RocksDbWeight::get().reads(39_u64))
+			.saturating_add(RocksDbWeight::get().writes(29_u64))
+	}
+}
@@ -0,0 +1,33 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Lets wallets list `pallet-erc20` tokens without scanning storage themselves.
+//!
+//! A runtime implements [`Erc20Api`] by forwarding to `pallet_erc20::Pallet::token_page`
+//! and `pallet_erc20::Pallet::portfolio`, with `TokenDetails` set to
+//! `pallet_erc20::TokenDetails<Runtime>`.
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    pub trait Erc20Api<AccountId, TokenId, TokenDetails, TokenBalance>
+    where
+        AccountId: Codec,
+        TokenId: Codec,
+        TokenDetails: Codec,
+        TokenBalance: Codec,
+    {
+        /// Up to `limit` tokens after `start_after`, each with its total supply.
+        fn tokens(
+            start_after: Option<TokenId>,
+            limit: u32,
+        ) -> Vec<(TokenId, TokenDetails, TokenBalance)>;
+
+        /// Up to `limit` of the tokens `account` holds after `start_after`, each
+        /// with the free balance of `account`.
+        fn portfolio(
+            account: AccountId,
+            start_after: Option<TokenId>,
+            limit: u32,
+        ) -> Vec<(TokenId, TokenDetails, TokenBalance)>;
+    }
+}